@@ -1,9 +1,15 @@
+use std::cell::RefCell;
+use std::mem::size_of;
+
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 use trees::{rbtree, bstree, avltree};
 use trees::base::Tree;
-use trees::rbtree::RBTree;
+use trees::rbtree::{RBTree, RBTreeNode};
 use trees::bstree::BSTree;
-use trees::avltree::AVLTree;
+use trees::avltree::{AVLTree, AVLTreeNode};
+use trees::arena_rbtree::ArenaRBTree;
+use trees::arena_avltree::ArenaAVLTree;
+use trees::frozen::FrozenTree;
 
 fn bench_rbtree(criterion: &mut Criterion) {
     let mut group = criterion.benchmark_group("rbtree_test");
@@ -25,7 +31,11 @@ fn bench_rbtree(criterion: &mut Criterion) {
 
 fn bench_bstree(criterion: &mut Criterion) {
     let mut group = criterion.benchmark_group("bstree_test");
-    for size in [10, 100, 1000, 5000].iter() {
+    // The ascending keys make a chain as deep as the tree is large, which
+    // no longer overflows; each iteration takes quadratic time, so fewer
+    // samples keep the run short.
+    group.sample_size(10);
+    for size in [10000, 40000, 70000, 100000].iter() {
         group.bench_with_input(BenchmarkId::from_parameter(size), size, |bench, &size| {
             bench.iter(|| {
                 let mut tree: BSTree<i32> = bstree::BSTree::new();
@@ -124,6 +134,134 @@ fn bench_rbtree_search(criterion: &mut Criterion) {
     }
     group.finish();
 }
+// Builds the tree of every size with both node layouts, the linked one
+// (one allocation per node, an Rc for red-black and a Box for AVL) and the
+// arena one (all nodes in one Vec).
+fn bench_arena_insertion(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("arena_test_insertion");
+    for size in [10000, 70000, 130000].iter() {
+        group.bench_with_input(BenchmarkId::new("rbtree", size), size, |bench, &size| {
+            bench.iter(|| {
+                let mut tree: RBTree<i32> = RBTree::new();
+                for index in 1..size {
+                    tree.insert(index);
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("arena_rbtree", size), size, |bench, &size| {
+            bench.iter(|| {
+                let mut tree: ArenaRBTree<i32> = ArenaRBTree::new();
+                for index in 1..size {
+                    tree.insert(index);
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("avltree", size), size, |bench, &size| {
+            bench.iter(|| {
+                let mut tree: AVLTree<i32> = AVLTree::new();
+                for index in 1..size {
+                    tree.insert(index);
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("arena_avltree", size), size, |bench, &size| {
+            bench.iter(|| {
+                let mut tree: ArenaAVLTree<i32> = ArenaAVLTree::new();
+                for index in 1..size {
+                    tree.insert(index);
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_arena_search(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("arena_test_search");
+    let mut rb_tree: RBTree<i32> = RBTree::new();
+    let mut arena_rb_tree: ArenaRBTree<i32> = ArenaRBTree::new();
+    let mut avl_tree: AVLTree<i32> = AVLTree::new();
+    let mut arena_avl_tree: ArenaAVLTree<i32> = ArenaAVLTree::new();
+    for index in 1..130000 {
+        rb_tree.insert(index);
+        arena_rb_tree.insert(index);
+        avl_tree.insert(index);
+        arena_avl_tree.insert(index);
+    }
+    let size: i32 = 130000;
+    group.bench_function("rbtree", |bench| bench.iter(|| (1..size).filter(|&index| rb_tree.contain(index)).count()));
+    group.bench_function("arena_rbtree", |bench| bench.iter(|| (1..size).filter(|&index| arena_rb_tree.contain(index)).count()));
+    group.bench_function("avltree", |bench| bench.iter(|| (1..size).filter(|&index| avl_tree.contain(index)).count()));
+    group.bench_function("arena_avltree", |bench| bench.iter(|| (1..size).filter(|&index| arena_avl_tree.contain(index)).count()));
+    group.finish();
+}
+
+// Looks keys up in a frozen copy against the trees it was made from. The
+// keys are probed in a scattered order, so that each search starts cold
+// below the first levels.
+fn bench_frozen_search(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("frozen_search");
+    let size: i32 = 130000;
+    let rb_tree: RBTree<i32> = RBTree::from_sorted_iter(0..size);
+    let avl_tree: AVLTree<i32> = AVLTree::from_sorted_iter(0..size);
+    let frozen: FrozenTree<i32> = avl_tree.freeze();
+    let probes: Vec<i32> = (0..size).map(|index| (index * 7919) % size).collect();
+    group.bench_function("rbtree", |bench| bench.iter(|| probes.iter().filter(|&&index| rb_tree.contain(index)).count()));
+    group.bench_function("avltree", |bench| bench.iter(|| probes.iter().filter(|&&index| avl_tree.contain(index)).count()));
+    group.bench_function("frozen", |bench| bench.iter(|| probes.iter().filter(|&&index| frozen.contain(index)).count()));
+    group.finish();
+}
+
+// Builds the trees from sorted keys, inserting them one by one against
+// building the balanced tree at once.
+fn bench_sorted_construction(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("sorted_construction");
+    for size in [10000, 70000, 130000].iter() {
+        group.bench_with_input(BenchmarkId::new("avltree_insert", size), size, |bench, &size| {
+            bench.iter(|| {
+                let mut tree: AVLTree<i32> = AVLTree::new();
+                for index in 1..size {
+                    tree.insert(index);
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("avltree_from_sorted_iter", size), size, |bench, &size| {
+            bench.iter(|| AVLTree::<i32>::from_sorted_iter(1..size))
+        });
+        group.bench_with_input(BenchmarkId::new("rbtree_insert", size), size, |bench, &size| {
+            bench.iter(|| {
+                let mut tree: RBTree<i32> = RBTree::new();
+                for index in 1..size {
+                    tree.insert(index);
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("rbtree_from_sorted_iter", size), size, |bench, &size| {
+            bench.iter(|| RBTree::<i32>::from_sorted_iter(1..size))
+        });
+        group.bench_with_input(BenchmarkId::new("bstree_from_sorted_iter", size), size, |bench, &size| {
+            bench.iter(|| BSTree::<i32>::from_sorted_iter(1..size))
+        });
+    }
+    group.finish();
+}
+
+// Prints the memory taken by one node with each layout. A linked node is an
+// Rc allocation: two reference counts and the RefCell around the node.
+fn report_arena_memory(_criterion: &mut Criterion) {
+    // A red-black node lives in an Rc allocation, behind its strong and weak
+    // counts; an AVL node is a plain Box allocation.
+    let rc_bytes = |node_bytes: usize| 2 * size_of::<usize>() + node_bytes;
+    println!("bytes per node of i32 keys:");
+    println!("  rbtree        {:>3}", rc_bytes(size_of::<RefCell<RBTreeNode<i32>>>()));
+    println!("  arena_rbtree  {:>3}", ArenaRBTree::<i32>::node_bytes());
+    println!("  avltree       {:>3}", size_of::<AVLTreeNode<i32>>());
+    println!("  arena_avltree {:>3}", ArenaAVLTree::<i32>::node_bytes());
+}
+
 //criterion_group!(benches, bench_rbtree_search, bench_avl_tree_search);
 criterion_group!(benches, bench_rbtree_insertion, bench_avl_tree_insertion, bench_rbtree_search, bench_avl_tree_search, bench_bstree, bench_rbtree, bench_avl_tree);
-criterion_main!(benches);
+criterion_group!(arena_benches, report_arena_memory, bench_arena_insertion, bench_arena_search);
+criterion_group!(construction_benches, bench_sorted_construction);
+criterion_group!(frozen_benches, bench_frozen_search);
+criterion_main!(benches, arena_benches, construction_benches, frozen_benches);
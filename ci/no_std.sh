@@ -0,0 +1,13 @@
+#!/bin/sh
+# Builds the library without the std feature, for the host and for a target
+# that has no std at all, so that a use of std in the library fails the check.
+# The tests cannot show it: they import std whichever way the library is built.
+set -e
+cd "$(dirname "$0")/.."
+
+rustup target add thumbv7em-none-eabihf
+
+cargo build --no-default-features --lib
+cargo build --no-default-features --lib --target thumbv7em-none-eabihf
+cargo build --no-default-features --features stats --lib --target thumbv7em-none-eabihf
+cargo test --no-default-features
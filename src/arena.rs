@@ -0,0 +1,193 @@
+use core::cmp::Ordering;
+use core::fmt::Debug;
+use core::marker::PhantomData;
+use core::mem::size_of;
+use core::ops::{Index, IndexMut};
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::base::{self, NodeRef};
+use crate::svg::NodeStyle;
+
+/// The index standing for a missing node, like `None` in the linked trees.
+pub const NIL: u32 = u32::MAX;
+
+/// A node stored in an `Arena`, its children being indices in the same arena.
+pub trait ArenaNode<T: Ord + Clone + Debug>: Sized {
+    fn key(&self) -> &T;
+
+    fn left(&self) -> u32;
+
+    fn right(&self) -> u32;
+
+    /// How this node is drawn by `Tree::to_svg`, arena holding its children.
+    fn svg_style(&self, _arena: &Arena<Self>) -> NodeStyle {
+        NodeStyle::default()
+    }
+
+    /// Extra fields written next to the key by `Tree::to_json`.
+    fn json_attributes(&self) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
+
+    /// The text following the key in `Tree::render`.
+    fn render_suffix(&self) -> String {
+        String::new()
+    }
+}
+
+#[derive(Debug)]
+enum Slot<N> {
+    Occupied(N),
+    // The index of the next free slot, or NIL.
+    Vacant(u32),
+}
+
+/// The nodes of one tree, stored side by side in a single `Vec`.
+///
+/// A removed node leaves a vacant slot that the next allocation reuses,
+/// the vacant slots being chained into a free list, so a tree that keeps
+/// its size does not allocate any more.
+#[derive(Debug)]
+pub struct Arena<N> {
+    slots: Vec<Slot<N>>,
+    free: u32,
+    len: usize,
+}
+
+impl<N> Default for Arena<N> {
+    fn default() -> Self {
+        Arena { slots: Vec::new(), free: NIL, len: 0 }
+    }
+}
+
+impl<N> Arena<N> {
+    /// Stores the node and returns its index.
+    pub fn allocate(&mut self, node: N) -> u32 {
+        self.len += 1;
+        if self.free == NIL {
+            self.slots.push(Slot::Occupied(node));
+            return (self.slots.len() - 1) as u32;
+        }
+        let index: u32 = self.free;
+        match core::mem::replace(&mut self.slots[index as usize], Slot::Occupied(node)) {
+            Slot::Vacant(next) => self.free = next,
+            Slot::Occupied(_) => unreachable!("the free list only holds vacant slots"),
+        }
+        index
+    }
+
+    /// Takes the node out of the arena, its slot going to the free list.
+    pub fn remove(&mut self, index: u32) -> N {
+        match core::mem::replace(&mut self.slots[index as usize], Slot::Vacant(self.free)) {
+            Slot::Occupied(node) => {
+                self.free = index;
+                self.len -= 1;
+                node
+            }
+            Slot::Vacant(_) => panic!("the slot {} is already free", index),
+        }
+    }
+
+    /// The number of nodes in the arena.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Removes every node, keeping the memory for the next ones.
+    pub fn clear(&mut self) {
+        self.slots.clear();
+        self.free = NIL;
+        self.len = 0;
+    }
+
+    /// The size of one slot, that is the memory taken by one node.
+    pub fn slot_bytes() -> usize {
+        size_of::<Slot<N>>()
+    }
+
+    /// The memory held by the arena, vacant and spare slots included.
+    pub fn allocated_bytes(&self) -> usize {
+        self.slots.capacity() * Self::slot_bytes()
+    }
+}
+
+impl<N> Index<u32> for Arena<N> {
+    type Output = N;
+
+    fn index(&self, index: u32) -> &N {
+        match &self.slots[index as usize] {
+            Slot::Occupied(node) => node,
+            Slot::Vacant(_) => panic!("the slot {} is free", index),
+        }
+    }
+}
+
+impl<N> IndexMut<u32> for Arena<N> {
+    fn index_mut(&mut self, index: u32) -> &mut N {
+        match &mut self.slots[index as usize] {
+            Slot::Occupied(node) => node,
+            Slot::Vacant(_) => panic!("the slot {} is free", index),
+        }
+    }
+}
+
+/// A node of an arena tree, as seen by the algorithms of `NodeRef`.
+pub struct ArenaRef<'a, T, N> {
+    arena: &'a Arena<N>,
+    index: u32,
+    key: PhantomData<T>,
+}
+
+impl<'a, T, N> ArenaRef<'a, T, N> {
+    /// The node at index, or None for NIL.
+    pub fn new(arena: &'a Arena<N>, index: u32) -> Option<Self> {
+        match index {
+            NIL => None,
+            _ => Some(ArenaRef { arena, index, key: PhantomData }),
+        }
+    }
+
+    pub fn node(&self) -> &'a N {
+        &self.arena[self.index]
+    }
+}
+
+impl<'a, T: Ord + Clone + Debug, N: ArenaNode<T>> NodeRef<T> for ArenaRef<'a, T, N> {
+    fn key(&self) -> T {
+        self.node().key().clone()
+    }
+
+    fn left(&self) -> Option<Self> {
+        Self::new(self.arena, self.node().left())
+    }
+
+    fn right(&self) -> Option<Self> {
+        Self::new(self.arena, self.node().right())
+    }
+
+    fn compare(&self, value: &T) -> Ordering {
+        self.node().key().cmp(value)
+    }
+
+    fn svg_style(&self) -> NodeStyle {
+        self.node().svg_style(self.arena)
+    }
+
+    fn json_attributes(&self) -> Vec<(&'static str, String)> {
+        self.node().json_attributes()
+    }
+}
+
+/// Renders the tree rooted at root in the format of `Tree::render`.
+pub fn render<T, N>(arena: &Arena<N>, root: u32) -> String
+where
+    T: Ord + Clone + Debug,
+    N: ArenaNode<T>,
+{
+    base::render_tree(ArenaRef::new(arena, root), &|node: &ArenaRef<T, N>| node.node().render_suffix())
+}
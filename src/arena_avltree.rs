@@ -0,0 +1,333 @@
+use core::cmp::{max, Ordering};
+use core::fmt::Debug;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+
+use crate::arena::{self, Arena, ArenaNode, ArenaRef, NIL};
+use crate::avltree;
+use crate::base::Tree;
+use crate::json::{self, FromJson, JsonKey};
+use crate::observer::{NoopObserver, RotationDirection, TreeEvent, TreeObserver};
+use crate::svg::NodeStyle;
+
+/// A node of an ArenaAVLTree, its children being indices in the arena.
+#[derive(Debug)]
+pub struct ArenaAVLNode<T: Ord + Clone + Debug> {
+    key: T,
+    left: u32,
+    right: u32,
+    height: u32,
+}
+
+impl<T: Ord + Clone + Debug> ArenaNode<T> for ArenaAVLNode<T> {
+    fn key(&self) -> &T {
+        &self.key
+    }
+
+    fn left(&self) -> u32 {
+        self.left
+    }
+
+    fn right(&self) -> u32 {
+        self.right
+    }
+
+    fn json_attributes(&self) -> Vec<(&'static str, String)> {
+        vec![("height", self.height.to_string())]
+    }
+
+    fn svg_style(&self, arena: &Arena<Self>) -> NodeStyle {
+        let height = |index: u32| if index == NIL { 0 } else { arena[index].height as i64 };
+        NodeStyle {
+            annotation: Some(format!("h={} bf={}", self.height, height(self.left) - height(self.right))),
+            ..NodeStyle::default()
+        }
+    }
+}
+
+/// An AVL tree keeping its nodes in an `Arena` instead of one allocation each.
+///
+/// It balances exactly like `AVLTree`, so the same operations give the same
+/// shape, but its nodes are smaller and stored side by side, which makes it
+/// faster to build and to search.
+///
+/// # Examples
+/// ```
+/// use trees::arena_avltree::ArenaAVLTree;
+/// use trees::avltree::AVLTree;
+/// use trees::base::Tree;
+///
+/// let mut arena_tree = ArenaAVLTree::new();
+/// let mut tree = AVLTree::new();
+/// for i in 1..8 {
+///     arena_tree.insert(i);
+///     tree.insert(i);
+/// }
+/// arena_tree.delete(4);
+/// tree.delete(4);
+/// assert_eq!(arena_tree.render(), tree.render());
+/// assert_eq!(arena_tree.to_json(), tree.to_json());
+/// assert_eq!(arena_tree.to_svg(), tree.to_svg());
+/// ```
+#[derive(Debug)]
+pub struct ArenaAVLTree<T: Ord + Clone + Debug, O: TreeObserver<T> = NoopObserver> {
+    nodes: Arena<ArenaAVLNode<T>>,
+    root: u32,
+    observer: O,
+}
+
+impl<T: Ord + Clone + Debug> ArenaAVLTree<T> {
+    pub fn new() -> Self {
+        Self::with_observer(NoopObserver)
+    }
+}
+
+impl<T: Ord + Clone + Debug, O: TreeObserver<T> + Default> Default for ArenaAVLTree<T, O> {
+    fn default() -> Self {
+        Self::with_observer(O::default())
+    }
+}
+
+impl<T: Ord + Clone + Debug + JsonKey, O: TreeObserver<T> + Default> FromJson for ArenaAVLTree<T, O> {
+    /// Rebuilds the tree node for node, rejecting JSON that `AVLTree::from_json`
+    /// rejects: unbalanced nodes and wrong heights.
+    fn from_json(text: &str) -> Result<Self, String> {
+        let mut tree = Self::default();
+        if let Some(root) = json::parse_tree::<T>(text)? {
+            tree.root = json::fold(&root, |shape, left: Option<u32>, right: Option<u32>| {
+                let (left, right) = (left.unwrap_or(NIL), right.unwrap_or(NIL));
+                let height: u32 = avltree::check_json_height(shape, tree.height(left), tree.height(right))?;
+                Ok(tree.nodes.allocate(ArenaAVLNode { key: shape.key.clone(), left, right, height }))
+            })?;
+        }
+        Ok(tree)
+    }
+}
+
+impl<T: Ord + Clone + Debug, O: TreeObserver<T>> ArenaAVLTree<T, O> {
+    /// Makes a new empty ArenaAVLTree reporting its rotations to the observer.
+    pub fn with_observer(observer: O) -> Self {
+        Self { nodes: Arena::default(), root: NIL, observer }
+    }
+
+    /// The observer of the tree.
+    pub fn observer(&self) -> &O {
+        &self.observer
+    }
+
+    /// The observer of the tree, mutably.
+    pub fn observer_mut(&mut self) -> &mut O {
+        &mut self.observer
+    }
+
+    /// The memory held by the nodes of the tree, spare slots included.
+    ///
+    /// # Examples
+    /// ```
+    /// use trees::arena_avltree::ArenaAVLTree;
+    /// use trees::base::Tree;
+    ///
+    /// let mut tree = ArenaAVLTree::new();
+    /// for i in 0..100 {
+    ///     tree.insert(i);
+    /// }
+    /// assert!(tree.allocated_bytes() >= 100 * ArenaAVLTree::<i32>::node_bytes());
+    /// ```
+    pub fn allocated_bytes(&self) -> usize {
+        self.nodes.allocated_bytes()
+    }
+
+    /// The memory taken by one node.
+    pub fn node_bytes() -> usize {
+        Arena::<ArenaAVLNode<T>>::slot_bytes()
+    }
+
+    /// Clear the ArenaAVLTree, removing all elements.
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.root = NIL;
+    }
+
+    fn height(&self, index: u32) -> u32 {
+        match index {
+            NIL => 0,
+            _ => self.nodes[index].height,
+        }
+    }
+
+    fn update_height(&mut self, index: u32) {
+        let node: &ArenaAVLNode<T> = &self.nodes[index];
+        let height: u32 = max(self.height(node.left), self.height(node.right)) + 1;
+        self.nodes[index].height = height;
+    }
+
+    fn balance_factor(&self, index: u32) -> i64 {
+        let node: &ArenaAVLNode<T> = &self.nodes[index];
+        self.height(node.left) as i64 - self.height(node.right) as i64
+    }
+
+    fn left_rotate(&mut self, root: u32) -> u32 {
+        let pivot: T = self.nodes[root].key.clone();
+        self.observer.on_event(&TreeEvent::Rotated { pivot, direction: RotationDirection::Left });
+        let new_root: u32 = self.nodes[root].right;
+        self.nodes[root].right = self.nodes[new_root].left;
+        self.update_height(root);
+        self.nodes[new_root].left = root;
+        self.update_height(new_root);
+        new_root
+    }
+
+    fn right_rotate(&mut self, root: u32) -> u32 {
+        let pivot: T = self.nodes[root].key.clone();
+        self.observer.on_event(&TreeEvent::Rotated { pivot, direction: RotationDirection::Right });
+        let new_root: u32 = self.nodes[root].left;
+        self.nodes[root].left = self.nodes[new_root].right;
+        self.update_height(root);
+        self.nodes[new_root].right = root;
+        self.update_height(new_root);
+        new_root
+    }
+
+    fn left_right_rotate(&mut self, root: u32) -> u32 {
+        self.nodes[root].left = self.left_rotate(self.nodes[root].left);
+        self.right_rotate(root)
+    }
+
+    fn right_left_rotate(&mut self, root: u32) -> u32 {
+        self.nodes[root].right = self.right_rotate(self.nodes[root].right);
+        self.left_rotate(root)
+    }
+
+    // Restores the balance of the subtree rooted at index after one of its
+    // subtrees changed height, choosing the rotation from the heights of the
+    // grandchildren. Returns the new root of the subtree.
+    fn rebalance(&mut self, index: u32) -> u32 {
+        let balance_factor: i64 = self.balance_factor(index);
+        let new_root: u32 = match balance_factor {
+            2 => {
+                let left: u32 = self.nodes[index].left;
+                if self.balance_factor(left) >= 0 {
+                    self.right_rotate(index)
+                } else {
+                    self.left_right_rotate(index)
+                }
+            }
+            -2 => {
+                let right: u32 = self.nodes[index].right;
+                if self.balance_factor(right) <= 0 {
+                    self.left_rotate(index)
+                } else {
+                    self.right_left_rotate(index)
+                }
+            }
+            _ => index,
+        };
+        self.update_height(new_root);
+        if balance_factor.abs() == 2 {
+            let key: T = self.nodes[index].key.clone();
+            self.observer.on_event(&TreeEvent::Rebalanced { key });
+        }
+        new_root
+    }
+
+    fn insert_at(&mut self, index: u32, data: T) -> u32 {
+        if index == NIL {
+            self.observer.on_event(&TreeEvent::Inserted { key: data.clone() });
+            return self.nodes.allocate(ArenaAVLNode { key: data, left: NIL, right: NIL, height: 1 });
+        }
+        match data.cmp(&self.nodes[index].key) {
+            Ordering::Less => {
+                let left: u32 = self.insert_at(self.nodes[index].left, data);
+                self.nodes[index].left = left;
+            }
+            Ordering::Greater => {
+                let right: u32 = self.insert_at(self.nodes[index].right, data);
+                self.nodes[index].right = right;
+            }
+            Ordering::Equal => {}
+        }
+        self.rebalance(index)
+    }
+
+    fn min_key(&self, mut index: u32) -> T {
+        while self.nodes[index].left != NIL {
+            index = self.nodes[index].left;
+        }
+        self.nodes[index].key.clone()
+    }
+
+    fn delete_at(&mut self, index: u32, data: &T) -> u32 {
+        if index == NIL {
+            return NIL;
+        }
+        match self.nodes[index].key.cmp(data) {
+            Ordering::Greater => {
+                let left: u32 = self.delete_at(self.nodes[index].left, data);
+                self.nodes[index].left = left;
+            }
+            Ordering::Less => {
+                let right: u32 = self.delete_at(self.nodes[index].right, data);
+                self.nodes[index].right = right;
+            }
+            Ordering::Equal => {
+                let (left, right) = (self.nodes[index].left, self.nodes[index].right);
+                if left != NIL && right != NIL {
+                    // Like AVLTree, take the key of the successor and delete the successor.
+                    let min_key: T = self.min_key(right);
+                    let right: u32 = self.delete_at(right, &min_key);
+                    self.nodes[index].key = min_key;
+                    self.nodes[index].right = right;
+                } else {
+                    self.nodes.remove(index);
+                    return if left != NIL { left } else { right };
+                }
+            }
+        }
+        self.rebalance(index)
+    }
+}
+
+impl<T: Ord + Clone + Debug, O: TreeObserver<T>> Tree<T> for ArenaAVLTree<T, O> {
+    type Node<'a> = ArenaRef<'a, T, ArenaAVLNode<T>> where Self: 'a;
+
+    fn get_root(&self) -> Option<Self::Node<'_>> {
+        ArenaRef::new(&self.nodes, self.root)
+    }
+
+    fn insert(&mut self, data: T) {
+        if self.contain(data.clone()) {
+            #[cfg(feature = "std")]
+            println!("This node already exists in the tree!");
+        } else {
+            self.root = self.insert_at(self.root, data);
+        }
+    }
+
+    fn delete(&mut self, data: T) {
+        if !self.contain(data.clone()) {
+            #[cfg(feature = "std")]
+            println!("This node does not exist in the tree!");
+        } else {
+            self.observer.on_event(&TreeEvent::Deleted { key: data.clone() });
+            self.root = self.delete_at(self.root, &data);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn print_tree(&self) {
+        print!("{}", self.render());
+    }
+
+    fn render(&self) -> String {
+        arena::render(&self.nodes, self.root)
+    }
+
+    fn count_nodes(&self) -> u32 {
+        self.nodes.len() as u32
+    }
+
+    fn get_height(&self) -> u32 {
+        self.height(self.root)
+    }
+}
@@ -0,0 +1,464 @@
+use core::cmp::Ordering;
+use core::fmt::Debug;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::vec;
+
+use crate::arena::{self, Arena, ArenaNode, ArenaRef, NIL};
+use crate::base::Tree;
+use crate::json::{self, FromJson, JsonKey};
+use crate::observer::{NoopObserver, RotationDirection, TreeEvent, TreeObserver};
+use crate::rbtree::{self, NodeColor};
+use crate::svg::NodeStyle;
+
+/// A node of an ArenaRBTree, its parent and children being indices in the arena.
+#[derive(Debug)]
+pub struct ArenaRBNode<T: Ord + Clone + Debug> {
+    key: T,
+    color: NodeColor,
+    parent: u32,
+    left: u32,
+    right: u32,
+}
+
+impl<T: Ord + Clone + Debug> ArenaNode<T> for ArenaRBNode<T> {
+    fn key(&self) -> &T {
+        &self.key
+    }
+
+    fn left(&self) -> u32 {
+        self.left
+    }
+
+    fn right(&self) -> u32 {
+        self.right
+    }
+
+    fn json_attributes(&self) -> Vec<(&'static str, String)> {
+        let color = if self.color == NodeColor::Black { "\"black\"" } else { "\"red\"" };
+        vec![("color", color.to_string())]
+    }
+
+    fn svg_style(&self, _arena: &Arena<Self>) -> NodeStyle {
+        match self.color {
+            NodeColor::Red => NodeStyle { fill: "#cc2222", stroke: "#7a0000", text_color: "#ffffff", annotation: None },
+            NodeColor::Black => NodeStyle { fill: "#222222", stroke: "#000000", text_color: "#ffffff", annotation: None },
+        }
+    }
+
+    fn render_suffix(&self) -> String {
+        if self.color == NodeColor::Black { " Black".to_string() } else { " Red".to_string() }
+    }
+}
+
+/// A red black tree keeping its nodes in an `Arena` instead of one allocation each.
+///
+/// It applies the same insert and delete cases as `RBTree`, so the same
+/// operations give the same shape, but a node links to its parent with a
+/// plain index instead of a reference-counted pointer.
+///
+/// # Examples
+/// ```
+/// use trees::arena_rbtree::ArenaRBTree;
+/// use trees::rbtree::RBTree;
+/// use trees::base::Tree;
+///
+/// let mut arena_tree = ArenaRBTree::new();
+/// let mut tree = RBTree::new();
+/// for i in 1..8 {
+///     arena_tree.insert(i);
+///     tree.insert(i);
+/// }
+/// arena_tree.delete(2);
+/// tree.delete(2);
+/// assert_eq!(arena_tree.render(), tree.render());
+/// assert_eq!(arena_tree.to_json(), tree.to_json());
+/// ```
+#[derive(Debug)]
+pub struct ArenaRBTree<T: Ord + Clone + Debug, O: TreeObserver<T> = NoopObserver> {
+    nodes: Arena<ArenaRBNode<T>>,
+    root: u32,
+    observer: O,
+}
+
+impl<T: Ord + Clone + Debug> ArenaRBTree<T> {
+    pub fn new() -> Self {
+        Self::with_observer(NoopObserver)
+    }
+}
+
+impl<T: Ord + Clone + Debug, O: TreeObserver<T> + Default> Default for ArenaRBTree<T, O> {
+    fn default() -> Self {
+        Self::with_observer(O::default())
+    }
+}
+
+impl<T: Ord + Clone + Debug + JsonKey, O: TreeObserver<T> + Default> FromJson for ArenaRBTree<T, O> {
+    /// Rebuilds the tree node for node, colors included, after the checks of
+    /// `RBTree::from_json`.
+    fn from_json(text: &str) -> Result<Self, String> {
+        let mut tree = Self::default();
+        if let Some(root) = json::parse_tree::<T>(text)? {
+            rbtree::check_json_colors(&root)?;
+            tree.root = json::fold(&root, |shape, left: Option<u32>, right: Option<u32>| {
+                let color: NodeColor = rbtree::json_color(shape)?;
+                let (left, right) = (left.unwrap_or(NIL), right.unwrap_or(NIL));
+                let index: u32 = tree.nodes.allocate(ArenaRBNode { key: shape.key.clone(), color, parent: NIL, left, right });
+                for child in [left, right] {
+                    if child != NIL {
+                        tree.nodes[child].parent = index;
+                    }
+                }
+                Ok(index)
+            })?;
+        }
+        Ok(tree)
+    }
+}
+
+impl<T: Ord + Clone + Debug, O: TreeObserver<T>> ArenaRBTree<T, O> {
+    /// Makes a new empty ArenaRBTree reporting its rotations and recolorings to the observer.
+    pub fn with_observer(observer: O) -> Self {
+        Self { nodes: Arena::default(), root: NIL, observer }
+    }
+
+    /// The observer of the tree.
+    pub fn observer(&self) -> &O {
+        &self.observer
+    }
+
+    /// The observer of the tree, mutably.
+    pub fn observer_mut(&mut self) -> &mut O {
+        &mut self.observer
+    }
+
+    /// The memory held by the nodes of the tree, spare slots included.
+    pub fn allocated_bytes(&self) -> usize {
+        self.nodes.allocated_bytes()
+    }
+
+    /// The memory taken by one node.
+    pub fn node_bytes() -> usize {
+        Arena::<ArenaRBNode<T>>::slot_bytes()
+    }
+
+    /// Clear the ArenaRBTree, removing all elements.
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.root = NIL;
+    }
+
+    // A missing node is black.
+    fn color(&self, index: u32) -> NodeColor {
+        match index {
+            NIL => NodeColor::Black,
+            _ => self.nodes[index].color.clone(),
+        }
+    }
+
+    fn change_color(&mut self, index: u32, color: NodeColor) {
+        let from: NodeColor = self.nodes[index].color.clone();
+        if from != color {
+            self.nodes[index].color = color.clone();
+            let key: T = self.nodes[index].key.clone();
+            self.observer.on_event(&TreeEvent::Recolored { key, from, to: color });
+        }
+    }
+
+    fn is_left_child(&self, index: u32) -> bool {
+        self.nodes[self.nodes[index].parent].left == index
+    }
+
+    fn sibling(&self, index: u32) -> u32 {
+        let parent: &ArenaRBNode<T> = &self.nodes[self.nodes[index].parent];
+        if parent.left == index { parent.right } else { parent.left }
+    }
+
+    fn has_red_child(&self, index: u32) -> bool {
+        let node: &ArenaRBNode<T> = &self.nodes[index];
+        self.color(node.left) == NodeColor::Red || self.color(node.right) == NodeColor::Red
+    }
+
+    // Puts child where index was below the parent of index, or at the root.
+    fn replace_child(&mut self, index: u32, child: u32) {
+        let parent: u32 = self.nodes[index].parent;
+        if parent == NIL {
+            self.root = child;
+        } else if self.is_left_child(index) {
+            self.nodes[parent].left = child;
+        } else {
+            self.nodes[parent].right = child;
+        }
+        if child != NIL {
+            self.nodes[child].parent = parent;
+        }
+    }
+
+    fn left_rotate(&mut self, index: u32) {
+        let pivot: T = self.nodes[index].key.clone();
+        self.observer.on_event(&TreeEvent::Rotated { pivot, direction: RotationDirection::Left });
+        let right: u32 = self.nodes[index].right;
+        self.replace_child(index, right);
+        let inner: u32 = self.nodes[right].left;
+        self.nodes[index].right = inner;
+        if inner != NIL {
+            self.nodes[inner].parent = index;
+        }
+        self.nodes[right].left = index;
+        self.nodes[index].parent = right;
+    }
+
+    fn right_rotate(&mut self, index: u32) {
+        let pivot: T = self.nodes[index].key.clone();
+        self.observer.on_event(&TreeEvent::Rotated { pivot, direction: RotationDirection::Right });
+        let left: u32 = self.nodes[index].left;
+        self.replace_child(index, left);
+        let inner: u32 = self.nodes[left].right;
+        self.nodes[index].left = inner;
+        if inner != NIL {
+            self.nodes[inner].parent = index;
+        }
+        self.nodes[left].right = index;
+        self.nodes[index].parent = left;
+    }
+
+    // The node holding value, or the node below which value would be inserted.
+    fn search(&self, value: &T) -> (bool, u32) {
+        let (mut parent, mut child) = (NIL, self.root);
+        while child != NIL {
+            parent = child;
+            child = match self.nodes[child].key.cmp(value) {
+                Ordering::Greater => self.nodes[child].left,
+                Ordering::Less => self.nodes[child].right,
+                Ordering::Equal => return (true, child),
+            };
+        }
+        (false, parent)
+    }
+
+    fn insert_repair(&mut self, mut child: u32) {
+        loop {
+            let parent: u32 = self.nodes[child].parent;
+            if parent == NIL {
+                // 1. node is root
+                self.change_color(self.root, NodeColor::Black);
+                return;
+            }
+            if self.color(parent) == NodeColor::Black {
+                // 2. parent is black
+                return;
+            }
+            // 3. parent is red, so it is not the root and the grandparent exists
+            let grandparent: u32 = self.nodes[parent].parent;
+            let uncle: u32 = self.sibling(parent);
+            if self.color(uncle) == NodeColor::Red {
+                // 3.2.3 and 3.3.3 uncle is red
+                self.change_color(parent, NodeColor::Black);
+                self.change_color(uncle, NodeColor::Black);
+                self.change_color(grandparent, NodeColor::Red);
+                child = grandparent;
+                continue;
+            }
+            let mut parent: u32 = parent;
+            if self.is_left_child(parent) {
+                if !self.is_left_child(child) {
+                    // 3.2.2 child is right node
+                    self.left_rotate(parent);
+                    parent = self.nodes[grandparent].left;
+                }
+                // 3.2.1 child is left node
+                self.change_color(parent, NodeColor::Black);
+                self.change_color(grandparent, NodeColor::Red);
+                self.right_rotate(grandparent);
+            } else {
+                if self.is_left_child(child) {
+                    // 3.3.2 child is left child
+                    self.right_rotate(parent);
+                    parent = self.nodes[grandparent].right;
+                }
+                // 3.3.1 child is right child
+                self.change_color(parent, NodeColor::Black);
+                self.change_color(grandparent, NodeColor::Red);
+                self.left_rotate(grandparent);
+            }
+            let key: T = self.nodes[grandparent].key.clone();
+            self.observer.on_event(&TreeEvent::Rebalanced { key });
+            return;
+        }
+    }
+
+    // Like RBTree, a node with two children takes the key of its in-order
+    // predecessor, which is then removed instead.
+    fn replacement(&self, index: u32) -> u32 {
+        let node: &ArenaRBNode<T> = &self.nodes[index];
+        if node.left != NIL && node.right != NIL {
+            let mut replacement: u32 = node.left;
+            while self.nodes[replacement].right != NIL {
+                replacement = self.nodes[replacement].right;
+            }
+            replacement
+        } else if node.left != NIL {
+            node.left
+        } else {
+            node.right
+        }
+    }
+
+    fn delete_node(&mut self, index: u32) {
+        let replacement: u32 = self.replacement(index);
+        let parent: u32 = self.nodes[index].parent;
+        let double_black: bool = self.color(index) == NodeColor::Black && self.color(replacement) == NodeColor::Black;
+        if replacement == NIL {
+            // 1. node is leaf
+            if parent != NIL && double_black {
+                self.delete_repair(index);
+            }
+            self.replace_child(index, NIL);
+            self.nodes.remove(index);
+        } else if self.nodes[index].left == NIL || self.nodes[index].right == NIL {
+            // 2. node has one child
+            if parent == NIL {
+                // The root of a tree of two nodes takes the key of its red child.
+                let child: ArenaRBNode<T> = self.nodes.remove(replacement);
+                let root: &mut ArenaRBNode<T> = &mut self.nodes[index];
+                root.key = child.key;
+                root.left = NIL;
+                root.right = NIL;
+            } else {
+                self.replace_child(index, replacement);
+                self.nodes.remove(index);
+                if double_black {
+                    self.delete_repair(replacement);
+                } else {
+                    self.change_color(replacement, NodeColor::Black);
+                }
+            }
+        } else {
+            // 3. node has two children
+            self.nodes[index].key = self.nodes[replacement].key.clone();
+            self.delete_node(replacement);
+        }
+    }
+
+    fn delete_repair(&mut self, index: u32) {
+        let parent: u32 = self.nodes[index].parent;
+        if parent == NIL {
+            return;
+        }
+        let sibling: u32 = self.sibling(index);
+        if sibling == NIL {
+            // 1. no sibling
+            self.delete_repair(parent);
+        } else if self.color(sibling) == NodeColor::Black {
+            if !self.has_red_child(sibling) {
+                // 2.1 sibling has no red child
+                self.change_color(sibling, NodeColor::Red);
+                if self.color(parent) == NodeColor::Red {
+                    self.change_color(parent, NodeColor::Black);
+                } else {
+                    self.delete_repair(parent);
+                }
+                return;
+            }
+            let parent_color: NodeColor = self.color(parent);
+            let sibling_left: u32 = self.nodes[sibling].left;
+            let sibling_right: u32 = self.nodes[sibling].right;
+            let left_is_red: bool = self.color(sibling_left) == NodeColor::Red;
+            if !self.is_left_child(index) {
+                if left_is_red {
+                    // 2.2.1 ll
+                    self.change_color(sibling_left, NodeColor::Black);
+                    self.change_color(sibling, parent_color);
+                    self.right_rotate(parent);
+                } else {
+                    // 2.2.2 lr
+                    self.change_color(sibling_right, parent_color);
+                    self.left_rotate(sibling);
+                    self.right_rotate(parent);
+                }
+            } else if left_is_red {
+                // 2.2.3 rl
+                self.change_color(sibling_left, parent_color);
+                self.right_rotate(sibling);
+                self.left_rotate(parent);
+            } else {
+                // 2.2.4 rr
+                self.change_color(sibling_right, NodeColor::Black);
+                self.change_color(sibling, parent_color);
+                self.left_rotate(parent);
+            }
+            self.change_color(parent, NodeColor::Black);
+            let key: T = self.nodes[parent].key.clone();
+            self.observer.on_event(&TreeEvent::Rebalanced { key });
+        } else {
+            // 3. sibling is red
+            self.change_color(sibling, NodeColor::Black);
+            self.change_color(parent, NodeColor::Red);
+            if self.is_left_child(index) {
+                self.left_rotate(parent);
+            } else {
+                self.right_rotate(parent);
+            }
+            self.delete_repair(index);
+        }
+    }
+}
+
+impl<T: Ord + Clone + Debug, O: TreeObserver<T>> Tree<T> for ArenaRBTree<T, O> {
+    type Node<'a> = ArenaRef<'a, T, ArenaRBNode<T>> where Self: 'a;
+
+    fn get_root(&self) -> Option<Self::Node<'_>> {
+        ArenaRef::new(&self.nodes, self.root)
+    }
+
+    fn insert(&mut self, value: T) {
+        let (found, parent) = self.search(&value);
+        if found {
+            #[cfg(feature = "std")]
+            println!("The node already exists in the tree.");
+            return;
+        }
+        self.observer.on_event(&TreeEvent::Inserted { key: value.clone() });
+        if parent == NIL {
+            // 1. tree is empty
+            self.root = self.nodes.allocate(ArenaRBNode { key: value, color: NodeColor::Black, parent, left: NIL, right: NIL });
+            return;
+        }
+        let child_belongs_on_left: bool = value < self.nodes[parent].key;
+        let child: u32 = self.nodes.allocate(ArenaRBNode { key: value, color: NodeColor::Red, parent, left: NIL, right: NIL });
+        if child_belongs_on_left {
+            self.nodes[parent].left = child;
+        } else {
+            self.nodes[parent].right = child;
+        }
+        self.insert_repair(child);
+    }
+
+    fn delete(&mut self, value: T) {
+        let (found, index) = self.search(&value);
+        if !found {
+            #[cfg(feature = "std")]
+            println!("The node of value {:#?} doesn't exist.", value);
+            return;
+        }
+        self.observer.on_event(&TreeEvent::Deleted { key: value });
+        self.delete_node(index);
+    }
+
+    #[cfg(feature = "std")]
+    fn print_tree(&self) {
+        print!("{}", self.render());
+    }
+
+    fn render(&self) -> String {
+        arena::render(&self.nodes, self.root)
+    }
+
+    fn contain(&self, value: T) -> bool {
+        self.search(&value).0
+    }
+
+    fn count_nodes(&self) -> u32 {
+        self.nodes.len() as u32
+    }
+}
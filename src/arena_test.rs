@@ -0,0 +1,77 @@
+#[cfg(test)]
+mod test {
+    use crate::arena::{Arena, NIL};
+    use crate::arena_avltree::ArenaAVLTree;
+    use crate::arena_rbtree::ArenaRBTree;
+    use crate::avltree::AVLTree;
+    use crate::base::Tree;
+    use crate::observer::EventLog;
+    use crate::rbtree::RBTree;
+    use crate::workload::{Distribution, Mix, Workload};
+
+    #[test]
+    fn test_arena() {
+        let mut arena: Arena<i32> = Arena::default();
+        let first = arena.allocate(10);
+        let second = arena.allocate(20);
+        assert_eq!((first, second), (0, 1));
+        assert_eq!(arena.remove(first), 10);
+        assert_eq!(arena.len(), 1);
+        // A freed slot is reused before the arena grows.
+        assert_eq!(arena.allocate(30), first);
+        assert_eq!(arena[first], 30);
+        assert_eq!(arena.allocate(40), 2);
+        arena.clear();
+        assert!(arena.is_empty());
+        assert_ne!(NIL, 0);
+    }
+
+    #[test]
+    fn test_same_shape_as_linked_trees() {
+        let mix = Mix { insert: 50, delete: 40, lookup: 10 };
+        for distribution in Distribution::ALL {
+            let workload = Workload::generate(distribution, 600, mix, 9);
+            let mut arena_avl = ArenaAVLTree::with_observer(EventLog::default());
+            let mut avl = AVLTree::with_observer(EventLog::default());
+            let mut arena_rb = ArenaRBTree::with_observer(EventLog::default());
+            let mut rb = RBTree::with_observer(EventLog::default());
+            assert_eq!(workload.replay(&mut arena_avl), workload.replay(&mut avl));
+            assert_eq!(workload.replay(&mut arena_rb), workload.replay(&mut rb));
+
+            assert_eq!(arena_avl.to_json(), avl.to_json(), "{:?}", distribution);
+            assert_eq!(arena_avl.render(), avl.render());
+            assert_eq!(arena_avl.observer().events, avl.observer().events);
+            assert_eq!(arena_rb.to_json(), rb.to_json(), "{:?}", distribution);
+            assert_eq!(arena_rb.observer().events, rb.observer().events);
+            assert_eq!(arena_rb.to_svg(), rb.to_svg());
+
+            assert_eq!(arena_avl.count_nodes(), avl.count_nodes());
+            assert_eq!(arena_avl.get_height(), avl.get_height());
+            assert_eq!(arena_rb.count_leaves(), rb.count_leaves());
+            assert_eq!(arena_rb.range(100, 300), rb.range(100, 300));
+            assert_eq!(arena_rb.rank(250), rb.rank(250));
+        }
+    }
+
+    #[test]
+    fn test_slots_are_reused() {
+        let mut tree: ArenaRBTree<i32> = ArenaRBTree::new();
+        for number in 0..100 {
+            tree.insert(number);
+        }
+        let bytes = tree.allocated_bytes();
+        for round in 0..10 {
+            for number in 0..50 {
+                tree.delete(number * 2 + round % 2);
+            }
+            for number in 0..50 {
+                tree.insert(number * 2 + round % 2);
+            }
+        }
+        assert_eq!(tree.count_nodes(), 100);
+        assert_eq!(tree.allocated_bytes(), bytes);
+        tree.clear();
+        assert!(tree.is_empty());
+        assert_eq!(tree.render(), "This tree is empty!\n");
+    }
+}
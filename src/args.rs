@@ -0,0 +1,164 @@
+use trees::workload::{Distribution, Mix};
+
+use crate::registry::TreeKind;
+
+/// The help of the binary, with the kinds of tree of the registry.
+pub fn usage() -> String {
+    format!(
+        "\
+Usage: trees [COMMAND] [OPTIONS]
+
+Commands:
+  interactive          start the interactive menu (the default without a command)
+  run [FILE]           run a script of tree commands, from stdin without FILE or with '-'
+                       ('trees run --help' lists the script commands)
+  build [OPTIONS]      build a tree from a list of keys and print it
+  stats [OPTIONS]      build a tree from a list of keys and print its statistics
+  generate [OPTIONS]   print seeded keys, one per line, or a workload script with --mix
+  bench [OPTIONS]      time a seeded workload on every kind of tree
+
+Kinds of tree (KIND):
+  {}
+
+Options of build and stats:
+  --kind KIND          the kind of tree to build (default rb)
+  --from FILE          read the keys, separated by spaces or newlines, from FILE
+                       (default stdin, also with '-')
+Options of build only:
+  --print              print the tree as text (the default)
+  --json               print the tree as JSON
+  --svg                print the tree as an SVG drawing
+
+Options of generate and bench:
+  --dist NAME          uniform, ascending, descending, zigzag, clustered or
+                       adversarial (default uniform)
+  --count N            the number of keys, or of operations (default 10, 10000 for bench)
+  --seed N             the seed of the random choices (default 0)
+  --mix I,D,L          the weights of inserts, deletes and lookups of the workload
+                       (default 50,25,25 for bench); generate prints the operations
+                       as lines of 'trees run' scripts
+Options of bench only:
+  --kind KIND          only time this kind of tree, can be repeated (default all)
+  --csv                print comma separated values instead of a table
+
+  -h, --help           print this help
+  -V, --version        print the version",
+        TreeKind::names()
+    )
+}
+
+/// How `build` prints the tree.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Svg,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Command {
+    Help,
+    Version,
+    Interactive,
+    Run { script: Option<String> },
+    Build { kind: TreeKind, from: Option<String>, formats: Vec<OutputFormat> },
+    Stats { kind: TreeKind, from: Option<String> },
+    Generate { distribution: Distribution, count: usize, seed: u64, mix: Option<Mix> },
+    Bench { kinds: Vec<TreeKind>, distribution: Distribution, count: usize, seed: u64, mix: Mix, csv: bool },
+}
+
+// Splits "--name=value" and "--name value" alike, taking the value from the next argument if needed.
+fn option_value<'a>(name: &str, inline: Option<&'a str>, rest: &mut impl Iterator<Item = &'a String>) -> Result<&'a str, String> {
+    match inline {
+        Some(value) => Ok(value),
+        None => rest.next().map(String::as_str).ok_or_else(|| format!("'{}' needs a value", name)),
+    }
+}
+
+fn parse_tree_options(command: &str, arguments: &[String]) -> Result<Command, String> {
+    let mut kind: TreeKind = TreeKind::Rb;
+    let mut from: Option<String> = None;
+    let mut formats: Vec<OutputFormat> = Vec::new();
+    let mut rest = arguments.iter();
+    while let Some(argument) = rest.next() {
+        let (name, inline) = match argument.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name, Some(value)),
+            _ => (argument.as_str(), None),
+        };
+        match (command, name) {
+            (_, "--kind") => kind = TreeKind::parse(option_value(name, inline, &mut rest)?)?,
+            (_, "--from") => from = Some(option_value(name, inline, &mut rest)?.to_string()),
+            ("build", "--print") => formats.push(OutputFormat::Text),
+            ("build", "--json") => formats.push(OutputFormat::Json),
+            ("build", "--svg") => formats.push(OutputFormat::Svg),
+            _ => return Err(format!("unexpected argument '{}' for '{}'", argument, command)),
+        }
+    }
+    if command == "build" {
+        if formats.is_empty() {
+            formats.push(OutputFormat::Text);
+        }
+        Ok(Command::Build { kind, from, formats })
+    } else {
+        Ok(Command::Stats { kind, from })
+    }
+}
+
+fn parse_workload_options(command: &str, arguments: &[String]) -> Result<Command, String> {
+    let mut distribution: Distribution = Distribution::Uniform;
+    let mut count: usize = if command == "bench" { 10000 } else { 10 };
+    let mut seed: u64 = 0;
+    let mut mix: Option<Mix> = None;
+    let mut kinds: Vec<TreeKind> = Vec::new();
+    let mut csv: bool = false;
+    let mut rest = arguments.iter();
+    while let Some(argument) = rest.next() {
+        let (name, inline) = match argument.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name, Some(value)),
+            _ => (argument.as_str(), None),
+        };
+        match (command, name) {
+            (_, "--dist") => distribution = Distribution::parse(option_value(name, inline, &mut rest)?)?,
+            (_, "--count" | "--seed") => {
+                let value = option_value(name, inline, &mut rest)?;
+                let number: u64 = value.parse().map_err(|_| format!("'{}' needs a number, got '{}'", name, value))?;
+                match name {
+                    "--count" => count = number as usize,
+                    _ => seed = number,
+                }
+            }
+            (_, "--mix") => mix = Some(Mix::parse(option_value(name, inline, &mut rest)?)?),
+            ("bench", "--kind") => kinds.push(TreeKind::parse(option_value(name, inline, &mut rest)?)?),
+            ("bench", "--csv") => csv = true,
+            _ => return Err(format!("unexpected argument '{}' for '{}'", argument, command)),
+        }
+    }
+    if command == "generate" {
+        return Ok(Command::Generate { distribution, count, seed, mix });
+    }
+    if kinds.is_empty() {
+        kinds = TreeKind::ALL.to_vec();
+    }
+    Ok(Command::Bench { kinds, distribution, count, seed, mix: mix.unwrap_or_default(), csv })
+}
+
+/// Parses the arguments of the binary, without the program name.
+pub fn parse(arguments: &[String]) -> Result<Command, String> {
+    let (command, rest) = match arguments.split_first() {
+        None => return Ok(Command::Interactive),
+        Some((command, rest)) => (command.as_str(), rest),
+    };
+    // The script runner has its own help, listing the script commands.
+    if command != "run" && arguments.iter().any(|argument| argument == "--help" || argument == "-h") {
+        return Ok(Command::Help);
+    }
+    match command {
+        "--version" | "-V" if rest.is_empty() => Ok(Command::Version),
+        "interactive" if rest.is_empty() => Ok(Command::Interactive),
+        "run" if rest.len() <= 1 => Ok(Command::Run { script: rest.first().cloned() }),
+        "build" | "stats" => parse_tree_options(command, rest),
+        "generate" | "bench" => parse_workload_options(command, rest),
+        "--version" | "-V" | "interactive" | "run" => Err(format!("too many arguments for '{}'", command)),
+        _ => Err(format!("unknown command '{}'", command)),
+    }
+}
@@ -0,0 +1,83 @@
+#[cfg(test)]
+mod test {
+    use trees::workload::{Distribution, Mix};
+
+    use crate::args::{self, Command, OutputFormat};
+    use crate::registry::TreeKind;
+
+    fn parse(line: &str) -> Result<Command, String> {
+        let arguments: Vec<String> = line.split_whitespace().map(String::from).collect();
+        args::parse(&arguments)
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(parse(""), Ok(Command::Interactive));
+        assert_eq!(parse("interactive"), Ok(Command::Interactive));
+        assert_eq!(parse("--version"), Ok(Command::Version));
+        assert_eq!(parse("-V"), Ok(Command::Version));
+        assert_eq!(parse("--help"), Ok(Command::Help));
+        assert_eq!(parse("build --kind avl -h"), Ok(Command::Help));
+        assert_eq!(parse("run"), Ok(Command::Run { script: None }));
+        assert_eq!(parse("run script.txt"), Ok(Command::Run { script: Some("script.txt".to_string()) }));
+        // The script runner prints its own help.
+        assert_eq!(parse("run --help"), Ok(Command::Run { script: Some("--help".to_string()) }));
+        assert_eq!(
+            parse("build --kind rb --from keys.txt --print"),
+            Ok(Command::Build { kind: TreeKind::Rb, from: Some("keys.txt".to_string()), formats: vec![OutputFormat::Text] })
+        );
+        assert_eq!(
+            parse("build --kind=bst --json --svg"),
+            Ok(Command::Build { kind: TreeKind::Bst, from: None, formats: vec![OutputFormat::Json, OutputFormat::Svg] })
+        );
+        assert_eq!(parse("build"), Ok(Command::Build { kind: TreeKind::Rb, from: None, formats: vec![OutputFormat::Text] }));
+        assert_eq!(
+            parse("stats --from=keys.txt --kind avl"),
+            Ok(Command::Stats { kind: TreeKind::Avl, from: Some("keys.txt".to_string()) })
+        );
+        assert_eq!(parse("generate"), Ok(Command::Generate { distribution: Distribution::Uniform, count: 10, seed: 0, mix: None }));
+        assert_eq!(
+            parse("generate --dist=zigzag --count 100 --seed=7 --mix 2,1,1"),
+            Ok(Command::Generate {
+                distribution: Distribution::ZigZag,
+                count: 100,
+                seed: 7,
+                mix: Some(Mix { insert: 2, delete: 1, lookup: 1 }),
+            })
+        );
+        assert_eq!(
+            parse("bench --kind avl --kind bst --csv --dist ascending"),
+            Ok(Command::Bench {
+                kinds: vec![TreeKind::Avl, TreeKind::Bst],
+                distribution: Distribution::Ascending,
+                count: 10000,
+                seed: 0,
+                mix: Mix::default(),
+                csv: true,
+            })
+        );
+        match parse("bench --count 5") {
+            Ok(Command::Bench { kinds, count: 5, .. }) => assert_eq!(kinds, TreeKind::ALL),
+            command => panic!("unexpected {:?}", command),
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse("plant"), Err("unknown command 'plant'".to_string()));
+        assert_eq!(parse("build --kind"), Err("'--kind' needs a value".to_string()));
+        assert_eq!(parse("build --kind splay"), Err("unknown tree kind 'splay', expected rb, avl, bst, arena-rb, arena-avl, persistent-rb or persistent-avl".to_string()));
+        assert_eq!(parse("stats --print"), Err("unexpected argument '--print' for 'stats'".to_string()));
+        assert_eq!(
+            parse("bench --mix 4000000000,300000000,1"),
+            Err("the weights of the mix '4000000000,300000000,1' add up to more than 4294967295".to_string())
+        );
+        assert_eq!(parse("run a.txt b.txt"), Err("too many arguments for 'run'".to_string()));
+        assert_eq!(parse("generate --count ten"), Err("'--count' needs a number, got 'ten'".to_string()));
+        assert!(parse("generate --dist gaussian").is_err());
+        assert!(parse("generate --kind rb").is_err());
+        assert_eq!(parse("bench --print"), Err("unexpected argument '--print' for 'bench'".to_string()));
+        assert!(parse("interactive now").is_err());
+        assert!(parse("--version 2").is_err());
+    }
+}
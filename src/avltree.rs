@@ -1,200 +1,338 @@
-use std::rc::Rc;
-use std::cmp::max;
-use std::fmt::Debug;
-use std::cell::RefCell;
-use std::cmp::Ordering;
+use core::cmp::max;
+use core::fmt::{Debug, Write};
+use core::cmp::Ordering;
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
 
-use crate::base::{TreeNode, Tree};
+use crate::base::{self, NodeRef, TreeNode, Tree};
+use crate::svg::NodeStyle;
+use crate::json::{self, FromJson, JsonKey, JsonNode, JsonValue};
+use crate::observer::{NoopObserver, RotationDirection, TreeEvent, TreeObserver};
+use crate::trace::TraceStep;
+use crate::stats::StatsCounter;
+#[cfg(feature = "stats")]
+use crate::stats::TreeStats;
 
-type RcRefcellAVLNode<T> = Rc<RefCell<AVLTreeNode<T>>>;
-type OptionNode<T> = Option<RcRefcellAVLNode<T>>;
+type BoxAVLNode<T> = Box<AVLTreeNode<T>>;
+type OptionNode<T> = Option<BoxAVLNode<T>>;
+
+// Checks a node of a parsed JSON tree whose subtrees have the given heights,
+// for every kind of AVL tree, and returns its height: the subtrees may differ
+// by at most one and the `height` field, when present, must be right.
+pub(crate) fn check_json_height<T: Debug>(shape: &JsonNode<T>, left: u32, right: u32) -> Result<u32, String> {
+    if left.abs_diff(right) > 1 {
+        return Err(format!("The node {:?} is out of balance", shape.key));
+    }
+    let height: u32 = max(left, right) + 1;
+    match shape.attribute("height") {
+        None => Ok(height),
+        Some(JsonValue::Number(number)) if number.parse::<u32>() == Ok(height) => Ok(height),
+        Some(_) => Err(format!("The node {:?} has a wrong height, it should be {}", shape.key, height)),
+    }
+}
+
+// The ancestors of the subtree a recursive insert or delete works on, nearest
+// first, each missing the child the operation went down to.
+struct TracePath<'a, T: Ord + Clone + Debug> {
+    node: &'a AVLTreeNode<T>,
+    is_left: bool,
+    parent: Option<&'a TracePath<'a, T>>,
+}
 
 /// AVLTreeNode is a node in the Tree (The base moduel).
 /// data is the value of the node.
 /// _height is the height of the node.
+/// _leaves is the number of leaves below the node, itself included.
+/// Every node owns its children, so the tree is `Send` and `Sync` when its keys are.
 #[derive(Debug)]
-pub struct AVLTreeNode<T: Ord + Copy + Debug> {
+pub struct AVLTreeNode<T: Ord + Clone + Debug> {
     pub data: T,
     _left: OptionNode<T>,
     _right: OptionNode<T>,
     _height: u32,
+    _leaves: u32,
 }
 
-impl<T: Ord + Copy + Debug> TreeNode<T> for AVLTreeNode<T> {
-    fn get_left(&self) -> &OptionNode<T> {
-        &self._left
+impl<T: Ord + Clone + Debug> TreeNode<T> for AVLTreeNode<T> {
+    fn get_left(&self) -> Option<&Self> {
+        self._left.as_deref()
     }
 
-    fn get_right(&self) -> &OptionNode<T> {
-        &self._right
+    fn get_right(&self) -> Option<&Self> {
+        self._right.as_deref()
     }
 
     fn get_data(&self) -> T {
-        self.data
+        self.data.clone()
+    }
+
+    fn json_attributes(&self) -> Vec<(&'static str, String)> {
+        vec![("height", self._height.to_string())]
+    }
+
+    fn svg_style(&self) -> NodeStyle {
+        NodeStyle {
+            annotation: Some(format!("h={} bf={}", self._height, self.balance_factor())),
+            ..NodeStyle::default()
+        }
     }
 }
 
-impl<T: Ord + Copy + Debug> AVLTreeNode<T> {
+impl<T: Ord + Clone + Debug> AVLTreeNode<T> {
     /// Makes a new empty AVLTree.
     ///
     /// Does not allocate anything on its own.
     fn new(data: T) -> OptionNode<T> {
-        Some(Rc::new(RefCell::new(AVLTreeNode {
+        Some(Box::new(AVLTreeNode {
             data,
             _left: None,
             _right: None,
             _height: 1,
-        })))
+            _leaves: 1,
+        }))
     }
 
-    fn _get_height(node: Option<RcRefcellAVLNode<T>>) -> u32 {
-        node.map_or(0, |this_node| this_node.borrow()._height)
+    /// The height of the subtree rooted at this node, a leaf having height 1.
+    pub fn height(&self) -> u32 {
+        self._height
     }
 
-    fn _get_left_height(node: &RcRefcellAVLNode<T>) -> u32 {
-        Self::_get_height(node.borrow()._left.clone())
+    /// The height of the left subtree minus the height of the right subtree.
+    pub fn balance_factor(&self) -> i64 {
+        Self::_get_height(&self._left) as i64 - Self::_get_height(&self._right) as i64
     }
 
-    fn _get_right_height(node: &RcRefcellAVLNode<T>) -> u32 {
-        Self::_get_height(node.borrow()._right.clone())
+    fn _get_height(node: &OptionNode<T>) -> u32 {
+        node.as_ref().map_or(0, |this_node| this_node._height)
     }
 
-    fn _get_balance_factor(node: &RcRefcellAVLNode<T>) -> i64 {
-        Self::_get_left_height(node) as i64 - Self::_get_right_height(node) as i64
+    fn _get_left_height(&self) -> u32 {
+        Self::_get_height(&self._left)
     }
 
-    fn _left_rotate(root: RcRefcellAVLNode<T>) -> RcRefcellAVLNode<T> {
-        let new_root: RcRefcellAVLNode<T> = root.borrow()._right.clone().unwrap();
-        root.borrow_mut()._right = new_root.borrow()._left.clone().take();
-        root.borrow_mut()._height = max(
-            Self::_get_left_height(&root),
-            Self::_get_right_height(&root),
-        ) + 1;
-        new_root.borrow_mut()._left = Some(root);
-        new_root.borrow_mut()._height = max(
-            Self::_get_left_height(&new_root),
-            Self::_get_right_height(&new_root),
-        ) + 1;
+    fn _get_right_height(&self) -> u32 {
+        Self::_get_height(&self._right)
+    }
+
+    // Recomputes the height and the leaves of this node from its children,
+    // whose own are up to date.
+    fn _update_metadata(&mut self) {
+        self._height = max(self._get_left_height(), self._get_right_height()) + 1;
+        self._leaves = match (&self._left, &self._right) {
+            (None, None) => 1,
+            (left, right) => left.as_ref().map_or(0, |left| left._leaves) + right.as_ref().map_or(0, |right| right._leaves),
+        };
+    }
+
+    fn _left_rotate<O: TreeObserver<T>>(mut root: BoxAVLNode<T>, observer: &mut O) -> BoxAVLNode<T> {
+        let pivot: T = root.data.clone();
+        observer.on_event(&TreeEvent::Rotated { pivot, direction: RotationDirection::Left });
+        let mut new_root: BoxAVLNode<T> = root._right.take().unwrap();
+        root._right = new_root._left.take();
+        root._update_metadata();
+        new_root._left = Some(root);
+        new_root._update_metadata();
         new_root
     }
 
-    fn _right_rotate(root: RcRefcellAVLNode<T>) -> RcRefcellAVLNode<T> {
-        let new_root: RcRefcellAVLNode<T> = root.borrow()._left.clone().unwrap();
-        root.borrow_mut()._left = new_root.borrow()._right.clone().take();
-        root.borrow_mut()._height = max(
-            Self::_get_left_height(&root),
-            Self::_get_right_height(&root),
-        ) + 1;
-        new_root.borrow_mut()._right = Some(root);
-        new_root.borrow_mut()._height = max(
-            Self::_get_left_height(&new_root),
-            Self::_get_right_height(&new_root),
-        ) + 1;
+    fn _right_rotate<O: TreeObserver<T>>(mut root: BoxAVLNode<T>, observer: &mut O) -> BoxAVLNode<T> {
+        let pivot: T = root.data.clone();
+        observer.on_event(&TreeEvent::Rotated { pivot, direction: RotationDirection::Right });
+        let mut new_root: BoxAVLNode<T> = root._left.take().unwrap();
+        root._left = new_root._right.take();
+        root._update_metadata();
+        new_root._right = Some(root);
+        new_root._update_metadata();
         new_root
     }
 
-    fn _left_right_rotate(root: RcRefcellAVLNode<T>) -> RcRefcellAVLNode<T> {
-        let left: RcRefcellAVLNode<T> = root.borrow()._left.clone().take().unwrap();
-        root.borrow_mut()._left = Some(Self::_left_rotate(left));
-        Self::_right_rotate(root)
+    fn _left_right_rotate<O: TreeObserver<T>>(mut root: BoxAVLNode<T>, observer: &mut O) -> BoxAVLNode<T> {
+        let left: BoxAVLNode<T> = root._left.take().unwrap();
+        root._left = Some(Self::_left_rotate(left, observer));
+        Self::_right_rotate(root, observer)
     }
 
-    fn _right_left_rotate(root: RcRefcellAVLNode<T>) -> RcRefcellAVLNode<T> {
-        let right: RcRefcellAVLNode<T> = root.borrow()._right.clone().take().unwrap();
-        root.borrow_mut()._right = Some(Self::_right_rotate(right));
-        Self::_left_rotate(root)
+    fn _right_left_rotate<O: TreeObserver<T>>(mut root: BoxAVLNode<T>, observer: &mut O) -> BoxAVLNode<T> {
+        let right: BoxAVLNode<T> = root._right.take().unwrap();
+        root._right = Some(Self::_right_rotate(right, observer));
+        Self::_left_rotate(root, observer)
     }
 
-    fn insert(node: OptionNode<T>, data: T) -> OptionNode<T> {
-        let return_node: RcRefcellAVLNode<T> = match node {
-            None => AVLTreeNode::new(data).unwrap(),
-            Some(this_node) => {
-                let node_data: T = this_node.borrow().data;
-                match data.cmp(&node_data) {
+    // A copy of the subtree, for the snapshots of traced operations.
+    fn _copy(&self) -> BoxAVLNode<T> {
+        Box::new(AVLTreeNode {
+            data: self.data.clone(),
+            _left: self._left.as_ref().map(|left| left._copy()),
+            _right: self._right.as_ref().map(|right| right._copy()),
+            _height: self._height,
+            _leaves: self._leaves,
+        })
+    }
+
+    // Records a step of a traced insert or delete. While the recursion is running
+    // the subtree is detached from its ancestors, so the snapshot puts a copy of
+    // it back under copies of the ancestors to show the whole tree.
+    fn _trace_step(trace: &mut Option<Vec<TraceStep<T>>>, case: &str, nodes: Vec<T>, subtree: Option<&AVLTreeNode<T>>, path: Option<&TracePath<T>>) {
+        if let Some(steps) = trace.as_mut() {
+            let mut root: OptionNode<T> = subtree.map(AVLTreeNode::_copy);
+            let mut next: Option<&TracePath<T>> = path;
+            while let Some(ancestor) = next {
+                let mut copy: BoxAVLNode<T> = ancestor.node._copy();
+                match ancestor.is_left {
+                    true => copy._left = root,
+                    false => copy._right = root,
+                }
+                root = Some(copy);
+                next = ancestor.parent;
+            }
+            let snapshot: String = Self::render_root(root.as_deref());
+            steps.push(TraceStep { case: case.to_string(), nodes, snapshot });
+        }
+    }
+
+    fn render_root(root: Option<&AVLTreeNode<T>>) -> String {
+        let mut output = String::new();
+        match root {
+            None => output.push_str("This tree is empty!\n"),
+            Some(root) => root.render_node(
+                &"".to_string(),
+                "Root".to_string(),
+                false,
+                &mut output,
+            ),
+        }
+        output
+    }
+
+    fn insert<O: TreeObserver<T>>(node: OptionNode<T>, data: T, observer: &mut O, trace: &mut Option<Vec<TraceStep<T>>>, stats: &StatsCounter, path: Option<&TracePath<T>>) -> OptionNode<T> {
+        let _depth = stats.descend();
+        let return_node: BoxAVLNode<T> = match node {
+            None => {
+                observer.on_event(&TreeEvent::Inserted { key: data.clone() });
+                stats.allocation();
+                let leaf: OptionNode<T> = AVLTreeNode::new(data.clone());
+                Self::_trace_step(trace, "insert as leaf", vec![data.clone()], leaf.as_deref(), path);
+                leaf.unwrap()
+            }
+            Some(mut this_node) => {
+                stats.comparison();
+                match data.cmp(&this_node.data) {
                     Ordering::Less => {
-                        let left: OptionNode<T> = this_node.borrow()._left.clone();
-                        this_node.borrow_mut()._left = Self::insert(left, data);
+                        let left: OptionNode<T> = this_node._left.take();
+                        let below = TracePath { node: &this_node, is_left: true, parent: path };
+                        this_node._left = Self::insert(left, data.clone(), observer, trace, stats, Some(&below));
                     }
                     Ordering::Greater => {
-                        let right: OptionNode<T> = this_node.borrow()._right.clone();
-                        this_node.borrow_mut()._right = Self::insert(right, data);
+                        let right: OptionNode<T> = this_node._right.take();
+                        let below = TracePath { node: &this_node, is_left: false, parent: path };
+                        this_node._right = Self::insert(right, data.clone(), observer, trace, stats, Some(&below));
                     }
                     Ordering::Equal => {}
                 }
                 this_node
             }
         };
-        let balance_factor: i64 = Self::_get_balance_factor(&return_node);
-        let new_return_node: RcRefcellAVLNode<T> = match balance_factor {
+        let balance_factor: i64 = return_node.balance_factor();
+        let key: T = return_node.data.clone();
+        let mut case: &str = "";
+        let mut new_return_node: BoxAVLNode<T> = match balance_factor {
             2 => {
-                let new_data: T = return_node.borrow()._left.clone().unwrap().borrow().data;
+                let new_data: T = return_node._left.as_ref().unwrap().data.clone();
+                stats.comparison();
                 match data.cmp(&new_data) {
-                    Ordering::Less => Self::_right_rotate(return_node),
-                    Ordering::Greater => Self::_left_right_rotate(return_node),
+                    Ordering::Less => {
+                        case = "LL right rotation";
+                        stats.single_rotation();
+                        Self::_right_rotate(return_node, observer)
+                    }
+                    Ordering::Greater => {
+                        case = "LR left-right rotation";
+                        stats.double_rotation();
+                        Self::_left_right_rotate(return_node, observer)
+                    }
                     _ => return_node,
                 }
             }
             -2 => {
-                let new_data: T = return_node.borrow()._right.clone().unwrap().borrow().data;
+                let new_data: T = return_node._right.as_ref().unwrap().data.clone();
+                stats.comparison();
                 match data.cmp(&new_data) {
-                    Ordering::Less => Self::_right_left_rotate(return_node),
-                    Ordering::Greater => Self::_left_rotate(return_node),
+                    Ordering::Less => {
+                        case = "RL right-left rotation";
+                        stats.double_rotation();
+                        Self::_right_left_rotate(return_node, observer)
+                    }
+                    Ordering::Greater => {
+                        case = "RR left rotation";
+                        stats.single_rotation();
+                        Self::_left_rotate(return_node, observer)
+                    }
                     _ => return_node,
                 }
             }
             _ => return_node,
         };
-        new_return_node.borrow_mut()._height = max(
-            Self::_get_left_height(&new_return_node),
-            Self::_get_right_height(&new_return_node),
-        ) + 1;
+        new_return_node._update_metadata();
+        if balance_factor.abs() == 2 {
+            observer.on_event(&TreeEvent::Rebalanced { key: key.clone() });
+            Self::_trace_step(trace, case, vec![key, data], Some(&new_return_node), path);
+        }
         Some(new_return_node)
     }
 
-    fn delete(node: OptionNode<T>, data: T) -> OptionNode<T> {
+    fn delete<O: TreeObserver<T>>(node: OptionNode<T>, data: T, observer: &mut O, trace: &mut Option<Vec<TraceStep<T>>>, stats: &StatsCounter, path: Option<&TracePath<T>>) -> OptionNode<T> {
+        let _depth = stats.descend();
         let return_node: OptionNode<T> = match node {
             None => {
                 node
             }
-            Some(this_node) => {
-                let node_data: T = this_node.borrow().data;
-                match node_data.cmp(&data) {
+            Some(mut this_node) => {
+                stats.comparison();
+                match this_node.data.cmp(&data) {
                     Ordering::Greater => {
-                        let left: OptionNode<T> = this_node.borrow()._left.clone();
-                        match left {
+                        match this_node._left.take() {
                             None => return Some(this_node),
-                            Some(_) => {
-                                let left: OptionNode<T> = this_node.borrow()._left.clone().take();
-                                this_node.borrow_mut()._left = Self::delete(left, data);
+                            Some(left) => {
+                                let below = TracePath { node: &this_node, is_left: true, parent: path };
+                                this_node._left = Self::delete(Some(left), data, observer, trace, stats, Some(&below));
                             }
                         }
                         Some(this_node)
                     }
                     Ordering::Less => {
-                        let right: OptionNode<T> = this_node.borrow()._right.clone();
-                        match right {
+                        match this_node._right.take() {
                             None => return Some(this_node),
-                            Some(_) => {
-                                let right: OptionNode<T> = this_node.borrow()._right.clone().take();
-                                this_node.borrow_mut()._right = Self::delete(right, data);
+                            Some(right) => {
+                                let below = TracePath { node: &this_node, is_left: false, parent: path };
+                                this_node._right = Self::delete(Some(right), data, observer, trace, stats, Some(&below));
                             }
                         }
                         Some(this_node)
                     }
                     Ordering::Equal => {
-                        let left: OptionNode<T> = this_node.borrow()._left.clone();
-                        let right: OptionNode<T> = this_node.borrow()._right.clone();
-                        match (left.clone(), right.clone()) {
-                            (Some(_), Some(inner_right)) => {
-                                let min_value: T = inner_right.borrow().get_min();
-                                this_node.borrow_mut().data = min_value;
-                                let right: OptionNode<T> = this_node.borrow()._right.clone().take();
-                                this_node.borrow_mut()._right = Self::delete(right, min_value);
+                        match (this_node._left.take(), this_node._right.take()) {
+                            (Some(left), Some(right)) => {
+                                let min_value: T = right.as_ref().get_min();
+                                this_node.data = min_value.clone();
+                                this_node._left = Some(left);
+                                this_node._right = Some(right);
+                                Self::_trace_step(trace, "3 node has two children", vec![data, min_value.clone()], Some(&this_node), path);
+                                let right: OptionNode<T> = this_node._right.take();
+                                let below = TracePath { node: &this_node, is_left: false, parent: path };
+                                this_node._right = Self::delete(right, min_value, observer, trace, stats, Some(&below));
                                 Some(this_node)
                             }
-                            (Some(inner_left), _) => Some(inner_left),
-                            (_, Some(inner_right)) => Some(inner_right),
-                            (_, _) => None,
+                            (Some(child), _) | (_, Some(child)) => {
+                                let child_data: T = child.data.clone();
+                                Self::_trace_step(trace, "2 node has one child", vec![data, child_data], Some(&child), path);
+                                Some(child)
+                            }
+                            (_, _) => {
+                                Self::_trace_step(trace, "1 node is leaf", vec![data], None, path);
+                                None
+                            }
                         }
                     }
                 }
@@ -205,41 +343,96 @@ impl<T: Ord + Copy + Debug> AVLTreeNode<T> {
                 return_node
             }
             Some(this_node) => {
-                let balance_factor: i64 = Self::_get_balance_factor(&this_node);
-                let return_node: RcRefcellAVLNode<T> = match balance_factor {
+                let balance_factor: i64 = this_node.balance_factor();
+                let key: T = this_node.data.clone();
+                let mut case: &str = "";
+                let mut return_node: BoxAVLNode<T> = match balance_factor {
                     2 => {
-                        let left_child: &RcRefcellAVLNode<T> = &this_node.borrow()._left.clone().unwrap();
-                        let left_child_height: u32 = Self::_get_left_height(left_child);
-                        let right_child_height: u32 = Self::_get_right_height(left_child);
+                        let left_child: &AVLTreeNode<T> = this_node._left.as_ref().unwrap();
+                        let left_child_height: u32 = left_child._get_left_height();
+                        let right_child_height: u32 = left_child._get_right_height();
                         match left_child_height.cmp(&right_child_height) {
-                            Ordering::Greater | Ordering::Equal => Self::_right_rotate(this_node),
-                            Ordering::Less => Self::_left_right_rotate(this_node)
+                            Ordering::Greater | Ordering::Equal => {
+                                case = "LL right rotation";
+                                stats.single_rotation();
+                                Self::_right_rotate(this_node, observer)
+                            }
+                            Ordering::Less => {
+                                case = "LR left-right rotation";
+                                stats.double_rotation();
+                                Self::_left_right_rotate(this_node, observer)
+                            }
                         }
                     }
                     -2 => {
-                        let right_child: &RcRefcellAVLNode<T> = &this_node.borrow()._right.clone().unwrap();
-                        let left_child_height: u32 = Self::_get_left_height(right_child);
-                        let right_child_height: u32 = Self::_get_right_height(right_child);
+                        let right_child: &AVLTreeNode<T> = this_node._right.as_ref().unwrap();
+                        let left_child_height: u32 = right_child._get_left_height();
+                        let right_child_height: u32 = right_child._get_right_height();
                         match right_child_height.cmp(&left_child_height) {
-                            Ordering::Greater | Ordering::Equal => Self::_left_rotate(this_node),
-                            Ordering::Less => Self::_right_left_rotate(this_node)
+                            Ordering::Greater | Ordering::Equal => {
+                                case = "RR left rotation";
+                                stats.single_rotation();
+                                Self::_left_rotate(this_node, observer)
+                            }
+                            Ordering::Less => {
+                                case = "RL right-left rotation";
+                                stats.double_rotation();
+                                Self::_right_left_rotate(this_node, observer)
+                            }
                         }
                     }
                     _ => this_node,
                 };
-                return_node.borrow_mut()._height = max(
-                    Self::_get_left_height(&return_node),
-                    Self::_get_right_height(&return_node),
-                ) + 1;
+                return_node._update_metadata();
+                if balance_factor.abs() == 2 {
+                    observer.on_event(&TreeEvent::Rebalanced { key: key.clone() });
+                    Self::_trace_step(trace, case, vec![key], Some(&return_node), path);
+                }
                 Some(return_node)
             }
         }
     }
 
-    fn print_node(&self, prefix_space: &String, child_prefix: String, is_right: bool) {
+    // Frees the nodes one by one instead of letting every box drop its
+    // children, which would recurse as deep as the tree.
+    fn drop_nodes(root: OptionNode<T>) {
+        let mut stack: Vec<BoxAVLNode<T>> = root.into_iter().collect();
+        while let Some(mut node) = stack.pop() {
+            stack.extend(node._left.take());
+            stack.extend(node._right.take());
+        }
+    }
+
+    // Builds a balanced subtree from the next count keys, which come in
+    // ascending order: the smaller half goes left and the larger half right.
+    fn from_sorted(keys: &mut alloc::vec::IntoIter<T>, count: usize) -> OptionNode<T> {
+        if count == 0 {
+            return None;
+        }
+        let left: OptionNode<T> = Self::from_sorted(keys, (count - 1) / 2);
+        let mut node: BoxAVLNode<T> = AVLTreeNode::new(keys.next().unwrap()).unwrap();
+        node._left = left;
+        node._right = Self::from_sorted(keys, count / 2);
+        node._update_metadata();
+        Some(node)
+    }
+
+    // Builds a node of a parsed JSON tree on its built children, for
+    // `json::fold`, checking its balance and the height the JSON gives.
+    fn from_json_node(shape: &JsonNode<T>, left: Option<BoxAVLNode<T>>, right: Option<BoxAVLNode<T>>) -> Result<BoxAVLNode<T>, String> {
+        let height = |child: &Option<BoxAVLNode<T>>| child.as_ref().map_or(0, |child| child._height);
+        check_json_height(shape, height(&left), height(&right))?;
+        let mut node: BoxAVLNode<T> = AVLTreeNode::new(shape.key.clone()).unwrap();
+        node._left = left;
+        node._right = right;
+        node._update_metadata();
+        Ok(node)
+    }
+
+    fn render_node(&self, prefix_space: &String, child_prefix: String, is_right: bool, output: &mut String) {
         match prefix_space.len() {
-            6 => println!("|____ {} {:?}", child_prefix, self.data),
-            _ => println!("{}{} {:?}", prefix_space, child_prefix, self.data),
+            6 => { let _ = writeln!(output, "|____ {} {:?}", child_prefix, self.data); }
+            _ => { let _ = writeln!(output, "{}{} {:?}", prefix_space, child_prefix, self.data); }
         }
         let mut new_prefix_space: String = String::from(prefix_space);
         match is_right {
@@ -258,28 +451,39 @@ impl<T: Ord + Copy + Debug> AVLTreeNode<T> {
             ),
         }
         if let Some(left) = self.get_left() {
-            left.borrow().print_node(
+            left.render_node(
                 &new_prefix_space,
                 "L".to_string(),
                 false,
+                output,
             );
         }
         if let Some(right) = self.get_right() {
-            right.borrow().print_node(
+            right.render_node(
                 &new_prefix_space,
                 "R".to_string(),
                 true,
+                output,
             );
         }
     }
 }
 
+/// An AVL tree is a self-balancing binary search tree in which the heights
+/// of the two subtrees of any node differ by at most one.
+/// The observer receives the rotations done by the tree.
 #[derive(Debug)]
-pub struct AVLTree<T: Ord + Copy + Debug> {
+pub struct AVLTree<T: Ord + Clone + Debug, O: TreeObserver<T> = NoopObserver> {
     _root: OptionNode<T>,
+    len: u32,
+    observer: O,
+    trace: Option<Vec<TraceStep<T>>>,
+    stats: StatsCounter,
 }
 
-impl<T: Ord + Copy + Debug> Tree<T, AVLTreeNode<T>> for AVLTree<T> {
+impl<T: Ord + Clone + Debug, O: TreeObserver<T>> Tree<T> for AVLTree<T, O> {
+    type Node<'a> = &'a AVLTreeNode<T> where Self: 'a;
+
     /// Return the root node of the RBTree.
     ///
     /// # Examples
@@ -301,11 +505,27 @@ impl<T: Ord + Copy + Debug> Tree<T, AVLTreeNode<T>> for AVLTree<T> {
     /// //       |____ L 5
     /// //       |____ R 7
     /// let node = tree.get_root();
-    /// println!("The value of root is {}", node.as_ref().unwrap().borrow().data);
-    /// assert_eq!(node.as_ref().unwrap().borrow().data, 4);
+    /// println!("The value of root is {}", node.unwrap().data);
+    /// assert_eq!(node.unwrap().data, 4);
     /// ```
-    fn get_root(&self) -> &OptionNode<T> {
-        &self._root
+    fn get_root(&self) -> Option<&AVLTreeNode<T>> {
+        self._root.as_deref()
+    }
+
+    fn contain(&self, value: T) -> bool {
+        let mut node: Option<&AVLTreeNode<T>> = self._root.as_deref();
+        let mut depth: u64 = 0;
+        while let Some(current) = node {
+            depth += 1;
+            self.stats.reached_depth(depth);
+            self.stats.comparison();
+            node = match current.data.cmp(&value) {
+                Ordering::Greater => current._left.as_deref(),
+                Ordering::Less => current._right.as_deref(),
+                Ordering::Equal => return true,
+            };
+        }
+        false
     }
 
     /// Inserting a new element.
@@ -326,13 +546,14 @@ impl<T: Ord + Copy + Debug> Tree<T, AVLTreeNode<T>> for AVLTree<T> {
     /// assert_eq!(tree.count_nodes(), 4);
     /// ```
     fn insert(&mut self, data: T) {
-        if self.contain(data) == true {
+        if self.contain(data.clone()) {
+            #[cfg(feature = "std")]
             println!("This node already exists in the tree!");
+            AVLTreeNode::_trace_step(&mut self.trace, "already exists", vec![data], self._root.as_deref(), None);
         } else {
-            match self._root.take() {
-                Some(root) => self._root = AVLTreeNode::insert(Some(root), data),
-                None => self._root = AVLTreeNode::new(data),
-            }
+            let root: OptionNode<T> = self._root.take();
+            self._root = AVLTreeNode::insert(root, data, &mut self.observer, &mut self.trace, &self.stats, None);
+            self.len += 1;
         }
     }
 
@@ -362,12 +583,16 @@ impl<T: Ord + Copy + Debug> Tree<T, AVLTreeNode<T>> for AVLTree<T> {
     /// assert_eq!(tree.count_nodes(), 6);
     /// ```
     fn delete(&mut self, data: T) {
-        if self.contain(data) == false {
+        if !self.contain(data.clone()) {
+            #[cfg(feature = "std")]
             println!("This node does not exist in the tree!");
+            AVLTreeNode::_trace_step(&mut self.trace, "not found", vec![data], self._root.as_deref(), None);
         } else {
-            match self._root.take() {
-                Some(root) => self._root = AVLTreeNode::delete(Some(root), data),
-                None => return,
+            if let Some(root) = self._root.take() {
+                self.observer.on_event(&TreeEvent::Deleted { key: data.clone() });
+                self.stats.frees(1);
+                self._root = AVLTreeNode::delete(Some(root), data, &mut self.observer, &mut self.trace, &self.stats, None);
+                self.len -= 1;
             }
         }
     }
@@ -392,26 +617,245 @@ impl<T: Ord + Copy + Debug> Tree<T, AVLTreeNode<T>> for AVLTree<T> {
     /// //       |____ L 5
     /// //       |____ R 7
     /// ```
+    #[cfg(feature = "std")]
     fn print_tree(&self) {
-        match &self.get_root() {
-            None => println!("This tree is empty!"),
-            Some(root) => root.borrow().print_node(
-                &"".to_string(),
-                "Root".to_string(),
-                false,
-            ),
-        }
+        print!("{}", self.render());
+    }
+
+    /// Inserting a new element, recording every rebalancing applied.
+    ///
+    /// The snapshot of every step shows the whole tree right after it.
+    ///
+    /// # Examples
+    /// ```
+    /// use trees::avltree::AVLTree;
+    /// use crate::trees::base::Tree;
+    ///
+    /// let mut tree = AVLTree::new();
+    /// for i in [3, 1] {
+    ///     tree.insert(i);
+    /// }
+    /// let steps = tree.insert_traced(2);
+    /// let cases: Vec<&str> = steps.iter().map(|step| step.case.as_str()).collect();
+    /// assert_eq!(cases, vec!["insert as leaf", "LR left-right rotation"]);
+    /// assert_eq!(steps.last().unwrap().snapshot, tree.render());
+    /// ```
+    fn insert_traced(&mut self, data: T) -> Vec<TraceStep<T>> {
+        self._traced(|tree| tree.insert(data))
+    }
+
+    /// Remove the element with the target value, recording every case applied.
+    fn delete_traced(&mut self, data: T) -> Vec<TraceStep<T>> {
+        self._traced(|tree| tree.delete(data))
+    }
+
+    fn render(&self) -> String {
+        AVLTreeNode::render_root(self._root.as_deref())
+    }
+
+    /// The height of the tree, kept by the root.
+    fn get_height(&self) -> u32 {
+        self._root.as_ref().map_or(0, |root| root._height)
+    }
+
+    /// The number of leaves of the tree, kept by the root.
+    fn count_leaves(&self) -> u32 {
+        self._root.as_ref().map_or(0, |root| root._leaves)
+    }
+
+    /// The number of elements, kept by the tree.
+    fn count_nodes(&self) -> u32 {
+        self.len
     }
 }
 
 
-impl<T: Ord + Copy + Debug> AVLTree<T> {
+impl<T: Ord + Clone + Debug, O: TreeObserver<T> + Default> Default for AVLTree<T, O> {
+    fn default() -> Self {
+        Self::with_observer(O::default())
+    }
+}
+
+impl<T: Ord + Clone + Debug, O: TreeObserver<T>> Drop for AVLTree<T, O> {
+    fn drop(&mut self) {
+        AVLTreeNode::drop_nodes(self._root.take());
+    }
+}
+
+impl<T: Ord + Clone + Debug + JsonKey, O: TreeObserver<T> + Default> FromJson for AVLTree<T, O> {
+    fn from_json(text: &str) -> Result<Self, String> {
+        Self::from_json(text)
+    }
+}
+
+impl<T: Ord + Clone + Debug> AVLTree<T> {
     pub fn new() -> Self {
         Self {
-            _root: None
+            _root: None,
+            len: 0,
+            observer: NoopObserver,
+            trace: None,
+            stats: StatsCounter::new(),
+        }
+    }
+}
+
+impl<T: Ord + Clone + Debug, O: TreeObserver<T>> AVLTree<T, O> {
+    /// Makes a new empty AVLTree reporting its rotations to the observer.
+    ///
+    /// # Examples
+    /// ```
+    /// use trees::avltree::AVLTree;
+    /// use trees::base::Tree;
+    /// use trees::observer::{EventLog, RotationDirection, TreeEvent};
+    ///
+    /// let mut tree = AVLTree::with_observer(EventLog::default());
+    /// for i in 1..4 {
+    ///     tree.insert(i);
+    /// }
+    /// assert!(tree.observer().events.contains(&TreeEvent::Rotated { pivot: 1, direction: RotationDirection::Left }));
+    /// ```
+    pub fn with_observer(observer: O) -> Self {
+        Self {
+            _root: None,
+            len: 0,
+            observer,
+            trace: None,
+            stats: StatsCounter::new(),
         }
     }
 
+    // Runs an insert or delete while recording its steps.
+    fn _traced(&mut self, operation: impl FnOnce(&mut Self)) -> Vec<TraceStep<T>> {
+        self.trace = Some(Vec::new());
+        operation(self);
+        let mut steps: Vec<TraceStep<T>> = self.trace.take().unwrap_or_default();
+        let snapshot: String = self.render();
+        if steps.last().map(|step| &step.snapshot) != Some(&snapshot) {
+            steps.push(TraceStep { case: "done".to_string(), nodes: Vec::new(), snapshot });
+        }
+        steps
+    }
+
+    /// The observer of the tree.
+    pub fn observer(&self) -> &O {
+        &self.observer
+    }
+
+    /// The observer of the tree, mutably.
+    pub fn observer_mut(&mut self) -> &mut O {
+        &mut self.observer
+    }
+
+    /// The counters of the tree since it was made or last reset.
+    ///
+    /// Only available with the `stats` feature.
+    ///
+    /// # Examples
+    /// ```
+    /// use trees::avltree::AVLTree;
+    /// use crate::trees::base::Tree;
+    ///
+    /// let mut tree = AVLTree::new();
+    /// for i in [3, 1, 2] {
+    ///     tree.insert(i);
+    /// }
+    /// let stats = tree.stats();
+    /// assert_eq!(stats.allocations, 3);
+    /// assert_eq!(stats.double_rotations, 1);
+    /// assert_eq!(stats.max_depth, 3);
+    /// tree.reset_stats();
+    /// assert_eq!(tree.stats().allocations, 0);
+    /// ```
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> TreeStats {
+        self.stats.get()
+    }
+
+    /// Sets every counter of the tree back to zero.
+    ///
+    /// Only available with the `stats` feature.
+    #[cfg(feature = "stats")]
+    pub fn reset_stats(&mut self) {
+        self.stats.reset();
+    }
+
+    /// Rebuilds an AVLTree from the JSON written by `Tree::to_json`.
+    ///
+    /// The keys must be in binary search tree order and the heights of the
+    /// two subtrees of every node may differ by at most one. The `height`
+    /// fields are optional, but when present they must be correct.
+    ///
+    /// # Examples
+    /// ```
+    /// use trees::avltree::AVLTree;
+    /// use crate::trees::base::Tree;
+    ///
+    /// let tree: AVLTree<i32> = AVLTree::from_json(r#"{"key":2,"left":{"key":1},"right":{"key":3}}"#).unwrap();
+    /// assert_eq!(tree.get_height(), 2);
+    ///
+    /// // A chain of three nodes is not balanced.
+    /// assert!(AVLTree::<i32>::from_json(r#"{"key":1,"right":{"key":2,"right":{"key":3}}}"#).is_err());
+    /// ```
+    pub fn from_json(text: &str) -> Result<Self, String>
+    where
+        T: JsonKey,
+        O: Default,
+    {
+        let mut tree = Self::with_observer(O::default());
+        if let Some(root) = json::parse_tree::<T>(text)? {
+            let root: BoxAVLNode<T> = json::fold(&root, AVLTreeNode::from_json_node)?;
+            tree.len = root.as_ref().count_nodes();
+            tree._root = Some(root);
+        }
+        Ok(tree)
+    }
+
+    /// Builds a balanced AVLTree from keys in strictly ascending order, in
+    /// linear time instead of inserting them one by one.
+    ///
+    /// The keys are not checked, unsorted or repeated keys giving a tree
+    /// that is not a search tree; see `try_from_sorted_iter`.
+    ///
+    /// # Examples
+    /// ```
+    /// use trees::avltree::AVLTree;
+    /// use crate::trees::base::Tree;
+    ///
+    /// let tree: AVLTree<i32> = AVLTree::from_sorted_iter(1..=7);
+    /// assert_eq!(tree.get_height(), 3);
+    /// assert_eq!(tree.count_leaves(), 4);
+    /// ```
+    pub fn from_sorted_iter<I: IntoIterator<Item = T>>(keys: I) -> Self
+    where
+        O: Default,
+    {
+        let keys: Vec<T> = keys.into_iter().collect();
+        let count: usize = keys.len();
+        let mut tree = Self::with_observer(O::default());
+        tree._root = AVLTreeNode::from_sorted(&mut keys.into_iter(), count);
+        tree.len = count as u32;
+        tree
+    }
+
+    /// Builds a balanced AVLTree like `from_sorted_iter`, failing when the
+    /// keys are not in strictly ascending order.
+    ///
+    /// # Examples
+    /// ```
+    /// use trees::avltree::AVLTree;
+    ///
+    /// assert!(AVLTree::<i32>::try_from_sorted_iter(vec![1, 2, 3]).is_ok());
+    /// assert!(AVLTree::<i32>::try_from_sorted_iter(vec![1, 3, 2]).is_err());
+    /// assert!(AVLTree::<i32>::try_from_sorted_iter(vec![1, 1]).is_err());
+    /// ```
+    pub fn try_from_sorted_iter<I: IntoIterator<Item = T>>(keys: I) -> Result<Self, String>
+    where
+        O: Default,
+    {
+        Ok(Self::from_sorted_iter(base::collect_sorted(keys)?))
+    }
+
     /// Clear the AVLTree, removing all elements.
     ///
     /// # Examples
@@ -425,7 +869,10 @@ impl<T: Ord + Copy + Debug> AVLTree<T> {
     /// assert!(tree.is_empty());
     /// ```
     pub fn clear(&mut self) {
-        *self = AVLTree::new();
+        self.stats.frees(self.count_nodes() as u64);
+        AVLTreeNode::drop_nodes(self._root.take());
+        self.len = 0;
+        #[cfg(feature = "std")]
         println!("Clear operation is complete!");
     }
 }
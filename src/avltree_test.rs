@@ -1,7 +1,12 @@
 #[cfg(test)]
 mod test {
+    extern crate std;
+
     use crate::base::Tree;
     use crate::avltree;
+    use std::string::{String, ToString};
+    use std::vec;
+    use std::vec::Vec;
 
     #[test]
     fn test_avl() {
@@ -41,4 +46,78 @@ mod test {
         }
         assert_eq!(avl_tree.is_empty(), true);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_send_sync() {
+        fn assert_send_sync<S: Send + Sync>() {}
+        assert_send_sync::<avltree::AVLTree<String>>();
+
+        let mut tree: avltree::AVLTree<i32> = avltree::AVLTree::new();
+        for number in [5, 2, 8, 1, 9, 3] {
+            tree.insert(number);
+        }
+        let tree = std::sync::Arc::new(tree);
+        let readers: Vec<_> = (0..4)
+            .map(|reader| {
+                let tree = std::sync::Arc::clone(&tree);
+                std::thread::spawn(move || tree.contain(reader * 3) as u32 + tree.count_nodes())
+            })
+            .collect();
+        let found: Vec<u32> = readers.into_iter().map(|reader| reader.join().unwrap()).collect();
+        assert_eq!(found, vec![6, 7, 6, 7]);
+        let mut tree = std::thread::spawn(move || std::sync::Arc::try_unwrap(tree).ok().unwrap()).join().unwrap();
+        tree.delete(5);
+        assert_eq!(tree.count_nodes(), 5);
+    }
+
+    #[test]
+    fn test_from_sorted_iter() {
+        for size in 0..70 {
+            let tree: avltree::AVLTree<i32> = avltree::AVLTree::from_sorted_iter(0..size);
+            // Parsing the JSON checks the order, the balance and the heights.
+            assert!(avltree::AVLTree::<i32>::from_json(&tree.to_json()).is_ok());
+            assert_eq!(tree.range(0, size), (0..size).collect::<Vec<i32>>());
+            assert_eq!(tree.get_height(), 32 - (size as u32).leading_zeros());
+        }
+        let mut tree: avltree::AVLTree<i32> = avltree::AVLTree::from_sorted_iter((0..100).map(|number| number * 2));
+        tree.insert(51);
+        tree.delete(0);
+        assert!(avltree::AVLTree::<i32>::from_json(&tree.to_json()).is_ok());
+
+        assert!(avltree::AVLTree::<i32>::try_from_sorted_iter(0..100).is_ok());
+        assert_eq!(
+            avltree::AVLTree::<i32>::try_from_sorted_iter(vec![1, 3, 2]).err(),
+            Some("The key 2 comes after 3, the keys must be sorted".to_string())
+        );
+        assert_eq!(
+            avltree::AVLTree::<i32>::try_from_sorted_iter(vec![1, 2, 2]).err(),
+            Some("The key 2 is given twice".to_string())
+        );
+    }
+
+    #[test]
+    fn test_cached_metadata() {
+        use crate::base::NodeRef;
+        use crate::workload::{Distribution, Mix, Operation, Workload};
+
+        let mix = Mix { insert: 50, delete: 40, lookup: 10 };
+        for distribution in Distribution::ALL {
+            let workload = Workload::generate(distribution, 400, mix, 3);
+            let mut tree: avltree::AVLTree<i32> = avltree::AVLTree::new();
+            for operation in &workload.operations {
+                match *operation {
+                    Operation::Insert(key) => tree.insert(key),
+                    Operation::Delete(key) => tree.delete(key),
+                    Operation::Lookup(_) => continue,
+                }
+                // The cached answers against walks of the whole tree.
+                let root = tree.get_root();
+                assert_eq!(tree.get_height(), root.as_ref().map_or(0, |root| root.get_height()), "{:?}", distribution);
+                assert_eq!(tree.count_leaves(), root.as_ref().map_or(0, |root| root.count_leaves()));
+                assert_eq!(tree.count_nodes(), root.as_ref().map_or(0, |root| root.count_nodes()));
+            }
+        }
+        let tree: avltree::AVLTree<i32> = avltree::AVLTree::from_json(&avltree::AVLTree::<i32>::from_sorted_iter(0..100).to_json()).unwrap();
+        assert_eq!((tree.count_nodes(), tree.get_height(), tree.count_leaves()), (100, 7, 37));
+    }
+}
@@ -1,123 +1,451 @@
-use std::rc::Rc;
-use std::fmt::Debug;
-use std::cell::RefCell;
-use std::cmp::{max, Ordering};
+use core::fmt::{Debug, Write};
+use core::cmp::{max, Ordering};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
 
-pub trait TreeNode<T: Ord + Copy + Debug> {
-    fn get_left(&self) -> &Option<Rc<RefCell<Self>>>;
+use crate::frozen::FrozenTree;
+use crate::json::{self, JsonKey};
+use crate::svg::{self, NodeStyle};
+use crate::trace::TraceStep;
 
-    fn get_right(&self) -> &Option<Rc<RefCell<Self>>>;
+/// A node owning its children, such as the nodes of AVLTree and BSTree.
+///
+/// A reference to such a node is a `NodeRef`, which gives the node all
+/// the algorithms of `NodeRef`.
+pub trait TreeNode<T: Ord + Clone + Debug> {
+    fn get_left(&self) -> Option<&Self>;
+
+    fn get_right(&self) -> Option<&Self>;
 
     fn get_data(&self) -> T;
 
+    /// How this node is drawn by `Tree::to_svg`.
+    fn svg_style(&self) -> NodeStyle {
+        NodeStyle::default()
+    }
+
+    /// Extra fields written next to the key by `Tree::to_json`,
+    /// each value being already encoded as JSON.
+    fn json_attributes(&self) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
+}
+
+/// A handle that reads one node of a tree, whatever the way the tree stores
+/// its nodes: a shared pointer, a plain reference or an index in an arena.
+///
+/// Handles are cheap to make, and every algorithm reading a tree is written
+/// once here for all of them.
+pub trait NodeRef<T: Ord + Clone + Debug>: Sized {
+    fn key(&self) -> T;
+
+    fn left(&self) -> Option<Self>;
+
+    fn right(&self) -> Option<Self>;
+
+    /// Compares the key of this node with value.
+    fn compare(&self, value: &T) -> Ordering {
+        self.key().cmp(value)
+    }
+
+    /// How this node is drawn by `Tree::to_svg`.
+    fn svg_style(&self) -> NodeStyle {
+        NodeStyle::default()
+    }
+
+    /// Extra fields written next to the key by `Tree::to_json`,
+    /// each value being already encoded as JSON.
+    fn json_attributes(&self) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
+
     fn get_height(&self) -> u32 {
-        match (self.get_left(), self.get_right()) {
-            (Some(left), Some(right)) => {
-                max(left.borrow().get_height(), right.borrow().get_height()) + 1
-            }
-            (Some(left), _) => left.borrow().get_height() + 1,
-            (_, Some(right)) => right.borrow().get_height() + 1,
-            (_, _) => 1,
-        }
+        max(subtree_height(self.left()), subtree_height(self.right())) + 1
     }
 
     fn get_min(&self) -> T {
-        match self.get_left() {
-            None => self.get_data(),
-            Some(left) => left.borrow().get_min(),
+        let mut min: T = self.key();
+        let mut next: Option<Self> = self.left();
+        while let Some(node) = next {
+            min = node.key();
+            next = node.left();
         }
+        min
     }
 
     fn get_max(&self) -> T {
-        match self.get_right() {
-            None => self.get_data(),
-            Some(right) => right.borrow().get_max(),
+        let mut max: T = self.key();
+        let mut next: Option<Self> = self.right();
+        while let Some(node) = next {
+            max = node.key();
+            next = node.right();
         }
+        max
     }
 
     fn count_leaves(&self) -> u32 {
-        match (self.get_left(), self.get_right()) {
-            (Some(left), Some(right)) => {
-                left.borrow().count_leaves() + right.borrow().count_leaves()
-            }
-            (Some(left), _) => left.borrow().count_leaves(),
-            (_, Some(right)) => right.borrow().count_leaves(),
-            (_, _) => 1,
+        match (self.left(), self.right()) {
+            (None, None) => 1,
+            (left, right) => subtree_leaves(left) + subtree_leaves(right),
         }
     }
 
     fn count_nodes(&self) -> u32 {
-        match (self.get_left(), self.get_right()) {
-            (Some(left), Some(right)) => {
-                left.borrow().count_nodes() + right.borrow().count_nodes() + 1
-            }
-            (Some(left), _) => left.borrow().count_nodes() + 1,
-            (_, Some(right)) => right.borrow().count_nodes() + 1,
-            (_, _) => 1,
-        }
+        subtree_nodes(self.left()) + subtree_nodes(self.right()) + 1
     }
 
+    #[cfg(feature = "std")]
     fn traverse_inorder(&self) {
-        if let Some(left) = self.get_left() {
-            left.borrow().traverse_inorder();
-        }
-        println!("{:#?}", self.get_data());
-        if let Some(right) = self.get_right() {
-            right.borrow().traverse_inorder();
-        }
+        for_each_inorder(self.left(), &mut |node: &Self| println!("{:#?}", node.key()));
+        println!("{:#?}", self.key());
+        for_each_inorder(self.right(), &mut |node: &Self| println!("{:#?}", node.key()));
     }
 
+    #[cfg(feature = "std")]
     fn traverse_preorder(&self) {
-        println!("{:#?}", self.get_data());
-        if let Some(left) = self.get_left() {
-            left.borrow().traverse_preorder();
+        println!("{:#?}", self.key());
+        for_each_preorder(self.left(), &mut |node: &Self| println!("{:#?}", node.key()));
+        for_each_preorder(self.right(), &mut |node: &Self| println!("{:#?}", node.key()));
+    }
+
+    #[cfg(feature = "std")]
+    fn traverse_postorder(&self) {
+        for_each_postorder(self.left(), &mut |node: &Self| println!("{:#?}", node.key()));
+        for_each_postorder(self.right(), &mut |node: &Self| println!("{:#?}", node.key()));
+        println!("{:#?}", self.key());
+    }
+
+    fn contain(&self, value: T) -> bool {
+        let mut next: Option<Self> = match self.compare(&value) {
+            Ordering::Greater => self.left(),
+            Ordering::Less => self.right(),
+            Ordering::Equal => return true,
+        };
+        while let Some(node) = next {
+            next = match node.compare(&value) {
+                Ordering::Greater => node.left(),
+                Ordering::Less => node.right(),
+                Ordering::Equal => return true,
+            };
         }
-        if let Some(right) = self.get_right() {
-            right.borrow().traverse_preorder();
+        false
+    }
+
+    fn rank(&self, value: T) -> u32 {
+        let (mut rank, mut next) = rank_step(self, &value);
+        while let Some(node) = next {
+            let (smaller, further) = rank_step(&node, &value);
+            rank += smaller;
+            next = further;
         }
+        rank
     }
 
-    fn traverse_postorder(&self) {
-        if let Some(left) = self.get_left() {
-            left.borrow().traverse_postorder();
+    fn collect_range(&self, low: T, high: T, keys: &mut Vec<T>) {
+        let data: T = self.key();
+        if data > low {
+            collect_subtree_range(self.left(), &low, &high, keys);
+        }
+        let below_high: bool = data < high;
+        if low <= data && data <= high {
+            keys.push(data);
         }
-        if let Some(right) = self.get_right() {
-            right.borrow().traverse_postorder();
+        if below_high {
+            collect_subtree_range(self.right(), &low, &high, keys);
         }
-        println!("{:#?}", self.get_data());
     }
+}
 
-    fn contain(&self, value: T) -> bool {
-        match self.get_data().cmp(&value) {
-            Ordering::Greater => {
-                match self.get_left() {
-                    None => false,
-                    Some(left) => left.borrow().contain(value),
-                }
+// The algorithms of NodeRef walk the tree with a loop or an explicit stack
+// instead of recursing, so that a degenerate tree as deep as it is large
+// does not overflow the call stack.
+
+fn subtree_height<T: Ord + Clone + Debug, N: NodeRef<T>>(root: Option<N>) -> u32 {
+    let mut height: u32 = 0;
+    let mut stack: Vec<(N, u32)> = root.into_iter().map(|node| (node, 1)).collect();
+    while let Some((node, depth)) = stack.pop() {
+        height = max(height, depth);
+        stack.extend(node.left().into_iter().chain(node.right()).map(|child| (child, depth + 1)));
+    }
+    height
+}
+
+fn subtree_nodes<T: Ord + Clone + Debug, N: NodeRef<T>>(root: Option<N>) -> u32 {
+    let mut nodes: u32 = 0;
+    for_each_preorder(root, &mut |_: &N| nodes += 1);
+    nodes
+}
+
+fn subtree_leaves<T: Ord + Clone + Debug, N: NodeRef<T>>(root: Option<N>) -> u32 {
+    let mut leaves: u32 = 0;
+    for_each_preorder(root, &mut |node: &N| {
+        if node.left().is_none() && node.right().is_none() {
+            leaves += 1;
+        }
+    });
+    leaves
+}
+
+fn for_each_inorder<T: Ord + Clone + Debug, N: NodeRef<T>>(root: Option<N>, visit: &mut dyn FnMut(&N)) {
+    let mut stack: Vec<N> = Vec::new();
+    let mut next: Option<N> = root;
+    loop {
+        while let Some(node) = next {
+            next = node.left();
+            stack.push(node);
+        }
+        match stack.pop() {
+            None => return,
+            Some(node) => {
+                visit(&node);
+                next = node.right();
             }
-            Ordering::Less => {
-                match self.get_right() {
-                    None => false,
-                    Some(right) => right.borrow().contain(value),
+        }
+    }
+}
+
+fn for_each_preorder<T: Ord + Clone + Debug, N: NodeRef<T>>(root: Option<N>, visit: &mut dyn FnMut(&N)) {
+    let mut stack: Vec<N> = root.into_iter().collect();
+    while let Some(node) = stack.pop() {
+        visit(&node);
+        stack.extend(node.right());
+        stack.extend(node.left());
+    }
+}
+
+#[cfg(feature = "std")]
+fn for_each_postorder<T: Ord + Clone + Debug, N: NodeRef<T>>(root: Option<N>, visit: &mut dyn FnMut(&N)) {
+    // A node is visited when it comes off the stack the second time,
+    // once both its subtrees are done.
+    let mut stack: Vec<(N, bool)> = root.into_iter().map(|node| (node, false)).collect();
+    while let Some((node, children_done)) = stack.pop() {
+        if children_done {
+            visit(&node);
+        } else {
+            let (left, right) = (node.left(), node.right());
+            stack.push((node, true));
+            stack.extend(right.map(|child| (child, false)));
+            stack.extend(left.map(|child| (child, false)));
+        }
+    }
+}
+
+// The number of keys smaller than value found at node, and the child where
+// the search goes on. Counting the left subtree walks all of it.
+fn rank_step<T: Ord + Clone + Debug, N: NodeRef<T>>(node: &N, value: &T) -> (u32, Option<N>) {
+    match node.compare(value) {
+        Ordering::Greater => (0, node.left()),
+        Ordering::Equal => (subtree_nodes(node.left()), None),
+        Ordering::Less => (subtree_nodes(node.left()) + 1, node.right()),
+    }
+}
+
+fn collect_subtree_range<T: Ord + Clone + Debug, N: NodeRef<T>>(root: Option<N>, low: &T, high: &T, keys: &mut Vec<T>) {
+    let mut stack: Vec<N> = Vec::new();
+    let mut next: Option<N> = root;
+    loop {
+        while let Some(node) = next {
+            next = if node.key() > *low { node.left() } else { None };
+            stack.push(node);
+        }
+        match stack.pop() {
+            None => return,
+            Some(node) => {
+                let data: T = node.key();
+                next = if data < *high { node.right() } else { None };
+                if *low <= data && data <= *high {
+                    keys.push(data);
                 }
             }
-            Ordering::Equal => true,
         }
     }
 }
 
-pub trait Tree<T: Ord + Copy + Debug, TN: TreeNode<T>> {
-    fn get_root(&self) -> &Option<Rc<RefCell<TN>>>;
+impl<T: Ord + Clone + Debug, TN: TreeNode<T>> NodeRef<T> for &TN {
+    fn key(&self) -> T {
+        self.get_data()
+    }
+
+    fn left(&self) -> Option<Self> {
+        self.get_left()
+    }
+
+    fn right(&self) -> Option<Self> {
+        self.get_right()
+    }
+
+    fn svg_style(&self) -> NodeStyle {
+        TreeNode::svg_style(*self)
+    }
+
+    fn json_attributes(&self) -> Vec<(&'static str, String)> {
+        TreeNode::json_attributes(*self)
+    }
+}
+
+/// Renders the tree in the format of `Tree::render`, label giving the text
+/// written after the key of every node, such as its color.
+pub(crate) fn render_tree<T, N>(root: Option<N>, label: &dyn Fn(&N) -> String) -> String
+where
+    T: Ord + Clone + Debug,
+    N: NodeRef<T>,
+{
+    let mut output = String::new();
+    // Every node waits with the prefix of its line, its tag and its side.
+    let mut stack: Vec<(N, String, &str, bool)> = match root {
+        None => return "This tree is empty!\n".to_string(),
+        Some(root) => vec![(root, String::new(), "Root", false)],
+    };
+    while let Some((node, prefix_space, child_prefix, is_right)) = stack.pop() {
+        match prefix_space.len() {
+            6 => { let _ = writeln!(output, "|____ {} {:?}{}", child_prefix, node.key(), label(&node)); }
+            _ => { let _ = writeln!(output, "{}{} {:?}{}", prefix_space, child_prefix, node.key(), label(&node)); }
+        }
+        let mut new_prefix_space: String = prefix_space.clone();
+        match (is_right, prefix_space.len()) {
+            (false, 0) => new_prefix_space.push_str("|     "),
+            (false, length) => new_prefix_space.replace_range(length - 6..length, "|     |____ "),
+            (true, length) => new_prefix_space.replace_range(length - 6..length, "      |____ "),
+        }
+        if let Some(right) = node.right() {
+            stack.push((right, new_prefix_space.clone(), "R", true));
+        }
+        if let Some(left) = node.left() {
+            stack.push((left, new_prefix_space, "L", false));
+        }
+    }
+    output
+}
+
+/// Collects the keys given to a `try_from_sorted_iter` constructor, which
+/// must be in strictly ascending order.
+pub(crate) fn collect_sorted<T: Ord + Debug>(keys: impl IntoIterator<Item = T>) -> Result<Vec<T>, String> {
+    let keys: Vec<T> = keys.into_iter().collect();
+    for pair in keys.windows(2) {
+        match pair[0].cmp(&pair[1]) {
+            Ordering::Less => {}
+            Ordering::Equal => return Err(format!("The key {:?} is given twice", pair[0])),
+            Ordering::Greater => return Err(format!("The key {:?} comes after {:?}, the keys must be sorted", pair[1], pair[0])),
+        }
+    }
+    Ok(keys)
+}
+
+pub trait Tree<T: Ord + Clone + Debug> {
+    /// The handle through which the default methods read the nodes.
+    type Node<'a>: NodeRef<T>
+    where
+        Self: 'a;
+
+    fn get_root(&self) -> Option<Self::Node<'_>>;
 
     fn insert(&mut self, data: T);
 
     fn delete(&mut self, data: T);
 
+    /// Prints the tree to standard output. Like the traversals, it needs the
+    /// `std` feature.
+    #[cfg(feature = "std")]
     fn print_tree(&self);
 
+    /// Renders the tree as text, in the format used by `print_tree`.
+    fn render(&self) -> String;
+
+    /// Inserts an element and returns the steps taken to do it.
+    ///
+    /// This function can be used in RBTree, AVLTree and BSTree.
+    /// RBTree and AVLTree report every rebalancing case they apply,
+    /// the last step always holding the final shape of the tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trees::rbtree::RBTree;
+    /// use crate::trees::base::Tree;
+    ///
+    /// let mut tree = RBTree::new();
+    /// tree.insert(1);
+    /// tree.insert(2);
+    /// let steps = tree.insert_traced(3);
+    ///
+    /// let cases: Vec<&str> = steps.iter().map(|step| step.case.as_str()).collect();
+    /// assert_eq!(cases, vec!["insert as red leaf", "3.3.1 child is right child"]);
+    /// assert_eq!(steps.last().unwrap().snapshot, tree.render());
+    /// ```
+    fn insert_traced(&mut self, data: T) -> Vec<TraceStep<T>> {
+        self.insert(data.clone());
+        vec![TraceStep { case: "insert".to_string(), nodes: vec![data], snapshot: self.render() }]
+    }
+
+    /// Removes an element and returns the steps taken to do it.
+    ///
+    /// This function can be used in RBTree, AVLTree and BSTree.
+    /// See `insert_traced`.
+    fn delete_traced(&mut self, data: T) -> Vec<TraceStep<T>> {
+        self.delete(data.clone());
+        vec![TraceStep { case: "delete".to_string(), nodes: vec![data], snapshot: self.render() }]
+    }
+
+    /// Renders the tree as a standalone SVG document.
+    ///
+    /// This function can be used in RBTree, AVLTree and BSTree.
+    /// Red-black nodes are filled with their color and AVL nodes
+    /// are annotated with their height and balance factor.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trees::rbtree::RBTree;
+    /// use crate::trees::base::Tree;
+    ///
+    /// let mut tree = RBTree::new();
+    /// tree.insert(1);
+    /// tree.insert(0);
+    /// tree.insert(2);
+    ///
+    /// let svg = tree.to_svg();
+    /// assert!(svg.starts_with("<svg"));
+    /// assert_eq!(svg.matches("<circle").count(), 3);
+    /// ```
+    fn to_svg(&self) -> String {
+        svg::render(self.get_root())
+    }
+
+    /// Exports the shape of the tree as nested JSON.
+    ///
+    /// This function can be used in RBTree, AVLTree and BSTree.
+    /// Every node is written as `{"key":..,"left":..,"right":..}`, a missing
+    /// child being `null`. Red-black nodes also carry their `color` and
+    /// AVL nodes their `height`. An empty tree is written as `null`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trees::rbtree::RBTree;
+    /// use crate::trees::base::Tree;
+    ///
+    /// let mut tree = RBTree::new();
+    /// tree.insert(1);
+    /// tree.insert(2);
+    ///
+    /// assert_eq!(
+    ///     tree.to_json(),
+    ///     r#"{"key":1,"color":"black","left":null,"right":{"key":2,"color":"red","left":null,"right":null}}"#
+    /// );
+    /// ```
+    fn to_json(&self) -> String
+    where
+        T: JsonKey,
+    {
+        json::write_tree(self.get_root())
+    }
+
     /// The height of the tree.
     /// 
     /// This function can be used in RBTree, AVLTree and BSTree.
+    /// The default walks the whole tree, AVLTree and RBTree keep the
+    /// height of every node and answer in constant time.
     /// 
     /// Returns to the height.
     /// 
@@ -136,10 +464,7 @@ pub trait Tree<T: Ord + Copy + Debug, TN: TreeNode<T>> {
     /// assert_eq!(tree.get_height(), 2);
     /// ```
     fn get_height(&self) -> u32 {
-        match &self.get_root() {
-            None => 0,
-            Some(node) => node.borrow().get_height(),
-        }
+        self.get_root().map_or(0, |node| node.get_height())
     }
 
     /// The minimum element of the tree.
@@ -163,10 +488,7 @@ pub trait Tree<T: Ord + Copy + Debug, TN: TreeNode<T>> {
     /// assert_eq!(tree.get_min(), Some(0));
     /// ```
     fn get_min(&self) -> Option<T> {
-        match &self.get_root() {
-            None => None,
-            Some(node) => Some(node.borrow().get_min()),
-        }
+        self.get_root().map(|node| node.get_min())
     }
 
     /// The maximum element of the tree.
@@ -190,15 +512,14 @@ pub trait Tree<T: Ord + Copy + Debug, TN: TreeNode<T>> {
     /// assert_eq!(tree.get_max(), Some(2));
     /// ```
     fn get_max(&self) -> Option<T> {
-        match &self.get_root() {
-            None => None,
-            Some(node) => Some(node.borrow().get_max()),
-        }
+        self.get_root().map(|node| node.get_max())
     }
 
     /// Returns the number of leaf nodes in the tree.
     ///
     /// This function can be used in RBTree, AVLTree and BSTree.
+    /// The default walks the whole tree, AVLTree and RBTree keep the
+    /// leaves below every node and answer in constant time.
     /// 
     /// # Examples
     ///
@@ -213,15 +534,14 @@ pub trait Tree<T: Ord + Copy + Debug, TN: TreeNode<T>> {
     /// assert_eq!(tree.count_leaves(), 1);
     /// ```
     fn count_leaves(&self) -> u32 {
-        match &self.get_root() {
-            None => 0,
-            Some(node) => node.borrow().count_leaves(),
-        }
+        self.get_root().map_or(0, |node| node.count_leaves())
     }
 
     /// Returns the number of elements in the tree.
     ///
     /// This function can be used in RBTree, AVLTree and BSTree.
+    /// The default walks the whole tree, the trees of this crate keep
+    /// their count and answer in constant time.
     /// 
     /// # Examples
     ///
@@ -235,10 +555,7 @@ pub trait Tree<T: Ord + Copy + Debug, TN: TreeNode<T>> {
     /// assert_eq!(tree.count_nodes(), 1);
     /// ```
     fn count_nodes(&self) -> u32 {
-        match &self.get_root() {
-            None => 0,
-            Some(node) => node.borrow().count_nodes(),
-        }
+        self.get_root().map_or(0, |node| node.count_nodes())
     }
 
     /// Inorder traverse iterator of tree.
@@ -266,11 +583,11 @@ pub trait Tree<T: Ord + Copy + Debug, TN: TreeNode<T>> {
     /// // And we should get the following sequence of its elements: 1, 2, 3, 4, 5, 6, 7
     /// tree.traverse_inorder();
     /// ```
+    #[cfg(feature = "std")]
     fn traverse_inorder(&self) {
-        match &self.get_root() {
-            None => { println!("This tree is empty!");
-            return },
-            Some(node) => node.borrow().traverse_inorder(),
+        match self.get_root() {
+            None => println!("This tree is empty!"),
+            Some(node) => node.traverse_inorder(),
         }
     }
 
@@ -299,11 +616,11 @@ pub trait Tree<T: Ord + Copy + Debug, TN: TreeNode<T>> {
     /// // And we should get the following sequence of its elements: 2, 1, 4, 3, 6, 5, 7
     /// tree.traverse_preorder();
     /// ```
+    #[cfg(feature = "std")]
     fn traverse_preorder(&self) {
-        match &self.get_root() {
-            None => { println!("This tree is empty!");
-            return },
-            Some(node) => node.borrow().traverse_preorder(),
+        match self.get_root() {
+            None => println!("This tree is empty!"),
+            Some(node) => node.traverse_preorder(),
         }
     }
 
@@ -332,11 +649,11 @@ pub trait Tree<T: Ord + Copy + Debug, TN: TreeNode<T>> {
     /// // And we should get the following sequence of its elements: 1, 3, 5, 7, 6, 4, 2
     /// tree.traverse_postorder();
     /// ```
+    #[cfg(feature = "std")]
     fn traverse_postorder(&self) {
-        match &self.get_root() {
-            None => { println!("This tree is empty!");
-            return },
-            Some(node) => node.borrow().traverse_postorder(),
+        match self.get_root() {
+            None => println!("This tree is empty!"),
+            Some(node) => node.traverse_postorder(),
         }
     }
 
@@ -363,10 +680,91 @@ pub trait Tree<T: Ord + Copy + Debug, TN: TreeNode<T>> {
     /// assert!(!tree.contain(999));
     /// ```
     fn contain(&self, value: T) -> bool {
-        match &self.get_root() {
-            None => false,
-            Some(node) => node.borrow().contain(value),
+        self.get_root().is_some_and(|node| node.contain(value))
+    }
+
+    /// Returns the elements between low and high (both included), in ascending order.
+    ///
+    /// This function can be used in RBTree, AVLTree and BSTree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trees::rbtree::RBTree;
+    /// use crate::trees::base::Tree;
+    ///
+    /// let mut tree = RBTree::new();
+    /// for i in [8, 3, 10, 1, 6, 14, 4, 7, 13] {
+    ///     tree.insert(i);
+    /// }
+    ///
+    /// assert_eq!(tree.range(4, 10), vec![4, 6, 7, 8, 10]);
+    /// assert!(tree.range(15, 20).is_empty());
+    /// ```
+    fn range(&self, low: T, high: T) -> Vec<T> {
+        let mut keys: Vec<T> = Vec::new();
+        if let Some(node) = self.get_root() {
+            node.collect_range(low, high, &mut keys);
         }
+        keys
+    }
+
+    /// Returns the number of elements smaller than value, which is the position
+    /// value has (or would have) in the inorder sequence.
+    ///
+    /// This function can be used in RBTree, AVLTree and BSTree.
+    /// The nodes do not keep the size of their subtrees, so the left subtree
+    /// of every node on the search path is walked: unlike `count_nodes`,
+    /// `get_height` and `count_leaves`, rank takes time linear in the size of
+    /// the tree. The rank of a `FrozenTree` counts its subtrees without
+    /// walking them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trees::rbtree::RBTree;
+    /// use crate::trees::base::Tree;
+    ///
+    /// let mut tree = RBTree::new();
+    /// for i in [10, 20, 30, 40] {
+    ///     tree.insert(i);
+    /// }
+    ///
+    /// assert_eq!(tree.rank(10), 0);
+    /// assert_eq!(tree.rank(30), 2);
+    /// assert_eq!(tree.rank(35), 3);
+    /// assert_eq!(tree.rank(99), 4);
+    /// ```
+    fn rank(&self, value: T) -> u32 {
+        self.get_root().map_or(0, |node| node.rank(value))
+    }
+
+    /// Copies the keys into a `FrozenTree`, an immutable array laid out for
+    /// fast lookups. Later changes to the tree do not reach the copy.
+    ///
+    /// This function can be used in RBTree, AVLTree and BSTree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trees::avltree::AVLTree;
+    /// use crate::trees::base::Tree;
+    ///
+    /// let mut tree = AVLTree::new();
+    /// for i in [10, 20, 30, 40] {
+    ///     tree.insert(i);
+    /// }
+    /// let frozen = tree.freeze();
+    /// tree.delete(20);
+    ///
+    /// assert_eq!(frozen.len(), 4);
+    /// assert!(frozen.contain(20));
+    /// assert_eq!(frozen.rank(30), 2);
+    /// ```
+    fn freeze(&self) -> FrozenTree<T> {
+        let mut keys: Vec<T> = Vec::new();
+        for_each_inorder(self.get_root(), &mut |node: &Self::Node<'_>| keys.push(node.key()));
+        FrozenTree::from_sorted(keys)
     }
 
     /// Сhecking if the tree is empty.
@@ -386,10 +784,7 @@ pub trait Tree<T: Ord + Copy + Debug, TN: TreeNode<T>> {
     /// assert!(!tree.is_empty());
     /// ```
     fn is_empty(&self) -> bool {
-        match &self.get_root() {
-            Some(_) => false,
-            None => true,
-        }
+        self.get_root().is_none()
     }
 
 }
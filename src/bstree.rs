@@ -1,38 +1,41 @@
-use std::cell::RefCell;
-use std::rc::Rc;
-use std::fmt::Debug;
-use std::cmp::{Ord, Ordering};
+use core::fmt::Debug;
+use core::cmp::{Ord, Ordering};
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
 
-use crate::base::{TreeNode, Tree};
+use crate::base::{self, NodeRef, TreeNode, Tree};
+use crate::json::{self, FromJson, JsonKey, JsonNode};
 
-type RcRefcellBSNode<T>= Rc<RefCell<BSTreeNode<T>>>;
-type OptionNode<T>= Option<RcRefcellBSNode<T>>;
+type BoxBSNode<T> = Box<BSTreeNode<T>>;
+type OptionNode<T> = Option<BoxBSNode<T>>;
 
-pub struct BSTreeNode<T: Ord + Copy + Debug> {
+pub struct BSTreeNode<T: Ord + Clone + Debug> {
     key: T,
     left: OptionNode<T>,
     right: OptionNode<T>,
 }
 
-pub struct BSTree<T: Ord + Copy + Debug> {
-    root: OptionNode<T>
+pub struct BSTree<T: Ord + Clone + Debug> {
+    root: OptionNode<T>,
+    len: u32,
 }
 
-impl<T: Ord + Copy + Debug> TreeNode<T> for BSTreeNode<T> {
-    fn get_left(&self) -> &OptionNode<T> {
-        &self.left
+impl<T: Ord + Clone + Debug> TreeNode<T> for BSTreeNode<T> {
+    fn get_left(&self) -> Option<&Self> {
+        self.left.as_deref()
     }
 
-    fn get_right(&self) -> &OptionNode<T> {
-        &self.right
+    fn get_right(&self) -> Option<&Self> {
+        self.right.as_deref()
     }
 
     fn get_data(&self) -> T {
-        self.key
+        self.key.clone()
     }
 }
 
-impl <T: Ord + Copy + Debug> BSTreeNode<T> {
+impl <T: Ord + Clone + Debug> BSTreeNode<T> {
     fn new(value: T) -> BSTreeNode<T> {
         BSTreeNode {
             key: value,
@@ -41,179 +44,248 @@ impl <T: Ord + Copy + Debug> BSTreeNode<T> {
         }
     }
 
-    fn insert(&mut self, value: T) {
-        if self.key == value {
-            println!("The node already exists.");
-            return
-        }
-        let new_node =
-            if value < self.key {&mut self.left}
-            else {&mut self.right};
-        match new_node {
-            Some(node) => node.borrow_mut().insert(value),
-            None => {
-                *new_node = Some(Rc::new(RefCell::new(BSTreeNode::new(value))));
+    // Walks down to the empty slot where value belongs instead of recursing,
+    // so that inserting sorted keys into a chain does not overflow the stack.
+    // Returns whether value was inserted.
+    fn insert(&mut self, value: T) -> bool {
+        let mut node: &mut BSTreeNode<T> = self;
+        loop {
+            let slot: &mut OptionNode<T> = match value.cmp(&node.key) {
+                Ordering::Equal => {
+                    #[cfg(feature = "std")]
+                    println!("The node already exists.");
+                    return false
+                }
+                Ordering::Less => &mut node.left,
+                Ordering::Greater => &mut node.right,
+            };
+            match slot {
+                Some(child) => node = child,
+                None => {
+                    *slot = Some(Box::new(BSTreeNode::new(value)));
+                    return true
+                }
             }
         }
     }
 
-    fn delete(&mut self, value: T) {
-        match self.key.cmp(&value) {
-            Ordering::Greater => self._delete_left(value),
-            Ordering::Less => self._delete_right(value),
-            _ => unreachable!(),
+    fn _delete_node_have_two_children(node: &mut BSTreeNode<T>) {
+        // The successor has no left child, so its right child takes its place.
+        let mut slot: &mut OptionNode<T> = &mut node.right;
+        while slot.as_ref().is_some_and(|child| child.left.is_some()) {
+            slot = &mut slot.as_mut().unwrap().left;
         }
+        let mut successor: BoxBSNode<T> = slot.take().unwrap();
+        *slot = successor.right.take();
+        node.key = successor.key;
     }
 
-    fn _delete_node_have_two_children(left: &RcRefcellBSNode<T>) {
-        let right_min = left.borrow().right.as_ref().unwrap().borrow().get_min();
-        left.borrow_mut().delete(right_min);
-        left.borrow_mut().key = right_min;
-    }
-
-    fn _delete_right(&mut self, value: T) {
-        if let Some(right) = self.right.as_ref() {
-            if right.borrow().key == value {
-                if right.borrow().left.is_none() && right.borrow().right.is_none() {
-                    self.right = None;
-                } else if right.borrow().left.is_none() && !right.borrow().right.is_none() {
-                    self.right.take().map(|node| {
-                        self.right = node.borrow().right.clone()
-                    });
-                } else if !right.borrow().left.is_none() && right.borrow().right.is_none() {
-                    self.right.take().map(|node| {
-                        self.right = node.borrow().left.clone()
-                    });
-                } else {
-                    Self::_delete_node_have_two_children(right);
-                }
+    // Removes the node holding value from the subtree in slot, walking down
+    // to it instead of recursing.
+    fn _delete_child(slot: &mut OptionNode<T>, value: T) {
+        let mut slot: &mut OptionNode<T> = slot;
+        while slot.as_ref().is_some_and(|child| child.key != value) {
+            let child: &mut BSTreeNode<T> = slot.as_mut().unwrap();
+            slot = if value < child.key { &mut child.left } else { &mut child.right };
+        }
+        if let Some(child) = slot.as_mut() {
+            if child.left.is_none() && child.right.is_none() {
+                *slot = None;
+            } else if child.left.is_none() && child.right.is_some() {
+                *slot = child.right.take();
+            } else if child.left.is_some() && child.right.is_none() {
+                *slot = child.left.take();
             } else {
-                right.borrow_mut().delete(value);
+                Self::_delete_node_have_two_children(child);
             }
         }
     }
 
-    fn _delete_left(&mut self, value: T) {
-        if let Some(left) = self.left.as_ref() {
-            if left.borrow().key == value {
-                if left.borrow().left.is_none() && left.borrow().right.is_none() {
-                    self.left = None;
-                } else if left.borrow().left.is_none() && !left.borrow().right.is_none() {
-                    self.left.take().map(|node| {
-                        self.left = node.borrow().right.clone()
-                    });
-                } else if !left.borrow().left.is_none() && left.borrow().right.is_none() {
-                    self.left.take().map(|node| {
-                        self.left = node.borrow().left.clone()
-                    });
-                } else {
-                    Self::_delete_node_have_two_children(left);
-                }
-            } else {
-                left.borrow_mut().delete(value);
-            }
+    // Frees the nodes one by one, as a degenerate tree nests its boxes as
+    // deep as it is large and their default drop would recurse that deep.
+    fn drop_nodes(root: OptionNode<T>) {
+        let mut stack: Vec<BoxBSNode<T>> = root.into_iter().collect();
+        while let Some(mut node) = stack.pop() {
+            stack.extend(node.left.take());
+            stack.extend(node.right.take());
         }
     }
 
-    fn print_node(&self, prefix_space: &String, child_prefix: String, is_right: bool) {
-        match prefix_space.len() {
-            6 => println!("|____ {} {:?}", child_prefix, self.key),
-            _ => println!("{}{} {:?}", prefix_space, child_prefix, self.key),
-        }
-        let mut new_prefix_space: String = String::from(prefix_space);
-        match is_right {
-            false => {
-                match prefix_space.len() {
-                    0 => new_prefix_space.push_str("|     "),
-                    _ => new_prefix_space.replace_range(
-                        prefix_space.len() - 6..prefix_space.len(),
-                        "|     |____ ",
-                    ),
-                }
-            }
-            true => new_prefix_space.replace_range(
-                prefix_space.len() - 6..prefix_space.len(),
-                "      |____ ",
-            ),
-        }
-        if let Some(left) = self.get_left() {
-            left.borrow().print_node(
-                &new_prefix_space,
-                "L".to_string(),
-                false,
-            );
-        }
-        if let Some(right) = self.get_right() {
-            right.borrow().print_node(
-                &new_prefix_space,
-                "R".to_string(),
-                true,
-            );
+    // Builds a balanced subtree from the next count keys, which come in
+    // ascending order: the smaller half goes left and the larger half right.
+    fn from_sorted(keys: &mut alloc::vec::IntoIter<T>, count: usize) -> OptionNode<T> {
+        if count == 0 {
+            return None;
         }
+        let left: OptionNode<T> = Self::from_sorted(keys, (count - 1) / 2);
+        let mut node: BSTreeNode<T> = BSTreeNode::new(keys.next().unwrap());
+        node.left = left;
+        node.right = Self::from_sorted(keys, count / 2);
+        Some(Box::new(node))
+    }
+
+    // Builds a node of a parsed JSON tree on its built children, for `json::fold`.
+    fn from_json_node(shape: &JsonNode<T>, left: OptionNode<T>, right: OptionNode<T>) -> Result<BoxBSNode<T>, String> {
+        Ok(Box::new(BSTreeNode { key: shape.key.clone(), left, right }))
     }
 }
 
-impl <T: Ord + Copy + Debug> Tree<T, BSTreeNode<T>> for BSTree<T> {
-    fn get_root(&self) -> &OptionNode<T> {
-        &self.root
+impl <T: Ord + Clone + Debug> Tree<T> for BSTree<T> {
+    type Node<'a> = &'a BSTreeNode<T> where Self: 'a;
+
+    fn get_root(&self) -> Option<&BSTreeNode<T>> {
+        self.root.as_deref()
     }
 
     fn insert(&mut self, value: T) {
-        if self.root.is_none() {
-            self.root = Some(Rc::new(RefCell::new(BSTreeNode::new(value))));
-        } else {
-            self.root.as_ref().unwrap().borrow_mut().insert(value);
+        let inserted: bool = match &mut self.root {
+            None => {
+                self.root = Some(Box::new(BSTreeNode::new(value)));
+                true
+            }
+            Some(root) => root.insert(value),
+        };
+        if inserted {
+            self.len += 1;
         }
     }
 
     fn delete(&mut self, value:T){
-        if self.root.is_none()||!self.contain(value) {
+        if self.root.is_none()||!self.contain(value.clone()) {
+            #[cfg(feature = "std")]
             println!("The node of value {:#?} doesn't exist.",value);
-            return ;
         }else {
-            if let Some(root) = self.root.as_ref() {
-                if root.borrow().key == value {
-                    if root.borrow().left.is_none() && root.borrow().right.is_none() {
-                        self.root = None;
-                    } else if root.borrow().left.is_none() && !root.borrow().right.is_none() {
-                        self.root.take().map(|node| {
-                            self.root = node.borrow().right.clone()
-                        });
-                    } else if !root.borrow().left.is_none() && root.borrow().right.is_none() {
-                        self.root.take().map(|node| {
-                            self.root = node.borrow().left.clone()
-                        });
-                    } else {
-                        BSTreeNode::_delete_node_have_two_children(root);
-                    }
-                } else {
-                    root.borrow_mut().delete(value);
-                }
-            }
+            BSTreeNode::_delete_child(&mut self.root, value);
+            self.len -= 1;
         }
     }
 
+    #[cfg(feature = "std")]
     fn print_tree(&self) {
-        match &self.get_root() {
-            None => println!("This tree is empty!"),
-            Some(root) => root.borrow().print_node(
-                &"".to_string(),
-                "Root".to_string(),
-                false,
-            ),
-        }
+        print!("{}", self.render());
+    }
+
+    fn render(&self) -> String {
+        base::render_tree(self.get_root(), &|_| String::new())
+    }
+
+    /// The number of elements, kept by the tree.
+    fn count_nodes(&self) -> u32 {
+        self.len
+    }
+}
+
+impl<T: Ord + Clone + Debug> Default for BSTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord + Clone + Debug> Drop for BSTree<T> {
+    fn drop(&mut self) {
+        BSTreeNode::drop_nodes(self.root.take());
+    }
+}
+
+impl<T: Ord + Clone + Debug + JsonKey> FromJson for BSTree<T> {
+    fn from_json(text: &str) -> Result<Self, String> {
+        Self::from_json(text)
     }
 }
 
-impl<T:Ord + Copy +Debug> BSTree<T> {
+impl<T: Ord + Clone + Debug> BSTree<T> {
     pub fn new() -> Self {
         BSTree {
-            root:None,
+            root: None,
+            len: 0,
         }
     }
     
+    /// Rebuilds a BSTree from the JSON written by `Tree::to_json`.
+    ///
+    /// The keys must be in binary search tree order, extra fields such as
+    /// the color of a red-black node are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use trees::bstree::BSTree;
+    /// use crate::trees::base::Tree;
+    ///
+    /// let tree: BSTree<i32> = BSTree::from_json(r#"{"key":2,"left":{"key":1},"right":null}"#).unwrap();
+    /// assert_eq!(tree.count_nodes(), 2);
+    ///
+    /// assert!(BSTree::<i32>::from_json(r#"{"key":2,"left":{"key":3}}"#).is_err());
+    /// ```
+    pub fn from_json(text: &str) -> Result<Self, String>
+    where
+        T: JsonKey,
+    {
+        let root: OptionNode<T> = match json::parse_tree::<T>(text)? {
+            None => None,
+            Some(root) => Some(json::fold(&root, BSTreeNode::from_json_node)?),
+        };
+        let len: u32 = root.as_deref().map_or(0, |root| root.count_nodes());
+        Ok(BSTree { root, len })
+    }
+
+    /// Builds a balanced BSTree from keys in strictly ascending order, in
+    /// linear time, where inserting them one by one would build a chain.
+    ///
+    /// The keys are not checked, unsorted or repeated keys giving a tree
+    /// that is not a search tree; see `try_from_sorted_iter`.
+    ///
+    /// # Examples
+    /// ```
+    /// use trees::bstree::BSTree;
+    /// use crate::trees::base::Tree;
+    ///
+    /// let tree: BSTree<i32> = BSTree::from_sorted_iter(0..1000);
+    /// assert_eq!(tree.get_height(), 10);
+    /// assert!(tree.contain(999));
+    /// ```
+    pub fn from_sorted_iter<I: IntoIterator<Item = T>>(keys: I) -> Self {
+        let keys: Vec<T> = keys.into_iter().collect();
+        let count: usize = keys.len();
+        BSTree {
+            root: BSTreeNode::from_sorted(&mut keys.into_iter(), count),
+            len: count as u32,
+        }
+    }
+
+    /// Builds a balanced BSTree like `from_sorted_iter`, failing when the
+    /// keys are not in strictly ascending order.
+    ///
+    /// # Examples
+    /// ```
+    /// use trees::bstree::BSTree;
+    ///
+    /// assert!(BSTree::try_from_sorted_iter(vec!["a", "b"]).is_ok());
+    /// assert!(BSTree::try_from_sorted_iter(vec!["b", "a"]).is_err());
+    /// ```
+    pub fn try_from_sorted_iter<I: IntoIterator<Item = T>>(keys: I) -> Result<Self, String> {
+        Ok(Self::from_sorted_iter(base::collect_sorted(keys)?))
+    }
+
     pub fn clear(&mut self) {
-        *self = BSTree::new();
+        BSTreeNode::drop_nodes(self.root.take());
+        self.len = 0;
+        #[cfg(feature = "std")]
         println!("Clear operation is complete!");
     }
 }
+
+#[cfg(test)]
+impl<T: Ord + Clone + Debug> BSTree<T> {
+    /// The chain that inserting ascending keys builds, made without walking
+    /// down the chain for every key, which would take quadratic time.
+    pub(crate) fn ascending_chain(keys: Vec<T>) -> Self {
+        let len: u32 = keys.len() as u32;
+        let mut root: OptionNode<T> = None;
+        for key in keys.into_iter().rev() {
+            let mut node: BSTreeNode<T> = BSTreeNode::new(key);
+            node.right = root;
+            root = Some(Box::new(node));
+        }
+        BSTree { root, len }
+    }
+}
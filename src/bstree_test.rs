@@ -1,7 +1,12 @@
 #[cfg(test)]
 mod test {
+    extern crate std;
+
     use crate::base::Tree;
     use crate::bstree;
+    use std::string::String;
+    use std::vec;
+    use std::vec::Vec;
 
     #[test]
     fn test_bstree() {
@@ -41,4 +46,126 @@ mod test {
         }
         assert_eq!(bs_tree.is_empty(), true);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_range() {
+        let mut bs_tree: bstree::BSTree<i32> = bstree::BSTree::new();
+        for number in [0, 1, 2, 3, 4, 5, 6, 7, 8, 9] {
+            bs_tree.insert(number);
+        }
+        assert_eq!(bs_tree.range(3, 6), vec![3, 4, 5, 6]);
+        assert_eq!(bs_tree.range(-5, 0), vec![0]);
+        assert!(bs_tree.range(6, 3).is_empty());
+    }
+
+    #[test]
+    fn test_rank() {
+        let mut bs_tree: bstree::BSTree<i32> = bstree::BSTree::new();
+        for number in [0, 1, 2, 3, 4, 5, 6, 7, 8, 9] {
+            bs_tree.insert(number);
+        }
+        assert_eq!(bs_tree.rank(0), 0);
+        assert_eq!(bs_tree.rank(7), 7);
+        assert_eq!(bs_tree.rank(100), 10);
+    }
+
+    #[test]
+    fn test_send_sync() {
+        fn assert_send_sync<S: Send + Sync>() {}
+        assert_send_sync::<bstree::BSTree<String>>();
+
+        let mut tree: bstree::BSTree<i32> = bstree::BSTree::new();
+        for number in [5, 2, 8, 1, 9, 3] {
+            tree.insert(number);
+        }
+        let tree = std::sync::Arc::new(tree);
+        let readers: Vec<_> = (0..4)
+            .map(|reader| {
+                let tree = std::sync::Arc::clone(&tree);
+                std::thread::spawn(move || tree.contain(reader * 3) as u32 + tree.count_nodes())
+            })
+            .collect();
+        let found: Vec<u32> = readers.into_iter().map(|reader| reader.join().unwrap()).collect();
+        assert_eq!(found, vec![6, 7, 6, 7]);
+        let mut tree = std::thread::spawn(move || std::sync::Arc::try_unwrap(tree).ok().unwrap()).join().unwrap();
+        tree.delete(5);
+        assert_eq!(tree.count_nodes(), 5);
+    }
+
+    #[test]
+    fn test_sorted_keys_do_not_overflow() {
+        // Sorted keys make a chain as deep as the tree is large, which the
+        // recursive walks used to overflow the stack on.
+        let size: i32 = 20_000;
+        let mut tree: bstree::BSTree<i32> = bstree::BSTree::new();
+        for number in 0..size {
+            tree.insert(number);
+        }
+        assert_eq!(tree.get_height(), size as u32);
+        for number in (0..size).step_by(2) {
+            tree.delete(number);
+        }
+        assert_eq!(tree.count_nodes(), size as u32 / 2);
+        assert_eq!(tree.get_min(), Some(1));
+    }
+
+    #[test]
+    fn test_million_node_chain() {
+        // Inserting a million sorted keys takes quadratic time, so the chain
+        // they would make is built directly.
+        let size: i32 = 1_000_000;
+        let mut tree: bstree::BSTree<i32> = bstree::BSTree::ascending_chain((0..size).collect());
+        assert_eq!(tree.get_height(), size as u32);
+        assert_eq!(tree.get_min(), Some(0));
+        assert_eq!(tree.get_max(), Some(size - 1));
+        assert_eq!(tree.count_nodes(), size as u32);
+        assert_eq!(tree.count_leaves(), 1);
+        assert!(tree.contain(size - 1));
+        assert_eq!(tree.rank(size - 10), size as u32 - 10);
+        assert_eq!(tree.range(size - 3, size + 3), vec![size - 3, size - 2, size - 1]);
+
+        tree.insert(size);
+        assert_eq!(tree.get_max(), Some(size));
+        tree.delete(size);
+        tree.delete(size / 2);
+        assert!(!tree.contain(size / 2));
+        assert_eq!(tree.get_height(), size as u32 - 1);
+
+        // Writing, reading and drawing the chain do not recurse either.
+        let json: String = tree.to_json();
+        let copy: bstree::BSTree<i32> = bstree::BSTree::from_json(&json).unwrap();
+        assert_eq!(copy.get_height(), size as u32 - 1);
+        assert_eq!(copy.count_nodes(), size as u32 - 1);
+        assert_eq!(copy.to_json(), json);
+        let svg: String = tree.to_svg();
+        assert_eq!(svg.matches("<circle").count(), size as usize - 1);
+    }
+
+    #[test]
+    fn test_drop_million_node_chain() {
+        let size: i32 = 1_000_000;
+        let tree: bstree::BSTree<i32> = bstree::BSTree::ascending_chain((0..size).collect());
+        drop(tree);
+
+        let mut tree: bstree::BSTree<i32> = bstree::BSTree::ascending_chain((0..size).collect());
+        tree.clear();
+        assert!(tree.is_empty());
+        tree.insert(1);
+        assert_eq!(tree.count_nodes(), 1);
+    }
+
+    #[test]
+    fn test_from_sorted_iter() {
+        let size: i32 = 1_000_000;
+        let mut tree: bstree::BSTree<i32> = bstree::BSTree::from_sorted_iter(0..size);
+        assert_eq!(tree.get_height(), 20);
+        assert_eq!(tree.count_nodes(), size as u32);
+        assert_eq!(tree.rank(size / 3), size as u32 / 3);
+        tree.delete(size / 2);
+        assert!(!tree.contain(size / 2));
+        assert!(bstree::BSTree::<i32>::from_json(&bstree::BSTree::from_sorted_iter(0..100).to_json()).is_ok());
+
+        assert!(bstree::BSTree::try_from_sorted_iter(vec![1, 2, 3]).is_ok());
+        assert!(bstree::BSTree::try_from_sorted_iter(vec![1, 1]).is_err());
+    }
+}
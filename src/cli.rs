@@ -1,326 +1,443 @@
-use trees::bstree::BSTree;
-use trees::rbtree::RBTree;
-use trees::avltree::AVLTree;
-use trees::base::Tree;
-
-use std::io::{stdin, stdout, Write};
-
-
-fn avltree_cli() {
-    println!("\n------ AVL Tree branch ------\n");
-    let mut tree = AVLTree::<i32>::new();
-    available_operations();
-
-    loop {
-        print!("Operation > ");
-        let operation = get_user_input();
-
-        match operation.as_str().to_lowercase().trim() {
-            "1" => {
-                let value = get_value("insert");
-                tree.insert(value);
-            }
-            "2" => {
-                let value = get_value("delete");
-                tree.delete(value);
-            }
-            "3" => println!("Number of leaves: {:?}", tree.count_leaves()),
-            "4" => println!("Number of nodes: {:?}", tree.count_nodes()),
-            "5" => println!("Height of the tree: {:?}", tree.get_height()),
-            "6" => {
-                let max_value = tree.get_max();
-                match max_value {
-                    None => println!("The tree is empty!"),
-                    Some(max) => println!("Maximum Value: {:?}", max),
-                }
-            }
-            "7" => {
-                let min_value = tree.get_min();
-                match min_value {
-                    None => println!("The tree is empty!"),
-                    Some(min) => println!("Minimum Value: {:?}", min),
-                }
-            }
-            "8" => println!("Is the tree empty? {:?}", tree.is_empty()),
-            "9" | "search" => {
-                let value = get_value("search");
-                println!("Value found? {:?}", tree.contain(value));
-            }
-            "10" => {
-                loop {
-                    println!("Enter the number corresponding to the tree traversal type you want or type 'back' to select a different operation!\n1- Inorder \n2- Preorder \n3- Postorder");
-                    print!("input > ");
-                    let trav_type = get_user_input();
-                    match trav_type.as_str().to_lowercase().trim() {
-                        "1" => {
-                            print!("Your tree:\n");
-                            tree.traverse_inorder();
-                            break;
-                        }
-                        "2" => {
-                            print!("Your tree:\n");
-                            tree.traverse_preorder();
-                            break;
-                        }
-                        "3" => {
-                            print!("Your tree:\n");
-                            tree.traverse_postorder();
-                            break;
-                        }
-                        "back" => break,
-                        _ => {
-                            eprint!("This command is not recognized. \n");
-                        }
-                    }
-                }
-            }
-            "11" => { tree.print_tree(); }
-            "12" => { tree.clear(); }
-            "help" => available_operations(),
-            "back" => {
-                print!("\n");
-                return;
-            }
-            _ => println!("This command is not recognized. Type 'help' for the list of valid operations"),
-        }
-    }
-}
-
-
-fn rbtree_cli() {
-    println!("\n------ Red-Black Tree branch ------\n");
-    let mut tree = RBTree::<i32>::new();
-    available_operations();
-
-    loop {
-        print!("Operation > ");
-        let operation = get_user_input();
-
-        match operation.as_str().to_lowercase().trim() {
-            "1" => {
-                let value = get_value("insert");
-                tree.insert(value);
-            }
-            "2" => {
-                let value = get_value("delete");
-                tree.delete(value);
-            }
-            "3" => println!("Number of leaves: {:?}", tree.count_leaves()),
-            "4" => println!("Number of nodes: {:?}", tree.count_nodes()),
-            "5" => println!("Height of the tree: {:?}", tree.get_height()),
-            "6" => {
-                let max_value = tree.get_max();
-                match max_value {
-                    None => println!("The tree is empty!"),
-                    Some(max) => println!("Maximum Value: {:?}", max),
-                }
-            }
-            "7" => {
-                let min_value = tree.get_min();
-                match min_value {
-                    None => println!("The tree is empty!"),
-                    Some(min) => println!("Minimum Value: {:?}", min),
-                }
-            }
-            "8" => println!("Is the tree empty? {:?}", tree.is_empty()),
-            "9" | "search" => {
-                let value = get_value("search");
-                println!("Value found? {:?}", tree.contain(value));
-            }
-            "10" => {
-                loop {
-                    println!("Enter the number corresponding to the tree traversal type you want or type 'back' to select a different operation!\n1-Inorder \n2-Preorder \n3-Postorder");
-                    print!("input > ");
-                    let trav_type = get_user_input();
-                    match trav_type.as_str().to_lowercase().trim() {
-                        "1" => {
-                            print!("Your tree:\n");
-                            tree.traverse_inorder();
-                            break;
-                        }
-                        "2" => {
-                            print!("Your tree:\n");
-                            tree.traverse_preorder();
-                            break;
-                        }
-                        "3" => {
-                            print!("Your tree:\n");
-                            tree.traverse_postorder();
-                            break;
-                        }
-                        "back" => break,
-                        _ => {
-                            eprint!("This command is not recognized. \n");
-                        }
-                    }
-                }
-            }
-            "11" => { tree.print_tree(); }
-            "12" => { tree.clear(); }
-            "help" => available_operations(),
-            "back" => {
-                print!("\n");
-                return;
-            }
-            _ => println!("This command is not recognized. Type 'help' for the list of valid operations"),
-        }
-    }
-}
-
-
-fn bstree_cli() {
-    println!("\n------ Binary Search Tree branch ------\n");
-    let mut tree = BSTree::<i32>::new();
-    available_operations();
-
-    loop {
-        print!("Operation > ");
-        let operation = get_user_input();
-
-        match operation.as_str().to_lowercase().trim() {
-            "1" => {
-                let value = get_value("insert");
-                tree.insert(value);
-            }
-            "2" => {
-                let value = get_value("delete");
-                tree.delete(value);
-            }
-            "3" => println!("Number of leaves: {:?}", tree.count_leaves()),
-            "4" => println!("Number of nodes: {:?}", tree.count_nodes()),
-            "5" => println!("Height of the tree: {:?}", tree.get_height()),
-            "6" => {
-                let max_value = tree.get_max();
-                match max_value {
-                    None => println!("The tree is empty!"),
-                    Some(max) => println!("Maximum Value: {:?}", max),
-                }
-            }
-            "7" => {
-                let min_value = tree.get_min();
-                match min_value {
-                    None => println!("The tree is empty!"),
-                    Some(min) => println!("Minimum Value: {:?}", min),
-                }
-            }
-            "8" => println!("Is the tree empty? {:?}", tree.is_empty()),
-            "9" | "search" => {
-                let value = get_value("search");
-                println!("Value found? {:?}", tree.contain(value));
-            }
-            "10" => {
-                loop {
-                    println!("Enter the number corresponding to the tree traversal type you want or type 'back' to select a different operation!\n1-Inorder \n2-Preorder \n3-Postorder");
-                    print!("input > ");
-                    let trav_type = get_user_input();
-                    match trav_type.as_str().to_lowercase().trim() {
-                        "1" => {
-                            print!("Your tree:\n");
-                            tree.traverse_inorder();
-                            break;
-                        }
-                        "2" => {
-                            print!("Your tree:\n");
-                            tree.traverse_preorder();
-                            break;
-                        }
-                        "3" => {
-                            print!("Your tree:\n");
-                            tree.traverse_postorder();
-                            break;
-                        }
-                        "back" => break,
-                        _ => {
-                            eprint!("This command is not recognized. \n");
-                        }
-                    }
-                }
-            }
-            "11" => { tree.print_tree(); }
-            "12" => { tree.clear(); }
-            "help" => available_operations(),
-            "back" => {
-                print!("\n");
-                return;
-            }
-            _ => println!("This command is not recognized. Type 'help' for the list of valid operations"),
-        }
-    }
-}
-
-
-pub fn start_cli() {
-    loop {
-        println!("You can select a tree number to start or type 'exit' to leave!");
-        println!("Select a tree!\n1- Red-Black Tree \n2- AVL Tree \n3- Binary Search Tree");
-        print!("input > ");
-        let selected_tree_num = get_user_input();
-
-        match selected_tree_num.as_str().to_lowercase().trim() {
-            "1" => {
-                rbtree_cli();
-            }
-            "2" => {
-                avltree_cli();
-            }
-            "3" => {
-                bstree_cli();
-            }
-            "exit" => break,
-            _ => {
-                eprint!("This command is not recognized.\n\n");
-            }
-        }
-    }
-}
-
-pub fn get_user_input() -> String {
-    let mut line = String::new();
-    stdout().flush().expect("Failed to flush");
-    stdin().read_line(&mut line).expect("Failed to read from stdin");
-    line.to_string()
-}
-
-pub fn get_value(oper: &str) -> i32 {
-    loop {
-        print!("{} value > ", oper);
-        let value = get_user_input();
-        let trimmed_value = value.trim();
-        match trimmed_value.parse::<i32>() {
-            Ok(val) => {
-                println!("The {} operation for '{}' in the tree is complete!", oper, val);
-                return val;
-            }
-            Err(..) => {
-                println!("This is not an integer number");
-            }
-        };
-    }
-}
-
-pub fn available_operations() {
-    println!("\nAvailable Operations: \n------------------");
-    println!("Enter the number corresponding to the operation you want to perform! \n");
-    println!("1) Insert         - insert a node into the tree.");
-    println!("2) Delete         - delete a node from the tree.");
-    println!("3) Count Leaves   - count the number of leaves in the tree.");
-    println!("4) Count Nodes    - count the number of nodes in the tree.");
-    println!("5) Height         - return the height of the tree.");
-    println!("6) Maximum        - find the maximum value in the tree.");
-    println!("7) Minimum        - find the minimum value in the tree.");
-    println!("8) Empty          - check if the tree is empty.");
-    println!("9) Search         - check if the tree contains a certain value.");
-    println!("10) Traverse      - traverse the tree (Inorder, Preorder, or Postorder)");
-    println!("11) Print         - print the tree.");
-    println!("12) Clear         - clear the tree, removing all elements.");
-
-    println!("Back              - Go back to previous menu and erase current tree \n");
-}
-
-pub fn welcome() {
-    println!("---------------------------------------- Welcome to our Trees Command Line Interface ----------------------------------------\n");
-    println!("Available trees:\n- Red-Black Tree \n- AVL tree \n- Binary Search Tree \n");
-    println!("Available operations: \n1- Insert \n2- Delete \n3- Count Leaves \n4- Count Nodes \n5- Height \n6- Maximum \n7- Minimum \n8- Empty \n9- Search \n10- Traverse \n11- Print \n12- Clear\n");
-    println!("How to use the Command Line Interface: ");
-    println!("-------------------");
-}
+use trees::base::Tree;
+use trees::json::{FromJson, JsonKey};
+use trees::keys::TotalF64;
+use trees::trace::TraceStep;
+use trees::workload::{self, Distribution};
+
+use crate::compare::compare_cli;
+use crate::files;
+use crate::history::{restore, snapshot, History};
+use crate::registry::{with_tree, TreeKind};
+use crate::session::{NamedTree, Session};
+
+use std::fmt::{Debug, Display};
+use std::io::{stdin, stdout, Write};
+use std::str::FromStr;
+
+/// A key type offered by the interactive menu.
+pub trait CliKey: Ord + Clone + Debug + Display + FromStr + JsonKey {
+    /// How the type is called in the menus.
+    const NAME: &'static str;
+}
+
+impl CliKey for i32 {
+    const NAME: &'static str = "i32";
+}
+
+impl CliKey for i64 {
+    const NAME: &'static str = "i64";
+}
+
+impl CliKey for u64 {
+    const NAME: &'static str = "u64";
+}
+
+impl CliKey for TotalF64 {
+    const NAME: &'static str = "f64";
+}
+
+impl CliKey for String {
+    const NAME: &'static str = "String";
+}
+
+const KEY_TYPES: [&str; 5] = [i32::NAME, i64::NAME, u64::NAME, TotalF64::NAME, String::NAME];
+
+// Runs start with the key type chosen by the user, start being generic over
+// the key type.
+macro_rules! with_key_type {
+    ($start:ident($($argument:expr),*)) => {
+        match choose_key_type() {
+            0 => $start::<i32>($($argument),*),
+            1 => $start::<i64>($($argument),*),
+            2 => $start::<u64>($($argument),*),
+            3 => $start::<TotalF64>($($argument),*),
+            _ => $start::<String>($($argument),*),
+        }
+    };
+}
+
+const COMPARE_TITLE: &str = "All three trees, side by side";
+
+/// The entries of the interactive menu, in order: every kind of the
+/// registry, starting a session with one tree of that kind, and then the
+/// side by side comparison.
+pub fn menu() -> Vec<&'static str> {
+    TreeKind::ALL.iter().map(TreeKind::title).chain([COMPARE_TITLE]).collect()
+}
+
+// Returns the index of the chosen key type in KEY_TYPES.
+fn choose_key_type() -> usize {
+    loop {
+        println!("Select the type of the keys! (press Enter for {})", KEY_TYPES[0]);
+        for (number, name) in KEY_TYPES.iter().enumerate() {
+            println!("{}- {} ", number + 1, name);
+        }
+        print!("input > ");
+        let selected_type = get_user_input();
+        match selected_type.trim() {
+            "" => return 0,
+            number => match number.parse::<usize>() {
+                Ok(number) if (1..=KEY_TYPES.len()).contains(&number) => return number - 1,
+                _ => eprint!("This command is not recognized.\n\n"),
+            },
+        }
+    }
+}
+
+fn session_cli<K: CliKey>(kind: TreeKind) {
+    println!("\n------ {} branch, {} keys ------\n", kind.title(), K::NAME);
+    let mut session: Session<K> = Session::new(kind.name(), "tree").expect("the menu only offers known kinds");
+    available_operations();
+
+    loop {
+        print!("Operation ({}) > ", session.current().name);
+        let operation = get_user_input();
+        let words: Vec<&str> = operation.split_whitespace().collect();
+        // save, load and import may be followed by the file on the same line.
+        let (command, argument) = match operation.trim().split_once(char::is_whitespace) {
+            Some((command, argument)) => (command.to_lowercase(), Some(argument.trim().to_string())),
+            None => (operation.trim().to_lowercase(), None),
+        };
+
+        let result: Result<(), String> = match (command.as_str(), words.get(1..).unwrap_or(&[])) {
+            ("new", [kind, name]) => session.create(kind, name),
+            ("use", [name]) => session.switch(name),
+            ("list", []) => {
+                session.list().iter().for_each(|line| println!("{}", line));
+                Ok(())
+            }
+            ("union", [first, second, "into", into]) => session
+                .union(first, second, into)
+                .map(|_| println!("'{}' holds the keys of '{}' and '{}'", into, first, second)),
+            ("equal", [first, second]) => session
+                .equal(first, second)
+                .map(|equal| println!("Same keys? {:?}", equal)),
+            ("new" | "use" | "list" | "union" | "equal", _) => {
+                Err("usage: new KIND NAME, use NAME, list, union A B into C or equal A B".to_string())
+            }
+            ("help", _) => {
+                available_operations();
+                Ok(())
+            }
+            ("back", _) => {
+                println!();
+                return;
+            }
+            _ => {
+                let current: &mut NamedTree<K> = session.current_mut();
+                with_tree!(&mut current.tree, tree => tree_operation(tree, &mut current.history, &command, argument));
+                Ok(())
+            }
+        };
+        if let Err(error) = result {
+            println!("{}", error);
+        }
+    }
+}
+
+// Runs one operation of the menu on the tree, recording it in the history if it changed the tree.
+fn tree_operation<K, TR>(tree: &mut TR, history: &mut History, command: &str, path: Option<String>)
+where
+    K: CliKey,
+    TR: Tree<K> + Default + FromJson,
+{
+    // The operations changing the tree name themselves, with a snapshot of
+    // the tree before the change, for the history.
+    let mut change: Option<(String, String)> = None;
+
+    match command {
+        "1" => {
+            let value = get_value("insert");
+            change = Some((format!("insert {}", value), snapshot(tree)));
+            tree.insert(value);
+        }
+        "2" => {
+            let value = get_value("delete");
+            change = Some((format!("delete {}", value), snapshot(tree)));
+            tree.delete(value);
+        }
+        "3" => println!("Number of leaves: {:?}", tree.count_leaves()),
+        "4" => println!("Number of nodes: {:?}", tree.count_nodes()),
+        "5" => println!("Height of the tree: {:?}", tree.get_height()),
+        "6" => {
+            let max_value = tree.get_max();
+            match max_value {
+                None => println!("The tree is empty!"),
+                Some(max) => println!("Maximum Value: {:?}", max),
+            }
+        }
+        "7" => {
+            let min_value = tree.get_min();
+            match min_value {
+                None => println!("The tree is empty!"),
+                Some(min) => println!("Minimum Value: {:?}", min),
+            }
+        }
+        "8" => println!("Is the tree empty? {:?}", tree.is_empty()),
+        "9" | "search" => {
+            let value = get_value("search");
+            println!("Value found? {:?}", tree.contain(value));
+        }
+        "10" => {
+            loop {
+                println!("Enter the number corresponding to the tree traversal type you want or type 'back' to select a different operation!\n1- Inorder \n2- Preorder \n3- Postorder");
+                print!("input > ");
+                let trav_type = get_user_input();
+                match trav_type.as_str().to_lowercase().trim() {
+                    "1" => {
+                        println!("Your tree:");
+                        tree.traverse_inorder();
+                        break;
+                    }
+                    "2" => {
+                        println!("Your tree:");
+                        tree.traverse_preorder();
+                        break;
+                    }
+                    "3" => {
+                        println!("Your tree:");
+                        tree.traverse_postorder();
+                        break;
+                    }
+                    "back" => break,
+                    _ => {
+                        eprintln!("This command is not recognized. ");
+                    }
+                }
+            }
+        }
+        "11" => { tree.print_tree(); }
+        "12" => {
+            change = Some(("clear".to_string(), snapshot(tree)));
+            *tree = TR::default();
+            println!("Clear operation is complete!");
+        }
+        "13" => {
+            let value = get_value("traced insert");
+            change = Some((format!("insert {}", value), snapshot(tree)));
+            print_trace(&tree.insert_traced(value));
+        }
+        "14" => {
+            let value = get_value("traced delete");
+            change = Some((format!("delete {}", value), snapshot(tree)));
+            print_trace(&tree.delete_traced(value));
+        }
+        "15" | "range" => {
+            let low = get_value("range low");
+            let high = get_value("range high");
+            println!("Values in range: {:?}", tree.range(low, high));
+        }
+        "16" | "rank" => {
+            let value = get_value("rank");
+            println!("Number of smaller values: {:?}", tree.rank(value));
+        }
+        "17" | "save" => {
+            let path = path.unwrap_or_else(|| get_argument("save file"));
+            match files::save(tree, &path) {
+                Ok(()) => println!("The tree is saved to '{}'", path),
+                Err(error) => println!("Save failed: {}", error),
+            }
+        }
+        "18" | "load" => {
+            let path = path.unwrap_or_else(|| get_argument("load file"));
+            match files::load(&path) {
+                Ok(loaded) => {
+                    change = Some((format!("load {}", path), snapshot(tree)));
+                    *tree = loaded;
+                    println!("The tree is loaded from '{}'", path);
+                }
+                Err(error) => println!("Load failed, the tree is unchanged: {}", error),
+            }
+        }
+        "19" | "import" => {
+            let path = path.unwrap_or_else(|| get_argument("import file"));
+            match files::import(&path) {
+                Ok(imported) => {
+                    change = Some((format!("import {}", path), snapshot(tree)));
+                    *tree = imported;
+                    println!("The tree is built from the keys of '{}'", path);
+                }
+                Err(error) => println!("Import failed, the tree is unchanged: {}", error),
+            }
+        }
+        "20" | "undo" => match history.undo() {
+            Some(entry) => match restore(&entry.before) {
+                Ok(before) => {
+                    *tree = before;
+                    println!("Undid '{}'", entry.label);
+                }
+                Err(error) => println!("Undo failed, the tree is unchanged: {}", error),
+            },
+            None => println!("There is nothing to undo"),
+        },
+        "21" | "redo" => match history.redo() {
+            Some(entry) => match restore(&entry.after) {
+                Ok(after) => {
+                    *tree = after;
+                    println!("Redid '{}'", entry.label);
+                }
+                Err(error) => println!("Redo failed, the tree is unchanged: {}", error),
+            },
+            None => println!("There is nothing to redo"),
+        },
+        "22" | "history" => {
+            let lines = history.lines();
+            match lines.is_empty() {
+                true => println!("No operation changed the tree yet"),
+                false => lines.iter().for_each(|line| println!("{}", line)),
+            }
+        }
+        "23" | "generate" => {
+            let text = path.unwrap_or_else(|| get_argument("generate DISTRIBUTION COUNT [SEED]"));
+            match parse_generate(&text) {
+                Ok((distribution, count, seed)) => {
+                    change = Some((format!("generate {}", text), snapshot(tree)));
+                    let mut inserted = 0;
+                    // The generated keys are non-negative integers, which every key type can parse.
+                    for key in workload::keys(distribution, count, seed).iter().filter_map(|key| key.to_string().parse::<K>().ok()) {
+                        if !tree.contain(key.clone()) {
+                            tree.insert(key);
+                            inserted += 1;
+                        }
+                    }
+                    println!("Inserted {} new {} keys", inserted, distribution.name());
+                }
+                Err(error) => println!("{}", error),
+            }
+        }
+        "format" => println!("{}", files::FORMAT_HELP),
+        _ => println!("This command is not recognized. Type 'help' for the list of valid operations"),
+    }
+
+    if let Some((label, before)) = change {
+        history.record(label, before, snapshot(tree));
+    }
+}
+
+
+pub fn start_cli() {
+    loop {
+        println!("You can select a tree number to start or type 'exit' to leave!");
+        println!("Select a tree!");
+        for (number, title) in menu().iter().enumerate() {
+            println!("{}- {} ", number + 1, title);
+        }
+        print!("input > ");
+        let selected_tree_num = get_user_input();
+        let selected_tree_num = selected_tree_num.as_str().to_lowercase();
+
+        match selected_tree_num.trim() {
+            "exit" => break,
+            number => match number.parse::<usize>().map(|number| number.wrapping_sub(1)) {
+                Ok(index) if index < TreeKind::ALL.len() => with_key_type!(session_cli(TreeKind::ALL[index])),
+                Ok(index) if index == TreeKind::ALL.len() => with_key_type!(compare_cli(COMPARE_TITLE)),
+                _ => eprint!("This command is not recognized.\n\n"),
+            },
+        }
+    }
+}
+
+pub fn get_user_input() -> String {
+    let mut line = String::new();
+    stdout().flush().expect("Failed to flush");
+    stdin().read_line(&mut line).expect("Failed to read from stdin");
+    line.to_string()
+}
+
+pub fn print_trace<K: Debug>(steps: &[TraceStep<K>]) {
+    for (number, step) in steps.iter().enumerate() {
+        println!("Step {}: {}", number + 1, step);
+    }
+}
+
+pub fn get_value<K: CliKey>(oper: &str) -> K {
+    loop {
+        print!("{} value > ", oper);
+        let value = get_user_input();
+        let trimmed_value = value.trim();
+        match trimmed_value.parse::<K>() {
+            Ok(val) => {
+                println!("The {} operation for '{}' in the tree is complete!", oper, val);
+                return val;
+            }
+            Err(..) => {
+                println!("This is not a valid {} value", K::NAME);
+            }
+        };
+    }
+}
+
+pub fn get_argument(prompt: &str) -> String {
+    loop {
+        print!("{} > ", prompt);
+        let argument = get_user_input();
+        match argument.trim() {
+            "" => println!("Please enter a value"),
+            argument => return argument.to_string(),
+        }
+    }
+}
+
+// Parses "DISTRIBUTION COUNT [SEED]", the seed being 0 by default.
+fn parse_generate(text: &str) -> Result<(Distribution, usize, u64), String> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let usage = || "usage: generate DISTRIBUTION COUNT [SEED]".to_string();
+    match words[..] {
+        [distribution, count] | [distribution, count, _] => {
+            let distribution = Distribution::parse(distribution)?;
+            let count: usize = count.parse().map_err(|_| usage())?;
+            let seed: u64 = words.get(2).map_or(Ok(0), |seed| seed.parse()).map_err(|_| usage())?;
+            Ok((distribution, count, seed))
+        }
+        _ => Err(usage()),
+    }
+}
+
+pub fn available_operations() {
+    println!("\nAvailable Operations: \n------------------");
+    println!("Enter the number corresponding to the operation you want to perform! \n");
+    println!("1) Insert         - insert a node into the tree.");
+    println!("2) Delete         - delete a node from the tree.");
+    println!("3) Count Leaves   - count the number of leaves in the tree.");
+    println!("4) Count Nodes    - count the number of nodes in the tree.");
+    println!("5) Height         - return the height of the tree.");
+    println!("6) Maximum        - find the maximum value in the tree.");
+    println!("7) Minimum        - find the minimum value in the tree.");
+    println!("8) Empty          - check if the tree is empty.");
+    println!("9) Search         - check if the tree contains a certain value.");
+    println!("10) Traverse      - traverse the tree (Inorder, Preorder, or Postorder)");
+    println!("11) Print         - print the tree.");
+    println!("12) Clear         - clear the tree, removing all elements.");
+    println!("13) Traced Insert - insert a node, printing every rebalancing step.");
+    println!("14) Traced Delete - delete a node, printing every rebalancing step.");
+    println!("15) Range         - list the values between two bounds.");
+    println!("16) Rank          - count the values smaller than a value.");
+    println!("17) Save [file]   - save the tree to a file.");
+    println!("18) Load [file]   - replace the tree by the one saved in a file.");
+    println!("19) Import [file] - replace the tree by one built from a file of keys, one per line.");
+    println!("20) Undo          - take back the last operation that changed the tree.");
+    println!("21) Redo          - apply again the last undone operation.");
+    println!("22) History       - list the operations that changed the tree.");
+    println!("23) Generate DIST COUNT [SEED]");
+    println!("                  - insert seeded keys: uniform, ascending, descending, zigzag, clustered or adversarial.");
+    println!("New KIND NAME     - add an empty tree of a kind: {}; and use it.", TreeKind::names());
+    println!("Use NAME          - apply the operations to the tree with that name.");
+    println!("List              - list the trees of the session, the current one marked with '*'.");
+    println!("Union A B into C  - put the keys of trees A and B into tree C, created with the kind of A if needed.");
+    println!("Equal A B         - check if trees A and B hold the same keys.");
+    println!("Format            - describe the file formats of save and import.");
+
+    println!("Back              - go back to the previous menu, ending the session; save a tree first to load it again later.\n");
+}
+
+pub fn welcome() {
+    println!("---------------------------------------- Welcome to our Trees Command Line Interface ----------------------------------------\n");
+    println!("Available trees:");
+    for title in menu() {
+        println!("- {} ", title);
+    }
+    println!();
+    println!("Available operations: \n1- Insert \n2- Delete \n3- Count Leaves \n4- Count Nodes \n5- Height \n6- Maximum \n7- Minimum \n8- Empty \n9- Search \n10- Traverse \n11- Print \n12- Clear \n13- Traced Insert \n14- Traced Delete \n15- Range \n16- Rank \n17- Save \n18- Load \n19- Import \n20- Undo \n21- Redo \n22- History \n23- Generate\n");
+    println!("How to use the Command Line Interface: ");
+    println!("-------------------");
+}
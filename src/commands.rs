@@ -0,0 +1,192 @@
+use trees::base::Tree;
+use trees::observer::RotationCounter;
+#[cfg(feature = "stats")]
+use trees::stats::TreeStats;
+use trees::workload::{self, Distribution, Mix, Workload};
+
+use std::collections::HashSet;
+use std::fs;
+use std::io::{stdin, Read};
+use std::time::{Duration, Instant};
+
+use crate::args::OutputFormat;
+use crate::registry::{with_tree, AnyTree, TreeKind};
+use crate::script::{EXIT_IO_ERROR, EXIT_OK, EXIT_SCRIPT_ERROR};
+
+/// Reads the keys, separated by whitespace, from the file at path or from stdin.
+fn read_keys(path: Option<&str>) -> Result<Vec<i32>, (i32, String)> {
+    let mut text = String::new();
+    let (name, result) = match path {
+        None | Some("-") => ("stdin", stdin().read_to_string(&mut text).map(|_| ())),
+        Some(path) => (path, fs::read_to_string(path).map(|content| text = content)),
+    };
+    result.map_err(|error| (EXIT_IO_ERROR, format!("cannot read '{}': {}", name, error)))?;
+    text.split_whitespace()
+        .map(|word| word.parse::<i32>().map_err(|_| (EXIT_SCRIPT_ERROR, format!("{}: '{}' is not an integer", name, word))))
+        .collect()
+}
+
+// Inserts every key once; the trees print a message for keys they already hold.
+fn fill<TR: Tree<i32>>(tree: &mut TR, keys: &[i32]) {
+    let mut seen: HashSet<i32> = HashSet::new();
+    for &key in keys {
+        if seen.insert(key) {
+            tree.insert(key);
+        }
+    }
+}
+
+fn print_tree<TR: Tree<i32>>(tree: &TR, formats: &[OutputFormat]) {
+    for format in formats {
+        match format {
+            OutputFormat::Text => print!("{}", tree.render()),
+            OutputFormat::Json => println!("{}", tree.to_json()),
+            OutputFormat::Svg => print!("{}", tree.to_svg()),
+        }
+    }
+}
+
+fn print_summary<TR: Tree<i32>>(kind: &str, tree: &TR) {
+    let optional = |key: Option<i32>| key.map_or("none".to_string(), |key| key.to_string());
+    println!("kind {}", kind);
+    println!("count {}", tree.count_nodes());
+    println!("height {}", tree.get_height());
+    println!("leaves {}", tree.count_leaves());
+    println!("min {}", optional(tree.get_min()));
+    println!("max {}", optional(tree.get_max()));
+}
+
+#[cfg(feature = "stats")]
+fn print_counters(stats: TreeStats) {
+    println!("comparisons {}", stats.comparisons);
+    println!("single_rotations {}", stats.single_rotations);
+    println!("double_rotations {}", stats.double_rotations);
+    println!("recolorings {}", stats.recolorings);
+    println!("allocations {}", stats.allocations);
+    println!("frees {}", stats.frees);
+    println!("max_depth {}", stats.max_depth);
+}
+
+/// `trees build`: prints the tree built from the keys, returning the exit code.
+pub fn build(kind: TreeKind, from: Option<&str>, formats: &[OutputFormat]) -> i32 {
+    let keys: Vec<i32> = match read_keys(from) {
+        Ok(keys) => keys,
+        Err((code, message)) => {
+            eprintln!("error: {}", message);
+            return code;
+        }
+    };
+    let mut tree: AnyTree<i32> = AnyTree::new(kind);
+    with_tree!(&mut tree, tree => {
+        fill(tree, &keys);
+        print_tree(tree, formats);
+    });
+    EXIT_OK
+}
+
+/// `trees stats`: prints one "name value" line per statistic of the tree built from the keys.
+///
+/// With the `stats` feature the kinds keeping counters also print the counters of the build.
+pub fn stats(kind: TreeKind, from: Option<&str>) -> i32 {
+    let keys: Vec<i32> = match read_keys(from) {
+        Ok(keys) => keys,
+        Err((code, message)) => {
+            eprintln!("error: {}", message);
+            return code;
+        }
+    };
+    let mut tree: AnyTree<i32> = AnyTree::new(kind);
+    with_tree!(&mut tree, tree => {
+        fill(tree, &keys);
+        print_summary(kind.name(), tree);
+    });
+    #[cfg(feature = "stats")]
+    if let Some(stats) = tree.stats() {
+        print_counters(stats);
+    }
+    EXIT_OK
+}
+
+/// `trees generate`: prints the keys of the distribution, one per line, or
+/// with a mix the operations of a workload, one script command per line.
+pub fn generate(distribution: Distribution, count: usize, seed: u64, mix: Option<Mix>) -> i32 {
+    match mix {
+        None => workload::keys(distribution, count, seed).iter().for_each(|key| println!("{}", key)),
+        Some(mix) => Workload::generate(distribution, count, mix, seed).operations.iter().for_each(|operation| println!("{}", operation)),
+    }
+    EXIT_OK
+}
+
+/// The measures of one kind of tree in `trees bench`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BenchResult {
+    pub kind: TreeKind,
+    pub elapsed: Duration,
+    pub height: u32,
+    pub nodes: u32,
+    pub rotations: u32,
+}
+
+impl BenchResult {
+    /// Operations per second, 0 when the run was too short to be timed.
+    pub fn throughput(&self, operations: usize) -> f64 {
+        match self.elapsed.as_secs_f64() {
+            seconds if seconds > 0.0 => operations as f64 / seconds,
+            _ => 0.0,
+        }
+    }
+}
+
+fn measure<TR: Tree<i32>>(kind: TreeKind, tree: &mut TR, workload: &Workload<i32>) -> BenchResult {
+    let start = Instant::now();
+    workload.replay(tree);
+    let elapsed = start.elapsed();
+    BenchResult { kind, elapsed, height: tree.get_height(), nodes: tree.count_nodes(), rotations: 0 }
+}
+
+/// Replays the workload on an empty tree of the kind, timing the replay only.
+pub fn run_bench(kind: TreeKind, workload: &Workload<i32>) -> BenchResult {
+    let mut tree: AnyTree<i32, RotationCounter> = AnyTree::new(kind);
+    let result = with_tree!(&mut tree, tree => measure(kind, tree, workload));
+    BenchResult { rotations: tree.observer().map_or(0, |counter| counter.rotations), ..result }
+}
+
+/// Writes the results as an aligned table, or as CSV with a header line.
+pub fn format_bench(results: &[BenchResult], operations: usize, csv: bool) -> String {
+    let mut output = String::new();
+    let width: usize = TreeKind::ALL.iter().map(|kind| kind.name().len()).max().unwrap_or(0);
+    if csv {
+        output.push_str("kind,operations,seconds,ops_per_sec,height,nodes,rotations\n");
+    } else {
+        output.push_str(&format!("{:<width$} {:>10} {:>10} {:>12} {:>7} {:>8} {:>10}\n", "kind", "operations", "seconds", "ops/sec", "height", "nodes", "rotations"));
+    }
+    for result in results {
+        let (seconds, throughput) = (result.elapsed.as_secs_f64(), result.throughput(operations));
+        if csv {
+            output.push_str(&format!(
+                "{},{},{:.6},{:.0},{},{},{}\n",
+                result.kind.name(), operations, seconds, throughput, result.height, result.nodes, result.rotations
+            ));
+        } else {
+            output.push_str(&format!(
+                "{:<width$} {:>10} {:>10.6} {:>12.0} {:>7} {:>8} {:>10}\n",
+                result.kind.name(), operations, seconds, throughput, result.height, result.nodes, result.rotations
+            ));
+        }
+    }
+    output
+}
+
+/// `trees bench`: replays the same seeded workload on every kind and prints the measures.
+pub fn bench(kinds: &[TreeKind], distribution: Distribution, count: usize, seed: u64, mix: Mix, csv: bool) -> i32 {
+    let workload = Workload::generate(distribution, count, mix, seed);
+    let results: Vec<BenchResult> = kinds.iter().map(|&kind| run_bench(kind, &workload)).collect();
+    if !csv {
+        println!(
+            "{} operations, {} keys, mix {},{},{} (insert,delete,lookup), seed {}",
+            count, distribution.name(), mix.insert, mix.delete, mix.lookup, seed
+        );
+    }
+    print!("{}", format_bench(&results, count, csv));
+    EXIT_OK
+}
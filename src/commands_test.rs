@@ -0,0 +1,31 @@
+#[cfg(test)]
+mod test {
+    use trees::workload::{Distribution, Mix, Workload};
+
+    use crate::commands::{self, BenchResult};
+    use crate::registry::TreeKind;
+
+    #[test]
+    fn test_bench() {
+        let workload = Workload::generate(Distribution::Ascending, 500, Mix { insert: 1, delete: 0, lookup: 0 }, 0);
+        let results: Vec<BenchResult> = [TreeKind::Rb, TreeKind::Avl, TreeKind::Bst]
+            .iter()
+            .map(|&kind| commands::run_bench(kind, &workload))
+            .collect();
+        assert!(results.iter().all(|result| result.nodes == 500));
+        assert!(results[0].rotations > 0 && results[1].rotations > 0);
+        assert_eq!(results[2].rotations, 0);
+        assert_eq!(results[2].height, 500);
+        // The arena trees report their rotations too, the persistent trees have no observer.
+        assert!(commands::run_bench(TreeKind::ArenaRb, &workload).rotations > 0);
+        assert_eq!(commands::run_bench(TreeKind::PersistentAvl, &workload).rotations, 0);
+
+        let csv = commands::format_bench(&results, 500, true);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], "kind,operations,seconds,ops_per_sec,height,nodes,rotations");
+        assert!(lines[3].starts_with("bst,500,") && lines[3].ends_with(",500,500,0"));
+        let table = commands::format_bench(&results, 500, false);
+        assert!(table.lines().nth(1).unwrap().starts_with("rb "));
+    }
+}
@@ -0,0 +1,147 @@
+use trees::avltree::AVLTree;
+use trees::base::Tree;
+use trees::bstree::BSTree;
+use trees::observer::RotationCounter;
+use trees::rbtree::RBTree;
+
+use crate::cli::{get_user_input, get_value, CliKey};
+
+const COLUMN_SEPARATOR: &str = " | ";
+
+/// Lays out the columns next to each other, each column being a title and
+/// a block of lines. Every column is padded to its widest line.
+pub fn side_by_side(columns: &[(String, String)]) -> String {
+    let blocks: Vec<Vec<&str>> = columns
+        .iter()
+        .map(|(title, body)| std::iter::once(title.as_str()).chain(body.lines()).collect())
+        .collect();
+    let widths: Vec<usize> = blocks
+        .iter()
+        .map(|lines| lines.iter().map(|line| line.chars().count()).max().unwrap_or(0))
+        .collect();
+    let height: usize = blocks.iter().map(Vec::len).max().unwrap_or(0);
+    let mut output = String::new();
+    for row in 0..height {
+        let cells: Vec<String> = blocks
+            .iter()
+            .zip(&widths)
+            .map(|(lines, &width)| format!("{:width$}", lines.get(row).copied().unwrap_or(""), width = width))
+            .collect();
+        output.push_str(cells.join(COLUMN_SEPARATOR).trim_end());
+        output.push('\n');
+    }
+    output
+}
+
+/// The same keys applied to a red-black tree, an AVL tree and a binary search tree.
+pub struct Comparison<K: CliKey> {
+    rb_tree: RBTree<K, RotationCounter>,
+    avl_tree: AVLTree<K, RotationCounter>,
+    bs_tree: BSTree<K>,
+    // The rotations of (rb_tree, avl_tree) before the last operation.
+    last_rotations: (u32, u32),
+}
+
+impl<K: CliKey> Default for Comparison<K> {
+    fn default() -> Self {
+        Comparison {
+            rb_tree: RBTree::default(),
+            avl_tree: AVLTree::default(),
+            bs_tree: BSTree::default(),
+            last_rotations: (0, 0),
+        }
+    }
+}
+
+impl<K: CliKey> Comparison<K> {
+    fn rotations(&self) -> (u32, u32) {
+        (self.rb_tree.observer().rotations, self.avl_tree.observer().rotations)
+    }
+
+    pub fn insert(&mut self, value: K) {
+        self.last_rotations = self.rotations();
+        self.rb_tree.insert(value.clone());
+        self.avl_tree.insert(value.clone());
+        self.bs_tree.insert(value);
+    }
+
+    pub fn delete(&mut self, value: K) {
+        self.last_rotations = self.rotations();
+        self.rb_tree.delete(value.clone());
+        self.avl_tree.delete(value.clone());
+        self.bs_tree.delete(value);
+    }
+
+    /// The three renders side by side, followed by the height, the number of
+    /// leaves and the rotations of each tree, with the rotations of the last
+    /// insert or delete in parentheses.
+    pub fn report(&self) -> String {
+        let (rb_rotations, avl_rotations) = self.rotations();
+        side_by_side(&[
+            column("Red-Black Tree", &self.rb_tree, rb_rotations, rb_rotations - self.last_rotations.0),
+            column("AVL Tree", &self.avl_tree, avl_rotations, avl_rotations - self.last_rotations.1),
+            column("Binary Search Tree", &self.bs_tree, 0, 0),
+        ])
+    }
+}
+
+fn column<K: CliKey, TR: Tree<K>>(name: &str, tree: &TR, rotations: u32, last: u32) -> (String, String) {
+    let body = format!(
+        "{}\nheight    {}\nleaves    {}\nrotations {} (+{})\n",
+        tree.render(),
+        tree.get_height(),
+        tree.count_leaves(),
+        rotations,
+        last,
+    );
+    (name.to_string(), body)
+}
+
+/// Interactive mode applying every insert and delete to the three trees at
+/// once, printing them side by side after each step.
+pub fn compare_cli<K: CliKey>(name: &str) {
+    println!("\n------ {} branch, {} keys ------\n", name, K::NAME);
+    let mut comparison: Comparison<K> = Comparison::default();
+    available_operations();
+
+    loop {
+        print!("Operation > ");
+        let operation = get_user_input();
+
+        match operation.as_str().to_lowercase().trim() {
+            "1" => {
+                let value = get_value("insert");
+                comparison.insert(value);
+                print!("{}", comparison.report());
+            }
+            "2" => {
+                let value = get_value("delete");
+                comparison.delete(value);
+                print!("{}", comparison.report());
+            }
+            "3" => print!("{}", comparison.report()),
+            "4" => {
+                comparison = Comparison::default();
+                println!("Clear operation is complete!");
+            }
+            "help" => available_operations(),
+            "back" => {
+                println!();
+                return;
+            }
+            _ => println!("This command is not recognized. Type 'help' for the list of valid operations"),
+        }
+    }
+}
+
+fn available_operations() {
+    println!("\nAvailable Operations: \n------------------");
+    println!("Every operation is applied to a Red-Black Tree, an AVL Tree and a Binary Search Tree at once.");
+    println!("Enter the number corresponding to the operation you want to perform! \n");
+    println!("1) Insert         - insert a node into the three trees and print them.");
+    println!("2) Delete         - delete a node from the three trees and print them.");
+    println!("3) Print          - print the three trees side by side.");
+    println!("4) Clear          - clear the three trees, removing all elements.");
+
+    println!("Back              - Go back to previous menu and erase current trees \n");
+}
@@ -0,0 +1,38 @@
+#[cfg(test)]
+mod test {
+    use crate::compare::{side_by_side, Comparison};
+
+    #[test]
+    fn test_side_by_side() {
+        let columns = [
+            ("left".to_string(), "a\nlonger line\n".to_string()),
+            ("right".to_string(), "b\nc\nd\n".to_string()),
+        ];
+        assert_eq!(
+            side_by_side(&columns),
+            "left        | right\n\
+             a           | b\n\
+             longer line | c\n\
+             \x20           | d\n"
+        );
+    }
+
+    #[test]
+    fn test_comparison_report() {
+        let mut comparison: Comparison<i32> = Comparison::default();
+        for number in [1, 2, 3] {
+            comparison.insert(number);
+        }
+        let report = comparison.report();
+        let lines: Vec<&str> = report.lines().collect();
+        assert!(lines[0].starts_with("Red-Black Tree"));
+        assert!(lines[0].contains("| AVL Tree"));
+        assert!(lines[0].ends_with("| Binary Search Tree"));
+        assert!(lines.iter().any(|line| line.contains("height    2") && line.ends_with("height    3")));
+        assert!(lines.iter().any(|line| line.contains("rotations 1 (+1)") && line.ends_with("rotations 0 (+0)")));
+
+        comparison.delete(1);
+        let report = comparison.report();
+        assert!(report.lines().any(|line| line.starts_with("rotations 1 (+0)")));
+    }
+}
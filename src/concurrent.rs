@@ -0,0 +1,113 @@
+use std::fmt::Debug;
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::base::Tree;
+
+/// A tree shared between threads, read by any number of threads at once or
+/// written by one of them.
+///
+/// The tree must be `Send + Sync`, which `AVLTree`, `BSTree`, `ArenaAVLTree`
+/// and `ArenaRBTree` are when their keys are. `RBTree` is not, its nodes
+/// linking to their parent with `Rc`, so `ArenaRBTree` is the red-black tree
+/// to share.
+///
+/// A thread that panics while writing leaves the tree in an unknown state,
+/// so every later access panics too.
+///
+/// # Examples
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+/// use trees::arena_rbtree::ArenaRBTree;
+/// use trees::concurrent::ConcurrentTree;
+///
+/// let tree = Arc::new(ConcurrentTree::new(ArenaRBTree::new()));
+/// let writers: Vec<_> = (0..4)
+///     .map(|writer| {
+///         let tree = Arc::clone(&tree);
+///         thread::spawn(move || {
+///             for key in 0..100 {
+///                 tree.insert(writer * 100 + key);
+///             }
+///         })
+///     })
+///     .collect();
+/// for writer in writers {
+///     writer.join().unwrap();
+/// }
+/// assert_eq!(tree.len(), 400);
+/// assert!(tree.contain(399));
+/// ```
+#[derive(Debug, Default)]
+pub struct ConcurrentTree<TR> {
+    tree: RwLock<TR>,
+}
+
+impl<TR> ConcurrentTree<TR> {
+    pub fn new(tree: TR) -> Self {
+        ConcurrentTree { tree: RwLock::new(tree) }
+    }
+
+    /// Locks the tree for reading, other readers still being let in.
+    pub fn read(&self) -> RwLockReadGuard<'_, TR> {
+        self.tree.read().expect("a thread panicked while writing the tree")
+    }
+
+    /// Locks the tree for writing, waiting for the readers to leave.
+    pub fn write(&self) -> RwLockWriteGuard<'_, TR> {
+        self.tree.write().expect("a thread panicked while writing the tree")
+    }
+
+    /// Takes the tree back once no other thread holds it.
+    pub fn into_inner(self) -> TR {
+        self.tree.into_inner().expect("a thread panicked while writing the tree")
+    }
+
+    /// Inserts the key unless it is already there, returning whether it was inserted.
+    pub fn insert<T: Ord + Clone + Debug>(&self, key: T) -> bool
+    where
+        TR: Tree<T>,
+    {
+        let mut tree = self.write();
+        let missing: bool = !tree.contain(key.clone());
+        if missing {
+            tree.insert(key);
+        }
+        missing
+    }
+
+    /// Deletes the key if it is there, returning whether it was deleted.
+    pub fn delete<T: Ord + Clone + Debug>(&self, key: T) -> bool
+    where
+        TR: Tree<T>,
+    {
+        let mut tree = self.write();
+        let present: bool = tree.contain(key.clone());
+        if present {
+            tree.delete(key);
+        }
+        present
+    }
+
+    pub fn contain<T: Ord + Clone + Debug>(&self, key: T) -> bool
+    where
+        TR: Tree<T>,
+    {
+        self.read().contain(key)
+    }
+
+    /// The number of keys in the tree.
+    pub fn len<T: Ord + Clone + Debug>(&self) -> u32
+    where
+        TR: Tree<T>,
+    {
+        self.read().count_nodes()
+    }
+
+    pub fn is_empty<T: Ord + Clone + Debug>(&self) -> bool
+    where
+        TR: Tree<T>,
+    {
+        self.read().is_empty()
+    }
+}
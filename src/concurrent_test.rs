@@ -0,0 +1,102 @@
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+    use std::thread;
+
+    use crate::arena_avltree::ArenaAVLTree;
+    use crate::arena_rbtree::ArenaRBTree;
+    use crate::avltree::AVLTree;
+    use crate::base::Tree;
+    use crate::bstree::BSTree;
+    use crate::concurrent::ConcurrentTree;
+    use crate::rbtree::RBTree;
+    use crate::workload::Rng;
+
+    const WRITERS: i32 = 4;
+    const READERS: usize = 4;
+    const KEYS: i32 = 2000;
+
+    // Every writer inserts its own keys in a random order, then deletes the odd
+    // ones, while the readers check that the tree stays a sorted set.
+    fn hammer<TR: Tree<i32> + Default + Send + Sync + 'static>() -> TR {
+        let tree: Arc<ConcurrentTree<TR>> = Arc::new(ConcurrentTree::default());
+        let writers: Vec<_> = (0..WRITERS)
+            .map(|writer| {
+                let tree = Arc::clone(&tree);
+                thread::spawn(move || {
+                    let mut keys: Vec<i32> = (0..KEYS).map(|key| key * WRITERS + writer).collect();
+                    Rng::new(writer as u64).shuffle(&mut keys);
+                    for &key in &keys {
+                        assert!(tree.insert(key));
+                    }
+                    for &key in keys.iter().filter(|&&key| key % 2 == 1) {
+                        assert!(tree.delete(key));
+                        assert!(!tree.delete(key));
+                    }
+                })
+            })
+            .collect();
+        let readers: Vec<_> = (0..READERS)
+            .map(|reader| {
+                let tree = Arc::clone(&tree);
+                thread::spawn(move || {
+                    let mut rng = Rng::new(100 + reader as u64);
+                    for _ in 0..200 {
+                        let tree = tree.read();
+                        let keys: Vec<i32> = match (tree.get_min(), tree.get_max()) {
+                            (Some(low), Some(high)) => tree.range(low, high),
+                            _ => Vec::new(),
+                        };
+                        assert_eq!(keys.len() as u32, tree.count_nodes());
+                        assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+                        let key = rng.below((KEYS * WRITERS) as usize) as i32;
+                        assert_eq!(tree.contain(key), keys.binary_search(&key).is_ok());
+                    }
+                })
+            })
+            .collect();
+        for thread in writers.into_iter().chain(readers) {
+            thread.join().unwrap();
+        }
+        let tree: TR = Arc::try_unwrap(tree).ok().unwrap().into_inner();
+        let even: Vec<i32> = (0..KEYS * WRITERS).filter(|key| key % 2 == 0).collect();
+        assert_eq!(tree.range(0, KEYS * WRITERS), even);
+        tree
+    }
+
+    #[test]
+    fn test_concurrent_avl() {
+        let tree: AVLTree<i32> = hammer();
+        // Parsing the JSON checks the order and the balance of every node.
+        assert!(AVLTree::<i32>::from_json(&tree.to_json()).is_ok());
+        let tree: ArenaAVLTree<i32> = hammer();
+        assert!(AVLTree::<i32>::from_json(&tree.to_json()).is_ok());
+    }
+
+    #[test]
+    fn test_concurrent_rb() {
+        let tree: ArenaRBTree<i32> = hammer();
+        // Parsing the JSON checks the order, the colors and the black heights.
+        assert!(RBTree::<i32>::from_json(&tree.to_json()).is_ok());
+    }
+
+    #[test]
+    fn test_concurrent_bst() {
+        let tree: BSTree<i32> = hammer();
+        assert!(BSTree::<i32>::from_json(&tree.to_json()).is_ok());
+    }
+
+    #[test]
+    fn test_concurrent_tree() {
+        let tree = ConcurrentTree::new(AVLTree::new());
+        assert!(tree.is_empty());
+        assert!(tree.insert(3));
+        assert!(!tree.insert(3));
+        assert!(tree.contain(3));
+        tree.write().insert(4);
+        assert_eq!(tree.read().get_max(), Some(4));
+        assert!(tree.delete(3));
+        assert!(!tree.delete(3));
+        assert_eq!(tree.into_inner().count_nodes(), 1);
+    }
+}
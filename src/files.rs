@@ -0,0 +1,60 @@
+use trees::base::Tree;
+use trees::json::{FromJson, JsonKey};
+
+use std::fmt::Debug;
+use std::fs;
+use std::str::FromStr;
+
+/// How the save files are written, shown by the help of the tree menus.
+pub const FORMAT_HELP: &str = "\
+Save files hold the shape of the tree as one JSON document, the one printed by
+'trees build --json': every node is {\"key\":..,\"left\":..,\"right\":..}, a missing
+child being null and an empty tree being null. Red-black nodes also have a
+\"color\" (\"red\" or \"black\") and AVL nodes may have a \"height\". String keys are
+JSON strings, f64 keys are JSON numbers or one of \"NaN\", \"inf\" and \"-inf\".
+Loading checks the ordering of the keys and the balance rules of the tree.
+
+Import files hold one key per line, blank lines being skipped.";
+
+/// Writes the tree to the file at path, in the format of `FORMAT_HELP`.
+pub fn save<K, TR>(tree: &TR, path: &str) -> Result<(), String>
+where
+    K: Ord + Clone + Debug + JsonKey,
+    TR: Tree<K>,
+{
+    fs::write(path, tree.to_json() + "\n").map_err(|error| format!("cannot write '{}': {}", path, error))
+}
+
+/// Reads a tree saved by `save`, with the exact same shape.
+pub fn load<TR: FromJson>(path: &str) -> Result<TR, String> {
+    let text = fs::read_to_string(path).map_err(|error| format!("cannot read '{}': {}", path, error))?;
+    TR::from_json(&text).map_err(|error| format!("'{}': {}", path, error))
+}
+
+/// Parses one key per line, skipping blank lines.
+pub fn parse_keys<K: FromStr>(text: &str) -> Result<Vec<K>, String> {
+    text.lines()
+        .enumerate()
+        .map(|(number, line)| (number + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(number, line)| line.parse::<K>().map_err(|_| format!("line {}: '{}' is not a valid key", number, line)))
+        .collect()
+}
+
+/// Builds a new tree from the keys listed in the file at path, one per line,
+/// in file order. Repeated keys are inserted once.
+pub fn import<K, TR>(path: &str) -> Result<TR, String>
+where
+    K: Ord + Clone + Debug + FromStr,
+    TR: Tree<K> + Default,
+{
+    let text = fs::read_to_string(path).map_err(|error| format!("cannot read '{}': {}", path, error))?;
+    let keys: Vec<K> = parse_keys(&text).map_err(|error| format!("'{}' {}", path, error))?;
+    let mut tree = TR::default();
+    for key in keys {
+        if !tree.contain(key.clone()) {
+            tree.insert(key);
+        }
+    }
+    Ok(tree)
+}
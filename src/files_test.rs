@@ -0,0 +1,58 @@
+#[cfg(test)]
+mod test {
+    use trees::avltree::AVLTree;
+    use trees::base::Tree;
+    use trees::bstree::BSTree;
+    use trees::keys::TotalF64;
+    use trees::rbtree::RBTree;
+
+    use std::env;
+    use std::fs;
+
+    use crate::files;
+
+    fn temp_path(name: &str) -> String {
+        env::temp_dir().join(format!("trees-{}-{}", std::process::id(), name)).to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = temp_path("save.json");
+        let mut rb_tree: RBTree<String> = RBTree::new();
+        for word in ["kiwi", "apple", "fig", "pear", "date"] {
+            rb_tree.insert(word.to_string());
+        }
+        files::save(&rb_tree, &path).unwrap();
+        let loaded: RBTree<String> = files::load(&path).unwrap();
+        assert_eq!(loaded.to_json(), rb_tree.to_json());
+
+        let mut avl_tree: AVLTree<TotalF64> = AVLTree::new();
+        for value in [2.5, f64::NAN, -0.0, f64::NEG_INFINITY, 1e-9] {
+            avl_tree.insert(TotalF64(value));
+        }
+        files::save(&avl_tree, &path).unwrap();
+        let loaded: AVLTree<TotalF64> = files::load(&path).unwrap();
+        assert_eq!(loaded.to_json(), avl_tree.to_json());
+        assert!(loaded.contain(TotalF64(f64::NAN)));
+        assert!(loaded.contain(TotalF64(-0.0)) && !loaded.contain(TotalF64(0.0)));
+
+        // An AVL save has no colors, it is not a valid red-black tree.
+        assert!(files::load::<RBTree<TotalF64>>(&path).is_err());
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(files::load::<BSTree<TotalF64>>(&path), Err(error) if error.starts_with("cannot read")));
+    }
+
+    #[test]
+    fn test_import() {
+        assert_eq!(files::parse_keys::<u64>("3\n\n 1 \n2\n"), Ok(vec![3, 1, 2]));
+        assert_eq!(files::parse_keys::<u64>("3\n-1\n"), Err("line 2: '-1' is not a valid key".to_string()));
+
+        let path = temp_path("keys.txt");
+        fs::write(&path, "5\n3\n8\n3\n\n1\n").unwrap();
+        let tree: BSTree<i64> = files::import(&path).unwrap();
+        assert_eq!(tree.to_json(), BSTree::<i64>::from_json(r#"{"key":5,"left":{"key":3,"left":{"key":1}},"right":{"key":8}}"#).unwrap().to_json());
+        fs::write(&path, "5\nfive\n").unwrap();
+        assert!(files::import::<i64, BSTree<i64>>(&path).is_err());
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,172 @@
+use core::fmt::Debug;
+use alloc::vec::Vec;
+use alloc::vec;
+
+/// An immutable copy of a tree for lookups, made by `Tree::freeze`.
+///
+/// The keys are kept in one array, in the order a breadth-first walk of a
+/// complete tree would meet them (the Eytzinger layout): the children of
+/// the key at position k, counting from 1, are at 2k and 2k + 1. There are
+/// no pointers to follow, and the first levels of every search share the
+/// same few cache lines.
+///
+/// # Examples
+/// ```
+/// use trees::rbtree::RBTree;
+/// use trees::base::Tree;
+///
+/// let mut tree = RBTree::new();
+/// for i in [40, 10, 30, 20, 50] {
+///     tree.insert(i);
+/// }
+/// let frozen = tree.freeze();
+///
+/// assert!(frozen.contain(30));
+/// assert_eq!(frozen.floor(35), Some(30));
+/// assert_eq!(frozen.ceiling(35), Some(40));
+/// assert_eq!(frozen.range(15, 45), vec![20, 30, 40]);
+/// assert_eq!(frozen.rank(35), 3);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct FrozenTree<T: Ord + Clone + Debug> {
+    // keys[k - 1] is the key at position k.
+    keys: Vec<T>,
+}
+
+impl<T: Ord + Clone + Debug> FrozenTree<T> {
+    /// Lays out keys, which must be in strictly ascending order.
+    pub(crate) fn from_sorted(sorted: Vec<T>) -> Self {
+        let count: usize = sorted.len();
+        let mut slots: Vec<Option<T>> = vec![None; count];
+        let mut sorted = sorted.into_iter();
+        // An inorder walk of the implicit tree meets the positions in the
+        // order of the sorted keys.
+        let mut stack: Vec<usize> = Vec::new();
+        let mut position: usize = 1;
+        loop {
+            while position <= count {
+                stack.push(position);
+                position *= 2;
+            }
+            match stack.pop() {
+                None => break,
+                Some(top) => {
+                    slots[top - 1] = sorted.next();
+                    position = top * 2 + 1;
+                }
+            }
+        }
+        FrozenTree { keys: slots.into_iter().map(|slot| slot.unwrap()).collect() }
+    }
+
+    fn key(&self, position: usize) -> &T {
+        &self.keys[position - 1]
+    }
+
+    // The number of positions below position, itself included.
+    fn subtree_size(&self, position: usize) -> usize {
+        let mut size: usize = 0;
+        let mut first: usize = position;
+        let mut width: usize = 1;
+        while first <= self.keys.len() {
+            size += (first + width - 1).min(self.keys.len()) - first + 1;
+            first *= 2;
+            width *= 2;
+        }
+        size
+    }
+
+    // The position of the smallest key at least value, 0 if there is none.
+    fn lower_bound(&self, value: &T) -> usize {
+        let mut found: usize = 0;
+        let mut position: usize = 1;
+        while position <= self.keys.len() {
+            if self.key(position) >= value {
+                found = position;
+                position *= 2;
+            } else {
+                position = position * 2 + 1;
+            }
+        }
+        found
+    }
+
+    // The position of the next key in ascending order, 0 after the last one.
+    fn successor(&self, position: usize) -> usize {
+        let mut next: usize = position * 2 + 1;
+        if next <= self.keys.len() {
+            while next * 2 <= self.keys.len() {
+                next *= 2;
+            }
+            return next;
+        }
+        // Up to the first ancestor reached from its left subtree.
+        next = position;
+        while next % 2 == 1 {
+            next /= 2;
+        }
+        next / 2
+    }
+
+    /// The number of keys.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn contain(&self, value: T) -> bool {
+        self.ceiling(value.clone()) == Some(value)
+    }
+
+    /// The largest key smaller than or equal to value.
+    pub fn floor(&self, value: T) -> Option<T> {
+        let mut found: Option<&T> = None;
+        let mut position: usize = 1;
+        while position <= self.keys.len() {
+            if *self.key(position) <= value {
+                found = Some(self.key(position));
+                position = position * 2 + 1;
+            } else {
+                position *= 2;
+            }
+        }
+        found.cloned()
+    }
+
+    /// The smallest key larger than or equal to value.
+    pub fn ceiling(&self, value: T) -> Option<T> {
+        match self.lower_bound(&value) {
+            0 => None,
+            position => Some(self.key(position).clone()),
+        }
+    }
+
+    /// Returns the keys between low and high (both included), in ascending order.
+    pub fn range(&self, low: T, high: T) -> Vec<T> {
+        let mut keys: Vec<T> = Vec::new();
+        let mut position: usize = self.lower_bound(&low);
+        while position != 0 && *self.key(position) <= high {
+            keys.push(self.key(position).clone());
+            position = self.successor(position);
+        }
+        keys
+    }
+
+    /// Returns the number of keys smaller than value.
+    pub fn rank(&self, value: T) -> u32 {
+        let mut rank: usize = 0;
+        let mut position: usize = 1;
+        while position <= self.keys.len() {
+            if *self.key(position) < value {
+                rank += self.subtree_size(position * 2) + 1;
+                position = position * 2 + 1;
+            } else {
+                position *= 2;
+            }
+        }
+        rank as u32
+    }
+}
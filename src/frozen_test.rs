@@ -0,0 +1,81 @@
+#[cfg(test)]
+mod test {
+    extern crate std;
+
+    use crate::avltree::AVLTree;
+    use crate::base::Tree;
+    use crate::bstree::BSTree;
+    use crate::frozen::FrozenTree;
+    use crate::rbtree::RBTree;
+    use crate::workload::{self, Distribution};
+    use std::vec::Vec;
+
+    fn assert_matches_sorted(frozen: &FrozenTree<i32>, sorted: &[i32]) {
+        assert_eq!(frozen.len(), sorted.len());
+        let low: i32 = sorted.first().map_or(0, |key| key - 2);
+        let high: i32 = sorted.last().map_or(0, |key| key + 2);
+        for probe in low..=high {
+            let rank: usize = sorted.partition_point(|key| *key < probe);
+            assert_eq!(frozen.rank(probe), rank as u32);
+            assert_eq!(frozen.contain(probe), sorted.get(rank) == Some(&probe));
+            assert_eq!(frozen.ceiling(probe), sorted.get(rank).copied());
+            let floor: usize = sorted.partition_point(|key| *key <= probe);
+            assert_eq!(frozen.floor(probe), floor.checked_sub(1).map(|index| sorted[index]));
+        }
+        assert_eq!(frozen.range(low, high), sorted);
+    }
+
+    #[test]
+    fn test_every_size() {
+        // Each size leaves the last level of the layout filled differently.
+        for size in 0..70 {
+            let sorted: Vec<i32> = (0..size).map(|number| number * 3).collect();
+            let frozen: FrozenTree<i32> = AVLTree::<i32>::from_sorted_iter(sorted.clone()).freeze();
+            assert_matches_sorted(&frozen, &sorted);
+            assert_eq!(frozen.is_empty(), size == 0);
+            for low in (-1..size * 3 + 1).step_by(4) {
+                let high: i32 = low + 7;
+                let expected: Vec<i32> = sorted.iter().copied().filter(|key| (low..=high).contains(key)).collect();
+                assert_eq!(frozen.range(low, high), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_freeze_trees() {
+        for distribution in Distribution::ALL {
+            let keys: Vec<i32> = workload::keys(distribution, 500, 7);
+            let mut rb_tree: RBTree<i32> = RBTree::new();
+            let mut avl_tree: AVLTree<i32> = AVLTree::new();
+            let mut bs_tree: BSTree<i32> = BSTree::new();
+            for key in &keys {
+                rb_tree.insert(*key);
+                avl_tree.insert(*key);
+                bs_tree.insert(*key);
+            }
+            let mut sorted: Vec<i32> = keys.clone();
+            sorted.sort();
+            sorted.dedup();
+
+            let frozen: FrozenTree<i32> = rb_tree.freeze();
+            assert_matches_sorted(&frozen, &sorted);
+            assert_eq!(avl_tree.freeze(), frozen);
+            assert_eq!(bs_tree.freeze(), frozen);
+        }
+    }
+
+    #[test]
+    fn test_frozen_copy_is_independent() {
+        let mut tree: RBTree<i32> = RBTree::new();
+        for number in 0..10 {
+            tree.insert(number);
+        }
+        let frozen: FrozenTree<i32> = tree.freeze();
+        tree.delete(3);
+        tree.insert(42);
+        assert!(frozen.contain(3));
+        assert!(!frozen.contain(42));
+        assert_eq!(frozen.range(0, 100), (0..10).collect::<Vec<i32>>());
+        assert!(RBTree::<i32>::new().freeze().is_empty());
+    }
+}
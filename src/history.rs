@@ -0,0 +1,71 @@
+use trees::base::Tree;
+use trees::json::{FromJson, JsonKey};
+
+use std::fmt::Debug;
+
+/// The whole tree as JSON, with the color or height its kind keeps for every
+/// node, so that `restore` gets back the exact tree and not only its keys.
+///
+/// Only the operations changing the tree take one, which costs a walk of the tree.
+pub fn snapshot<K: Ord + Clone + Debug + JsonKey, TR: Tree<K>>(tree: &TR) -> String {
+    tree.to_json()
+}
+
+/// Rebuilds the tree a snapshot was taken of, node for node.
+pub fn restore<TR: FromJson>(snapshot: &str) -> Result<TR, String> {
+    TR::from_json(snapshot)
+}
+
+/// One operation that changed the tree, with snapshots of the tree before and after it.
+///
+/// The snapshots are JSON, see `snapshot`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub label: String,
+    pub before: String,
+    pub after: String,
+}
+
+/// The operation log of a CLI session, for undo and redo.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct History {
+    done: Vec<Entry>,
+    // Most recently undone last.
+    undone: Vec<Entry>,
+}
+
+impl History {
+    /// Records an operation, unless it left the tree unchanged.
+    /// A new operation forgets the operations that were undone.
+    pub fn record(&mut self, label: String, before: String, after: String) {
+        if before != after {
+            self.done.push(Entry { label, before, after });
+            self.undone.clear();
+        }
+    }
+
+    /// Takes back the last operation, returning it; its `before` is the tree to restore.
+    pub fn undo(&mut self) -> Option<&Entry> {
+        let entry = self.done.pop()?;
+        self.undone.push(entry);
+        self.undone.last()
+    }
+
+    /// Applies again the last undone operation, returning it; its `after` is the tree to restore.
+    pub fn redo(&mut self) -> Option<&Entry> {
+        let entry = self.undone.pop()?;
+        self.done.push(entry);
+        self.done.last()
+    }
+
+    /// One line per operation, oldest first, the undone ones that redo can
+    /// apply again being marked as such.
+    pub fn lines(&self) -> Vec<String> {
+        let done = self.done.iter().map(|entry| entry.label.clone());
+        let undone = self.undone.iter().rev().map(|entry| format!("{} (undone)", entry.label));
+        done.chain(undone)
+            .enumerate()
+            .map(|(number, label)| format!("{}. {}", number + 1, label))
+            .collect()
+    }
+}
@@ -0,0 +1,83 @@
+#[cfg(test)]
+mod test {
+    use trees::avltree::AVLTree;
+    use trees::base::Tree;
+    use trees::json::FromJson;
+    use trees::rbtree::RBTree;
+
+    use crate::history::{self, History};
+
+    fn record<TR: Tree<i32>>(history: &mut History, tree: &mut TR, label: &str, change: impl FnOnce(&mut TR)) {
+        let before = history::snapshot(tree);
+        change(tree);
+        history.record(label.to_string(), before, history::snapshot(tree));
+    }
+
+    fn inserted<TR: Tree<i32> + Default>(keys: &[i32]) -> TR {
+        let mut tree = TR::default();
+        keys.iter().for_each(|key| tree.insert(*key));
+        tree
+    }
+
+    // Deletes key, undoes the delete and checks the tree is back node for node.
+    fn undo_delete<TR: Tree<i32> + FromJson>(mut tree: TR, key: i32) {
+        let mut history = History::default();
+        let shape = tree.to_json();
+        record(&mut history, &mut tree, "delete", |tree| tree.delete(key));
+        assert!(!tree.contain(key));
+        tree = history::restore(&history.undo().unwrap().before).unwrap();
+        assert_eq!(tree.to_json(), shape);
+    }
+
+    #[test]
+    fn test_undo_restores_shape() {
+        // Inserting the keys of these trees again in preorder gives other
+        // colors and another root, so undo has to restore the nodes as they were.
+        let mut rb_tree: RBTree<i32> = inserted(&(1..=10).collect::<Vec<i32>>());
+        rb_tree.delete(1);
+        assert_ne!(inserted::<RBTree<i32>>(&[6, 4, 2, 3, 5, 8, 7, 9, 10]).to_json(), rb_tree.to_json());
+        undo_delete(rb_tree, 6);
+
+        let mut avl_tree: AVLTree<i32> = inserted(&[4, 2, 5, 1, 3, 6]);
+        avl_tree.delete(6);
+        assert_ne!(inserted::<AVLTree<i32>>(&[4, 2, 1, 3, 5]).to_json(), avl_tree.to_json());
+        undo_delete(avl_tree, 5);
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let mut history: History = History::default();
+        let mut tree: AVLTree<i32> = AVLTree::new();
+        for number in [2, 1, 3, 4] {
+            record(&mut history, &mut tree, &format!("insert {}", number), |tree| tree.insert(number));
+        }
+        let shape = tree.to_json();
+        record(&mut history, &mut tree, "delete 1", |tree| tree.delete(1));
+        // Deleting a missing key does not change the tree, so it is not recorded.
+        record(&mut history, &mut tree, "delete 9", |tree| tree.delete(9));
+        assert_eq!(history.lines().len(), 5);
+
+        let entry = history.undo().unwrap();
+        assert_eq!(entry.label, "delete 1");
+        tree = history::restore(&entry.before).unwrap();
+        assert_eq!(tree.to_json(), shape);
+        assert_eq!(history.lines()[4], "5. delete 1 (undone)");
+
+        let entry = history.redo().unwrap();
+        tree = history::restore(&entry.after).unwrap();
+        assert!(!tree.contain(1));
+        assert!(history.redo().is_none());
+
+        for _ in 0..2 {
+            tree = history::restore(&history.undo().unwrap().before).unwrap();
+        }
+        assert!(tree.contain(1) && !tree.contain(4));
+        record(&mut history, &mut tree, "insert 5", |tree| tree.insert(5));
+        assert_eq!(history.lines(), ["1. insert 2", "2. insert 1", "3. insert 3", "4. insert 5"]);
+        assert!(history.redo().is_none());
+        for _ in 0..4 {
+            assert!(history.undo().is_some());
+        }
+        assert!(history.undo().is_none());
+    }
+}
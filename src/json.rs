@@ -0,0 +1,530 @@
+use core::fmt::Debug;
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+
+use crate::base::NodeRef;
+
+/// A parsed JSON value.
+///
+/// Numbers keep their original text so that 64-bit keys
+/// survive the round trip without going through `f64`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+// The derived drop would recurse once per level of nesting.
+impl Drop for JsonValue {
+    fn drop(&mut self) {
+        let mut pending: Vec<JsonValue> = Vec::new();
+        self.take_children(&mut pending);
+        while let Some(mut value) = pending.pop() {
+            value.take_children(&mut pending);
+        }
+    }
+}
+
+impl JsonValue {
+    fn take_children(&mut self, pending: &mut Vec<JsonValue>) {
+        match self {
+            JsonValue::Array(items) => pending.append(items),
+            JsonValue::Object(fields) => pending.extend(fields.drain(..).map(|(_, value)| value)),
+            _ => {}
+        }
+    }
+
+    /// Returns the value of the field with the given name, if this is an object.
+    pub fn get(&self, name: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(fields) => fields.iter().find(|(key, _)| key == name).map(|(_, value)| value),
+            _ => None,
+        }
+    }
+}
+
+/// A key type that can be written to and read from JSON.
+pub trait JsonKey: Sized {
+    fn to_json(&self) -> String;
+
+    fn from_json(value: &JsonValue) -> Option<Self>;
+}
+
+macro_rules! impl_json_key_for_integer {
+    ($($integer:ty),*) => {
+        $(
+            impl JsonKey for $integer {
+                fn to_json(&self) -> String {
+                    self.to_string()
+                }
+
+                fn from_json(value: &JsonValue) -> Option<Self> {
+                    match value {
+                        JsonValue::Number(number) => number.parse().ok(),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_json_key_for_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl JsonKey for bool {
+    fn to_json(&self) -> String {
+        self.to_string()
+    }
+
+    fn from_json(value: &JsonValue) -> Option<Self> {
+        match value {
+            JsonValue::Bool(boolean) => Some(*boolean),
+            _ => None,
+        }
+    }
+}
+
+impl JsonKey for char {
+    fn to_json(&self) -> String {
+        quote(&self.to_string())
+    }
+
+    fn from_json(value: &JsonValue) -> Option<Self> {
+        match value {
+            JsonValue::String(text) => {
+                let mut characters = text.chars();
+                match (characters.next(), characters.next()) {
+                    (Some(character), None) => Some(character),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+impl JsonKey for String {
+    fn to_json(&self) -> String {
+        quote(self)
+    }
+
+    fn from_json(value: &JsonValue) -> Option<Self> {
+        match value {
+            JsonValue::String(text) => Some(text.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// A tree that can be rebuilt from the JSON written by `Tree::to_json`.
+///
+/// Every tree implements it with its own `from_json`, so that code generic
+/// over the tree type can load a saved tree.
+///
+/// # Examples
+///
+/// ```
+/// use trees::avltree::AVLTree;
+/// use trees::base::Tree;
+/// use trees::json::FromJson;
+///
+/// fn load<TR: FromJson>(text: &str) -> TR {
+///     TR::from_json(text).unwrap()
+/// }
+///
+/// let tree: AVLTree<i32> = load(r#"{"key":1,"right":{"key":2}}"#);
+/// assert_eq!(tree.count_nodes(), 2);
+/// ```
+pub trait FromJson: Sized {
+    fn from_json(text: &str) -> Result<Self, String>;
+}
+
+/// Writes a string as a quoted JSON string.
+pub fn quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for character in text.chars() {
+        match character {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            character if (character as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", character as u32)),
+            character => quoted.push(character),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+// What is left to write: a node or missing child, or the text after a node.
+enum Pending<N> {
+    Node(Option<N>),
+    Text(&'static str),
+}
+
+/// Writes the tree as nested JSON objects, `null` standing for a missing child.
+pub fn write_tree<T, N>(root: Option<N>) -> String
+where
+    T: Ord + Clone + Debug + JsonKey,
+    N: NodeRef<T>,
+{
+    let mut json = String::new();
+    let mut pending: Vec<Pending<N>> = vec![Pending::Node(root)];
+    while let Some(item) = pending.pop() {
+        let node: N = match item {
+            Pending::Text(text) => {
+                json.push_str(text);
+                continue;
+            }
+            Pending::Node(None) => {
+                json.push_str("null");
+                continue;
+            }
+            Pending::Node(Some(node)) => node,
+        };
+        json.push_str("{\"key\":");
+        json.push_str(&node.key().to_json());
+        for (name, value) in node.json_attributes() {
+            json.push_str(&format!(",{}:{}", quote(name), value));
+        }
+        json.push_str(",\"left\":");
+        pending.push(Pending::Text("}"));
+        pending.push(Pending::Node(node.right()));
+        pending.push(Pending::Text(",\"right\":"));
+        pending.push(Pending::Node(node.left()));
+    }
+    json
+}
+
+/// Parses a complete JSON document.
+pub fn parse(text: &str) -> Result<JsonValue, String> {
+    let mut parser = Parser { bytes: text.as_bytes(), text, position: 0 };
+    let value: JsonValue = parser.parse_value()?;
+    parser.skip_whitespace();
+    if parser.position != parser.bytes.len() {
+        return Err(parser.error("unexpected trailing characters"));
+    }
+    Ok(value)
+}
+
+// An array or object whose closing bracket has not been read yet. An object
+// also holds the name of the field whose value comes next.
+enum Open {
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>, String),
+}
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    text: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, message: &str) -> String {
+        format!("Invalid JSON at byte {}: {}", self.position, message)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.position < self.bytes.len() && matches!(self.bytes[self.position], b' ' | b'\t' | b'\n' | b'\r') {
+            self.position += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.position).copied()
+    }
+
+    fn expect(&mut self, literal: &str) -> Result<(), String> {
+        if self.text[self.position..].starts_with(literal) {
+            self.position += literal.len();
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", literal)))
+        }
+    }
+
+    // Arrays and objects being read wait on a stack of their own rather than
+    // on the call stack, so that deeply nested input cannot overflow it.
+    fn parse_value(&mut self) -> Result<JsonValue, String> {
+        let mut open: Vec<Open> = Vec::new();
+        loop {
+            self.skip_whitespace();
+            let mut value: JsonValue = match self.peek() {
+                None => return Err(self.error("unexpected end of input")),
+                Some(b'n') => self.expect("null").map(|_| JsonValue::Null)?,
+                Some(b't') => self.expect("true").map(|_| JsonValue::Bool(true))?,
+                Some(b'f') => self.expect("false").map(|_| JsonValue::Bool(false))?,
+                Some(b'"') => self.parse_string().map(JsonValue::String)?,
+                Some(b'-') | Some(b'0'..=b'9') => self.parse_number()?,
+                Some(b'[') => {
+                    self.position += 1;
+                    self.skip_whitespace();
+                    if self.peek() != Some(b']') {
+                        open.push(Open::Array(Vec::new()));
+                        continue;
+                    }
+                    self.position += 1;
+                    JsonValue::Array(Vec::new())
+                }
+                Some(b'{') => {
+                    self.position += 1;
+                    self.skip_whitespace();
+                    if self.peek() != Some(b'}') {
+                        let name: String = self.parse_field_name()?;
+                        open.push(Open::Object(Vec::new(), name));
+                        continue;
+                    }
+                    self.position += 1;
+                    JsonValue::Object(Vec::new())
+                }
+                Some(_) => return Err(self.error("unexpected character")),
+            };
+            // Hand the finished value to the innermost open array or object,
+            // closing every one that ends right after it.
+            loop {
+                match open.last_mut() {
+                    None => return Ok(value),
+                    Some(Open::Array(items)) => {
+                        items.push(value);
+                        self.skip_whitespace();
+                        match self.peek() {
+                            Some(b',') => {
+                                self.position += 1;
+                                break;
+                            }
+                            Some(b']') => self.position += 1,
+                            _ => return Err(self.error("expected ',' or ']'")),
+                        }
+                    }
+                    Some(Open::Object(fields, name)) => {
+                        fields.push((core::mem::take(name), value));
+                        self.skip_whitespace();
+                        match self.peek() {
+                            Some(b',') => {
+                                self.position += 1;
+                                *name = self.parse_field_name()?;
+                                break;
+                            }
+                            Some(b'}') => self.position += 1,
+                            _ => return Err(self.error("expected ',' or '}'")),
+                        }
+                    }
+                }
+                value = match open.pop() {
+                    Some(Open::Array(items)) => JsonValue::Array(items),
+                    Some(Open::Object(fields, _)) => JsonValue::Object(fields),
+                    None => unreachable!(),
+                };
+            }
+        }
+    }
+
+    fn parse_field_name(&mut self) -> Result<String, String> {
+        self.skip_whitespace();
+        let name: String = self.parse_string()?;
+        self.skip_whitespace();
+        self.expect(":")?;
+        Ok(name)
+    }
+
+    fn parse_number(&mut self) -> Result<JsonValue, String> {
+        let start: usize = self.position;
+        while let Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9') = self.peek() {
+            self.position += 1;
+        }
+        let number: &str = &self.text[start..self.position];
+        match number.parse::<f64>() {
+            Ok(_) => Ok(JsonValue::Number(number.to_string())),
+            Err(_) => Err(self.error("malformed number")),
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, String> {
+        self.expect("\"")?;
+        let mut string = String::new();
+        loop {
+            let rest: &str = &self.text[self.position..];
+            let character: char = match rest.chars().next() {
+                None => return Err(self.error("unterminated string")),
+                Some(character) => character,
+            };
+            self.position += character.len_utf8();
+            match character {
+                '"' => return Ok(string),
+                '\\' => {
+                    let escaped: u8 = self.peek().ok_or_else(|| self.error("unterminated escape"))?;
+                    self.position += 1;
+                    match escaped {
+                        b'"' => string.push('"'),
+                        b'\\' => string.push('\\'),
+                        b'/' => string.push('/'),
+                        b'b' => string.push('\u{8}'),
+                        b'f' => string.push('\u{c}'),
+                        b'n' => string.push('\n'),
+                        b'r' => string.push('\r'),
+                        b't' => string.push('\t'),
+                        b'u' => {
+                            let hex: &str = self.text.get(self.position..self.position + 4).ok_or_else(|| self.error("short unicode escape"))?;
+                            let code: u32 = u32::from_str_radix(hex, 16).map_err(|_| self.error("bad unicode escape"))?;
+                            self.position += 4;
+                            string.push(char::from_u32(code).unwrap_or('\u{fffd}'));
+                        }
+                        _ => return Err(self.error("unknown escape")),
+                    }
+                }
+                character => string.push(character),
+            }
+        }
+    }
+}
+
+/// The shape of a tree read from JSON, before it is turned into real nodes.
+/// attributes holds every field other than key, left and right,
+/// such as the color of a red-black node or the height of an AVL node.
+#[derive(Debug)]
+pub struct JsonNode<T> {
+    pub key: T,
+    pub attributes: Vec<(String, JsonValue)>,
+    pub left: Option<Box<JsonNode<T>>>,
+    pub right: Option<Box<JsonNode<T>>>,
+}
+
+// The derived drop would recurse once per level of the tree.
+impl<T> Drop for JsonNode<T> {
+    fn drop(&mut self) {
+        let mut pending: Vec<Box<JsonNode<T>>> = Vec::new();
+        pending.extend(self.left.take());
+        pending.extend(self.right.take());
+        while let Some(mut node) = pending.pop() {
+            pending.extend(node.left.take());
+            pending.extend(node.right.take());
+        }
+    }
+}
+
+impl<T> JsonNode<T> {
+    /// Returns the attribute with the given name.
+    pub fn attribute(&self, name: &str) -> Option<&JsonValue> {
+        self.attributes.iter().find(|(key, _)| key == name).map(|(_, value)| value)
+    }
+}
+
+/// Folds a tree read from JSON from the leaves up: visit gets every node
+/// with what it returned for the left and right children, the first error
+/// stopping the fold.
+///
+/// The trees build their nodes with it, children first, without recursing
+/// once per level.
+///
+/// # Examples
+///
+/// ```
+/// use trees::json;
+///
+/// let root = json::parse_tree::<i32>(r#"{"key":2,"left":{"key":1},"right":null}"#).unwrap().unwrap();
+/// let height: Result<u32, String> = json::fold(&root, |_, left, right| Ok(1 + left.max(right).unwrap_or(0)));
+/// assert_eq!(height, Ok(2));
+/// ```
+pub fn fold<T, B, F>(root: &JsonNode<T>, mut visit: F) -> Result<B, String>
+where
+    F: FnMut(&JsonNode<T>, Option<B>, Option<B>) -> Result<B, String>,
+{
+    // Every node comes twice: first to put its children above it, then,
+    // with its children folded, to be visited.
+    let mut pending: Vec<(&JsonNode<T>, bool)> = vec![(root, false)];
+    let mut folded: Vec<B> = Vec::new();
+    while let Some((node, children_folded)) = pending.pop() {
+        if children_folded {
+            let right: Option<B> = node.right.as_ref().and_then(|_| folded.pop());
+            let left: Option<B> = node.left.as_ref().and_then(|_| folded.pop());
+            folded.push(visit(node, left, right)?);
+        } else {
+            pending.push((node, true));
+            pending.extend(node.right.as_deref().map(|right| (right, false)));
+            pending.extend(node.left.as_deref().map(|left| (left, false)));
+        }
+    }
+    Ok(folded.pop().expect("the root is folded last"))
+}
+
+// A node read from JSON before its children are, with the positions in the
+// list of nodes of its children.
+struct Flat<T> {
+    key: T,
+    attributes: Vec<(String, JsonValue)>,
+    left: Option<usize>,
+    right: Option<usize>,
+}
+
+// Reads the nodes in preorder into a flat list, then puts them together from
+// the last one, whose children always come after them.
+fn to_node<T: JsonKey + Debug>(value: &JsonValue) -> Result<Option<Box<JsonNode<T>>>, String> {
+    let mut flat: Vec<Flat<T>> = Vec::new();
+    // The values still to read, with the node and the side they hang from.
+    let mut pending: Vec<(&JsonValue, Option<(usize, bool)>)> = vec![(value, None)];
+    while let Some((value, parent)) = pending.pop() {
+        let fields: &Vec<(String, JsonValue)> = match value {
+            JsonValue::Null => continue,
+            JsonValue::Object(fields) => fields,
+            _ => return Err("Every node must be an object or null".to_string()),
+        };
+        let key: T = match value.get("key") {
+            None => return Err("A node is missing its key".to_string()),
+            Some(key) => T::from_json(key).ok_or_else(|| format!("Unsupported key {:?}", key))?,
+        };
+        let attributes = fields
+            .iter()
+            .filter(|(name, _)| name != "key" && name != "left" && name != "right")
+            .cloned()
+            .collect();
+        let index: usize = flat.len();
+        match parent {
+            Some((parent, true)) => flat[parent].left = Some(index),
+            Some((parent, false)) => flat[parent].right = Some(index),
+            None => {}
+        }
+        flat.push(Flat { key, attributes, left: None, right: None });
+        pending.extend(value.get("right").map(|right| (right, Some((index, false)))));
+        pending.extend(value.get("left").map(|left| (left, Some((index, true)))));
+    }
+    let mut built: Vec<Option<Box<JsonNode<T>>>> = (0..flat.len()).map(|_| None).collect();
+    while let Some(node) = flat.pop() {
+        let left = node.left.and_then(|left| built[left].take());
+        let right = node.right.and_then(|right| built[right].take());
+        built[flat.len()] = Some(Box::new(JsonNode { key: node.key, attributes: node.attributes, left, right }));
+    }
+    Ok(built.into_iter().next().flatten())
+}
+
+fn check_order<T: Ord + Debug>(root: &JsonNode<T>) -> Result<(), String> {
+    let mut pending: Vec<(&JsonNode<T>, Option<&T>, Option<&T>)> = vec![(root, None, None)];
+    while let Some((node, lower, upper)) = pending.pop() {
+        if lower.is_some_and(|lower| node.key <= *lower) || upper.is_some_and(|upper| node.key >= *upper) {
+            return Err(format!("The key {:?} breaks the binary search tree ordering", node.key));
+        }
+        pending.extend(node.right.as_deref().map(|right| (right, Some(&node.key), upper)));
+        pending.extend(node.left.as_deref().map(|left| (left, lower, Some(&node.key))));
+    }
+    Ok(())
+}
+
+/// Parses the nested JSON produced by `Tree::to_json` and checks that
+/// the keys are in binary search tree order, without duplicates.
+///
+/// Returns None for an empty tree (`null`).
+pub fn parse_tree<T: JsonKey + Ord + Debug>(text: &str) -> Result<Option<Box<JsonNode<T>>>, String> {
+    let root = to_node(&parse(text)?)?;
+    if let Some(root) = &root {
+        check_order(root)?;
+    }
+    Ok(root)
+}
@@ -0,0 +1,216 @@
+#[cfg(test)]
+mod test {
+    extern crate std;
+
+    use std::format;
+    use std::string::{String, ToString};
+    use std::vec;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::arena_avltree::ArenaAVLTree;
+    use crate::arena_rbtree::ArenaRBTree;
+    use crate::base::Tree;
+    use crate::json::{self, FromJson, JsonKey, JsonValue};
+    use crate::persistent_avltree::PersistentAVLTree;
+    use crate::persistent_rbtree::PersistentRBTree;
+    use crate::{avltree, bstree, rbtree};
+
+    // A key counting its live copies, to see whether nodes are freed.
+    static LIVE_KEYS: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+    struct CountedKey(i32);
+
+    impl CountedKey {
+        fn new(key: i32) -> Self {
+            LIVE_KEYS.fetch_add(1, Ordering::SeqCst);
+            CountedKey(key)
+        }
+    }
+
+    impl Clone for CountedKey {
+        fn clone(&self) -> Self {
+            CountedKey::new(self.0)
+        }
+    }
+
+    impl Drop for CountedKey {
+        fn drop(&mut self) {
+            LIVE_KEYS.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    impl JsonKey for CountedKey {
+        fn to_json(&self) -> String {
+            self.0.to_json()
+        }
+
+        fn from_json(value: &JsonValue) -> Option<Self> {
+            i32::from_json(value).map(CountedKey::new)
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let value = json::parse(r#" {"a": [1, -2.5e3, true, null], "b": "x\"yA"} "#).unwrap();
+        assert_eq!(
+            value.get("a"),
+            Some(&JsonValue::Array(vec![
+                JsonValue::Number("1".to_string()),
+                JsonValue::Number("-2.5e3".to_string()),
+                JsonValue::Bool(true),
+                JsonValue::Null,
+            ]))
+        );
+        assert_eq!(value.get("b"), Some(&JsonValue::String("x\"yA".to_string())));
+        assert!(json::parse("{\"a\": 1,}").is_err());
+        assert!(json::parse("[1, 2").is_err());
+        assert!(json::parse("1 2").is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut rb_tree: rbtree::RBTree<i64> = rbtree::RBTree::new();
+        let mut avl_tree: avltree::AVLTree<i64> = avltree::AVLTree::new();
+        let mut bs_tree: bstree::BSTree<i64> = bstree::BSTree::new();
+        assert_eq!(rb_tree.to_json(), "null");
+        assert!(rbtree::RBTree::<i64>::from_json("null").unwrap().is_empty());
+        for number in [50, 20, 80, 10, 30, 25, 35, 33, 90, 85, 99, 5, 1, i64::MAX, i64::MIN] {
+            rb_tree.insert(number);
+            avl_tree.insert(number);
+            bs_tree.insert(number);
+        }
+        rb_tree.delete(30);
+        avl_tree.delete(30);
+        bs_tree.delete(30);
+
+        let rb_copy: rbtree::RBTree<i64> = rbtree::RBTree::from_json(&rb_tree.to_json()).unwrap();
+        assert_eq!(rb_copy.to_json(), rb_tree.to_json());
+        let avl_copy: avltree::AVLTree<i64> = avltree::AVLTree::from_json(&avl_tree.to_json()).unwrap();
+        assert_eq!(avl_copy.to_json(), avl_tree.to_json());
+        let bs_copy: bstree::BSTree<i64> = bstree::BSTree::from_json(&bs_tree.to_json()).unwrap();
+        assert_eq!(bs_copy.to_json(), bs_tree.to_json());
+        assert_eq!(bs_copy.get_max(), Some(i64::MAX));
+
+        // The rebuilt trees keep working.
+        let mut rb_copy = rb_copy;
+        for number in 100..200 {
+            rb_copy.insert(number);
+        }
+        for number in 100..150 {
+            rb_copy.delete(number);
+        }
+        assert_eq!(rb_copy.count_nodes(), rb_tree.count_nodes() + 50);
+        assert!(rbtree::RBTree::<i64>::from_json(&rb_copy.to_json()).is_ok());
+
+        // Any binary search tree shape can be read by BSTree.
+        assert!(bstree::BSTree::<i64>::from_json(&rb_tree.to_json()).is_ok());
+    }
+
+    #[test]
+    fn test_arena_and_persistent_trees() {
+        // The copies keep the shape, colors and heights of the JSON.
+        let mut rb_tree: rbtree::RBTree<i32> = rbtree::RBTree::new();
+        let mut avl_tree: avltree::AVLTree<i32> = avltree::AVLTree::new();
+        for number in 1..=10 {
+            rb_tree.insert(number);
+            avl_tree.insert(number);
+        }
+        rb_tree.delete(1);
+        avl_tree.delete(10);
+        fn copy<TR: Tree<i32> + FromJson>(text: &str) -> String {
+            TR::from_json(text).unwrap().to_json()
+        }
+        let (rb_text, avl_text) = (rb_tree.to_json(), avl_tree.to_json());
+        assert_eq!(copy::<ArenaRBTree<i32>>(&rb_text), rb_text);
+        assert_eq!(copy::<PersistentRBTree<i32>>(&rb_text), rb_text);
+        assert_eq!(copy::<ArenaAVLTree<i32>>(&avl_text), avl_text);
+        assert_eq!(copy::<PersistentAVLTree<i32>>(&avl_text), avl_text);
+        assert_eq!(PersistentRBTree::<i32>::from_json(&rb_text).unwrap().count_nodes(), 9);
+        // The copies go on like the trees they were read from.
+        let mut arena_rb_tree: ArenaRBTree<i32> = ArenaRBTree::from_json(&rb_text).unwrap();
+        let mut arena_avl_tree: ArenaAVLTree<i32> = ArenaAVLTree::from_json(&avl_text).unwrap();
+        for number in [4, 8, 2] {
+            rb_tree.delete(number);
+            arena_rb_tree.delete(number);
+            avl_tree.delete(number);
+            arena_avl_tree.delete(number);
+        }
+        assert_eq!(arena_rb_tree.to_json(), rb_tree.to_json());
+        assert_eq!(arena_avl_tree.to_json(), avl_tree.to_json());
+        assert!(PersistentAVLTree::<i32>::from_json("null").unwrap().is_empty());
+
+        // The same JSON as for RBTree and AVLTree is rejected.
+        let red_root = r#"{"key":1,"color":"red"}"#;
+        let red_red = r#"{"key":2,"color":"black","left":{"key":1,"color":"red","left":{"key":0,"color":"red"}}}"#;
+        for text in [red_root, red_red, r#"{"key":5,"left":{"key":7}}"#] {
+            assert!(ArenaRBTree::<i32>::from_json(text).is_err());
+            assert!(PersistentRBTree::<i32>::from_json(text).is_err());
+        }
+        let chain = r#"{"key":1,"right":{"key":2,"right":{"key":3}}}"#;
+        let wrong_height = r#"{"key":1,"height":1,"right":{"key":2}}"#;
+        for text in [chain, wrong_height] {
+            assert!(ArenaAVLTree::<i32>::from_json(text).is_err());
+            assert!(PersistentAVLTree::<i32>::from_json(text).is_err());
+        }
+    }
+
+    #[test]
+    fn test_invalid_trees() {
+        // Out of order and duplicate keys.
+        assert!(bstree::BSTree::<i32>::from_json(r#"{"key":5,"left":{"key":3,"right":{"key":7}}}"#).is_err());
+        assert!(bstree::BSTree::<i32>::from_json(r#"{"key":5,"left":{"key":5}}"#).is_err());
+        assert!(bstree::BSTree::<i32>::from_json(r#"{"key":"5"}"#).is_err());
+        assert!(bstree::BSTree::<i32>::from_json(r#"{"left":null}"#).is_err());
+        // Red-black violations.
+        assert!(rbtree::RBTree::<i32>::from_json(r#"{"key":2}"#).is_err());
+        assert!(rbtree::RBTree::<i32>::from_json(
+            r#"{"key":2,"color":"black","left":{"key":1,"color":"red","left":{"key":0,"color":"red"}}}"#
+        ).is_err());
+        assert!(rbtree::RBTree::<i32>::from_json(r#"{"key":2,"color":"black","left":{"key":1,"color":"black"}}"#).is_err());
+        assert!(rbtree::RBTree::<i32>::from_json(r#"{"key":2,"color":"black","left":{"key":1,"color":"red"}}"#).is_ok());
+        // AVL violations.
+        assert!(avltree::AVLTree::<i32>::from_json(r#"{"key":1,"right":{"key":2,"right":{"key":3}}}"#).is_err());
+        assert!(avltree::AVLTree::<i32>::from_json(r#"{"key":1,"height":1,"right":{"key":2}}"#).is_err());
+        assert!(avltree::AVLTree::<i32>::from_json(r#"{"key":1,"height":2,"right":{"key":2}}"#).is_ok());
+    }
+
+    #[test]
+    fn test_deeply_nested_input() {
+        let depth: usize = 200_000;
+        let nested: String = "[".repeat(depth) + &"]".repeat(depth);
+        assert!(json::parse(&nested).is_ok());
+        assert!(json::parse(&"[".repeat(depth)).is_err());
+        assert!(json::parse(&"{\"a\":".repeat(depth)).is_err());
+        assert!(rbtree::RBTree::<i32>::from_json(&nested).is_err());
+        assert!(bstree::BSTree::<i32>::from_json(&nested).is_err());
+
+        // A chain of nodes as deep, valid for BSTree only.
+        let mut chain: String = String::new();
+        for key in 0..depth {
+            chain.push_str(&format!("{{\"key\":{},\"color\":\"black\",\"right\":", key));
+        }
+        chain.push_str("null");
+        chain.push_str(&"}".repeat(depth));
+        assert_eq!(bstree::BSTree::<i32>::from_json(&chain).unwrap().get_height(), depth as u32);
+        assert!(avltree::AVLTree::<i32>::from_json(&chain).is_err());
+        assert!(rbtree::RBTree::<i32>::from_json(&chain).is_err());
+    }
+
+    #[test]
+    fn test_invalid_rbtree_frees_nodes() {
+        let invalid = [
+            r#"{"key":2,"color":"black","left":{"key":1,"color":"black"},"right":{"key":3,"color":"red"}}"#,
+            r#"{"key":2,"color":"red","left":{"key":1,"color":"black"},"right":{"key":3,"color":"black"}}"#,
+            r#"{"key":2,"color":"black","left":{"key":1,"color":"red","left":{"key":0,"color":"red"}}}"#,
+        ];
+        for text in invalid {
+            assert!(rbtree::RBTree::<CountedKey>::from_json(text).is_err());
+            assert_eq!(LIVE_KEYS.load(Ordering::SeqCst), 0);
+        }
+        let tree = rbtree::RBTree::<CountedKey>::from_json(r#"{"key":2,"color":"black","left":{"key":1,"color":"red"}}"#).unwrap();
+        assert_eq!(tree.count_nodes(), 2);
+        drop(tree);
+        assert_eq!(LIVE_KEYS.load(Ordering::SeqCst), 0);
+    }
+}
@@ -0,0 +1,100 @@
+use core::cmp::Ordering;
+use core::fmt::{self, Debug, Display};
+use core::num::ParseFloatError;
+use core::str::FromStr;
+use alloc::string::{String, ToString};
+
+use crate::json::{self, JsonKey, JsonValue};
+
+/// An `f64` usable as a tree key.
+///
+/// `f64` is only `PartialOrd`, because NaN compares to nothing. This wrapper
+/// orders floats with `f64::total_cmp` instead: -NaN < -inf < ... < -0.0 < 0.0
+/// < ... < inf < NaN. As a consequence -0.0 and 0.0 are two different keys.
+///
+/// # Examples
+///
+/// ```
+/// use trees::keys::TotalF64;
+/// use trees::rbtree::RBTree;
+/// use crate::trees::base::Tree;
+///
+/// let mut tree = RBTree::new();
+/// for value in [2.5, f64::NAN, -1.0, f64::INFINITY] {
+///     tree.insert(TotalF64(value));
+/// }
+/// assert_eq!(tree.get_min(), Some(TotalF64(-1.0)));
+/// assert!(tree.get_max().unwrap().0.is_nan());
+/// assert!(tree.contain(TotalF64(f64::NAN)));
+/// ```
+#[derive(Clone, Copy, Default)]
+pub struct TotalF64(pub f64);
+
+impl PartialEq for TotalF64 {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for TotalF64 {}
+
+impl PartialOrd for TotalF64 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TotalF64 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+// Printed like the float itself, so trees of TotalF64 render like trees of f64.
+impl Debug for TotalF64 {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.0, formatter)
+    }
+}
+
+impl Display for TotalF64 {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.0, formatter)
+    }
+}
+
+impl FromStr for TotalF64 {
+    type Err = ParseFloatError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        text.parse().map(TotalF64)
+    }
+}
+
+impl From<f64> for TotalF64 {
+    fn from(value: f64) -> Self {
+        TotalF64(value)
+    }
+}
+
+// JSON numbers cannot be NaN or infinite, those are written as the strings
+// "NaN", "inf" and "-inf" instead.
+impl JsonKey for TotalF64 {
+    fn to_json(&self) -> String {
+        match self.0.is_finite() {
+            true => self.0.to_string(),
+            false => json::quote(&self.0.to_string()),
+        }
+    }
+
+    fn from_json(value: &JsonValue) -> Option<Self> {
+        match value {
+            JsonValue::Number(number) => number.parse().ok(),
+            JsonValue::String(text) => match text.as_str() {
+                "NaN" | "inf" | "-inf" => text.parse().ok(),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod test {
+    extern crate std;
+
+    use crate::base::Tree;
+    use crate::keys::TotalF64;
+    use crate::{avltree, bstree, rbtree};
+    use std::format;
+    use std::string::{String, ToString};
+    use std::vec::Vec;
+
+    #[test]
+    fn test_total_f64() {
+        let mut values: Vec<TotalF64> = [1.5, f64::NAN, -0.0, 0.0, f64::NEG_INFINITY, -2.0]
+            .iter()
+            .map(|&value| TotalF64(value))
+            .collect();
+        values.sort();
+        assert_eq!(format!("{:?}", values), "[-inf, -2.0, -0.0, 0.0, 1.5, NaN]");
+        assert_eq!(TotalF64(f64::NAN), TotalF64(f64::NAN));
+        assert_ne!(TotalF64(-0.0), TotalF64(0.0));
+        assert_eq!("2.25".parse::<TotalF64>(), Ok(TotalF64(2.25)));
+        assert!("two".parse::<TotalF64>().is_err());
+        assert_eq!(TotalF64(0.5).to_string(), "0.5");
+    }
+
+    #[test]
+    fn test_string_keys() {
+        let words = ["pear", "apple", "fig", "kiwi", "banana", "cherry", "date", "grape"];
+        let mut rb_tree: rbtree::RBTree<String> = rbtree::RBTree::new();
+        let mut avl_tree: avltree::AVLTree<String> = avltree::AVLTree::new();
+        let mut bs_tree: bstree::BSTree<String> = bstree::BSTree::new();
+        for word in words {
+            rb_tree.insert(word.to_string());
+            avl_tree.insert(word.to_string());
+            bs_tree.insert(word.to_string());
+        }
+        let mut sorted: Vec<String> = words.iter().map(|word| word.to_string()).collect();
+        sorted.sort();
+        assert_eq!(rb_tree.range("a".to_string(), "z".to_string()), sorted);
+        assert_eq!(avl_tree.range("a".to_string(), "z".to_string()), sorted);
+        assert_eq!(bs_tree.range("a".to_string(), "z".to_string()), sorted);
+        assert_eq!(rb_tree.get_min(), Some("apple".to_string()));
+        assert_eq!(avl_tree.get_max(), Some("pear".to_string()));
+        assert_eq!(bs_tree.rank("d".to_string()), 3);
+
+        for word in ["apple", "kiwi", "pear"] {
+            rb_tree.delete(word.to_string());
+            avl_tree.delete(word.to_string());
+            bs_tree.delete(word.to_string());
+        }
+        for tree_keys in [rb_tree.range("a".to_string(), "z".to_string()), avl_tree.range("a".to_string(), "z".to_string()), bs_tree.range("a".to_string(), "z".to_string())] {
+            assert_eq!(tree_keys, ["banana", "cherry", "date", "fig", "grape"]);
+        }
+        assert!(!rb_tree.contain("kiwi".to_string()));
+        assert!(avl_tree.contain("fig".to_string()));
+        assert!(avl_tree.get_height() <= 3);
+        assert!(rb_tree.render().contains("\"grape\""));
+    }
+}
@@ -1,7 +1,42 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 pub mod base;
 pub mod avltree;
 pub mod rbtree;
 pub mod bstree;
+pub mod svg;
+pub mod json;
+pub mod observer;
+pub mod trace;
+pub mod stats;
+pub mod keys;
+pub mod workload;
+pub mod arena;
+pub mod arena_avltree;
+pub mod arena_rbtree;
+#[cfg(feature = "std")]
+pub mod concurrent;
+pub mod persistent_avltree;
+pub mod persistent_rbtree;
+pub mod frozen;
+// The baseline tests compare booleans with assert_eq! and loop over vec!s.
+#[allow(clippy::bool_assert_comparison, clippy::useless_vec)]
 mod avltree_test;
+#[allow(clippy::bool_assert_comparison, clippy::useless_vec)]
 mod rbtree_test;
-mod bstree_test;
\ No newline at end of file
+#[allow(clippy::bool_assert_comparison, clippy::useless_vec)]
+mod bstree_test;
+mod svg_test;
+mod json_test;
+mod observer_test;
+mod trace_test;
+mod stats_test;
+mod keys_test;
+mod workload_test;
+mod arena_test;
+#[cfg(feature = "std")]
+mod concurrent_test;
+mod persistent_test;
+mod frozen_test;
@@ -3,18 +3,57 @@
 // mod bstree;
 //mod base;
 mod cli;
+mod script;
+mod args;
+mod commands;
+mod compare;
+mod files;
+mod history;
+mod session;
+mod registry;
+mod script_test;
+mod args_test;
+mod commands_test;
+mod compare_test;
+mod files_test;
+mod history_test;
+mod session_test;
 
+use std::env;
+use std::process;
 
 // use crate::base::{TreeNode, Tree};
 // use rbtree::RBTree;
 // use rbtree::RBTreeNode;
-// use std::env;
 // use trees::avltree::AVLTree;
 // use trees::base::Tree;
 
+use args::Command;
+
 fn main() {
-    cli::welcome();
-    cli::start_cli();
+    let arguments: Vec<String> = env::args().skip(1).collect();
+    let command: Command = match args::parse(&arguments) {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, args::usage());
+            process::exit(2);
+        }
+    };
+    match command {
+        Command::Help => println!("{}", args::usage()),
+        Command::Version => println!("trees {}", env!("CARGO_PKG_VERSION")),
+        Command::Interactive => {
+            cli::welcome();
+            cli::start_cli();
+        }
+        Command::Run { script } => process::exit(script::run_script(script.as_deref())),
+        Command::Build { kind, from, formats } => process::exit(commands::build(kind, from.as_deref(), &formats)),
+        Command::Stats { kind, from } => process::exit(commands::stats(kind, from.as_deref())),
+        Command::Generate { distribution, count, seed, mix } => process::exit(commands::generate(distribution, count, seed, mix)),
+        Command::Bench { kinds, distribution, count, seed, mix, csv } => {
+            process::exit(commands::bench(&kinds, distribution, count, seed, mix, csv))
+        }
+    }
 
     // let mut tree = RBTree::new();
     // let v=vec![1, 2, 3, 4, 5, 6, 7];
@@ -0,0 +1,107 @@
+use core::fmt::Debug;
+use alloc::vec::Vec;
+
+use crate::rbtree::NodeColor;
+
+/// The direction of a rotation around its pivot node.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RotationDirection {
+    Left,
+    Right,
+}
+
+/// Something that happened inside a tree while it was being modified.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TreeEvent<T> {
+    /// A new element was added to the tree. Sent before the rebalancing it causes.
+    Inserted { key: T },
+    /// An element is being removed from the tree. Sent before the rebalancing it causes.
+    Deleted { key: T },
+    /// The subtree rooted at pivot was rotated, pivot moving down one level.
+    Rotated { pivot: T, direction: RotationDirection },
+    /// A red-black node changed its color.
+    Recolored { key: T, from: NodeColor, to: NodeColor },
+    /// A rebalancing case that needed rotations was completed at this node.
+    Rebalanced { key: T },
+}
+
+/// Receives the events of the tree it was constructed with.
+///
+/// Every method has an empty default body, and the trees use `NoopObserver`
+/// unless told otherwise, so an unused observer costs nothing.
+///
+/// # Examples
+///
+/// ```
+/// use trees::rbtree::RBTree;
+/// use trees::base::Tree;
+/// use trees::observer::{TreeEvent, TreeObserver};
+///
+/// #[derive(Default)]
+/// struct RotationCounter {
+///     rotations: u32,
+/// }
+///
+/// impl TreeObserver<i32> for RotationCounter {
+///     fn on_event(&mut self, event: &TreeEvent<i32>) {
+///         if let TreeEvent::Rotated { .. } = event {
+///             self.rotations += 1;
+///         }
+///     }
+/// }
+///
+/// let mut tree = RBTree::with_observer(RotationCounter::default());
+/// for i in 1..4 {
+///     tree.insert(i);
+/// }
+/// assert_eq!(tree.observer().rotations, 1);
+/// ```
+pub trait TreeObserver<T> {
+    fn on_event(&mut self, _event: &TreeEvent<T>) {}
+}
+
+/// The observer that ignores every event.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NoopObserver;
+
+impl<T> TreeObserver<T> for NoopObserver {}
+
+/// An observer that keeps every event it receives, in order.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EventLog<T: Debug> {
+    pub events: Vec<TreeEvent<T>>,
+}
+
+impl<T: Clone + Debug> TreeObserver<T> for EventLog<T> {
+    fn on_event(&mut self, event: &TreeEvent<T>) {
+        self.events.push(event.clone());
+    }
+}
+
+/// An observer that counts the rotations of its tree.
+///
+/// # Examples
+///
+/// ```
+/// use trees::avltree::AVLTree;
+/// use trees::base::Tree;
+/// use trees::observer::RotationCounter;
+///
+/// let mut tree = AVLTree::with_observer(RotationCounter::default());
+/// for i in [3, 1, 2] {
+///     tree.insert(i);
+/// }
+/// assert_eq!(tree.observer().rotations, 2);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RotationCounter {
+    pub rotations: u32,
+}
+
+impl<T> TreeObserver<T> for RotationCounter {
+    fn on_event(&mut self, event: &TreeEvent<T>) {
+        if let TreeEvent::Rotated { .. } = event {
+            self.rotations += 1;
+        }
+    }
+}
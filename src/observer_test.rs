@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod test {
+    extern crate std;
+
+    use crate::base::Tree;
+    use crate::observer::{EventLog, RotationCounter, RotationDirection, TreeEvent, TreeObserver};
+    use crate::rbtree::NodeColor;
+    use crate::{avltree, rbtree};
+    use std::vec;
+
+    #[derive(Default)]
+    struct Counter {
+        inserted: u32,
+        deleted: u32,
+        rotations: u32,
+    }
+
+    impl TreeObserver<i32> for Counter {
+        fn on_event(&mut self, event: &TreeEvent<i32>) {
+            match event {
+                TreeEvent::Inserted { .. } => self.inserted += 1,
+                TreeEvent::Deleted { .. } => self.deleted += 1,
+                TreeEvent::Rotated { .. } => self.rotations += 1,
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn test_rbtree_events() {
+        let mut rb_tree = rbtree::RBTree::with_observer(EventLog::default());
+        for number in [1, 2, 3] {
+            rb_tree.insert(number);
+        }
+        assert_eq!(
+            rb_tree.observer().events,
+            vec![
+                TreeEvent::Inserted { key: 1 },
+                TreeEvent::Inserted { key: 2 },
+                TreeEvent::Inserted { key: 3 },
+                TreeEvent::Recolored { key: 2, from: NodeColor::Red, to: NodeColor::Black },
+                TreeEvent::Recolored { key: 1, from: NodeColor::Black, to: NodeColor::Red },
+                TreeEvent::Rotated { pivot: 1, direction: RotationDirection::Left },
+                TreeEvent::Rebalanced { key: 1 },
+            ]
+        );
+        rb_tree.observer_mut().events.clear();
+        rb_tree.insert(3);
+        rb_tree.delete(99);
+        assert!(rb_tree.observer().events.is_empty());
+        rb_tree.delete(1);
+        assert_eq!(rb_tree.observer().events[0], TreeEvent::Deleted { key: 1 });
+    }
+
+    #[test]
+    fn test_avltree_events() {
+        let mut avl_tree = avltree::AVLTree::with_observer(EventLog::default());
+        for number in [3, 1, 2] {
+            avl_tree.insert(number);
+        }
+        assert_eq!(
+            avl_tree.observer().events,
+            vec![
+                TreeEvent::Inserted { key: 3 },
+                TreeEvent::Inserted { key: 1 },
+                TreeEvent::Inserted { key: 2 },
+                TreeEvent::Rotated { pivot: 1, direction: RotationDirection::Left },
+                TreeEvent::Rotated { pivot: 3, direction: RotationDirection::Right },
+                TreeEvent::Rebalanced { key: 3 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_counting_observer() {
+        let mut rb_tree = rbtree::RBTree::with_observer(Counter::default());
+        let mut avl_tree = avltree::AVLTree::with_observer(Counter::default());
+        for number in 0..100 {
+            rb_tree.insert(number);
+            avl_tree.insert(number);
+        }
+        for number in 0..50 {
+            rb_tree.delete(number);
+            avl_tree.delete(number);
+        }
+        for counter in [rb_tree.observer(), avl_tree.observer()] {
+            assert_eq!(counter.inserted, 100);
+            assert_eq!(counter.deleted, 50);
+            assert!(counter.rotations > 0);
+        }
+    }
+
+    #[test]
+    fn test_rotation_counter() {
+        let mut rb_tree = rbtree::RBTree::with_observer(RotationCounter::default());
+        let mut counter = rbtree::RBTree::with_observer(Counter::default());
+        for number in 0..100 {
+            rb_tree.insert(number);
+            counter.insert(number);
+        }
+        assert_eq!(rb_tree.observer().rotations, counter.observer().rotations);
+    }
+}
@@ -0,0 +1,276 @@
+use core::cmp::{max, Ordering};
+use core::fmt::Debug;
+use alloc::sync::Arc;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+
+use crate::avltree;
+use crate::base::{self, NodeRef, Tree, TreeNode};
+use crate::json::{self, FromJson, JsonKey};
+use crate::svg::NodeStyle;
+
+type Link<T> = Option<Arc<PersistentAVLNode<T>>>;
+
+/// A node of a PersistentAVLTree, shared by every version of the tree holding it.
+#[derive(Debug)]
+pub struct PersistentAVLNode<T: Ord + Clone + Debug> {
+    pub data: T,
+    left: Link<T>,
+    right: Link<T>,
+    height: u32,
+}
+
+impl<T: Ord + Clone + Debug> TreeNode<T> for PersistentAVLNode<T> {
+    fn get_left(&self) -> Option<&Self> {
+        self.left.as_deref()
+    }
+
+    fn get_right(&self) -> Option<&Self> {
+        self.right.as_deref()
+    }
+
+    fn get_data(&self) -> T {
+        self.data.clone()
+    }
+
+    fn json_attributes(&self) -> Vec<(&'static str, String)> {
+        vec![("height", self.height.to_string())]
+    }
+
+    fn svg_style(&self) -> NodeStyle {
+        NodeStyle {
+            annotation: Some(format!("h={} bf={}", self.height, height(&self.left) as i64 - height(&self.right) as i64)),
+            ..NodeStyle::default()
+        }
+    }
+}
+
+fn height<T: Ord + Clone + Debug>(link: &Link<T>) -> u32 {
+    link.as_ref().map_or(0, |node| node.height)
+}
+
+// A new node above two existing subtrees.
+fn node<T: Ord + Clone + Debug>(left: Link<T>, data: T, right: Link<T>) -> Link<T> {
+    let height: u32 = max(height(&left), height(&right)) + 1;
+    Some(Arc::new(PersistentAVLNode { data, left, right, height }))
+}
+
+// A new node above two subtrees whose heights differ by at most two,
+// rotated like AVLTree does when they differ by two.
+fn balance<T: Ord + Clone + Debug>(left: Link<T>, data: T, right: Link<T>) -> Link<T> {
+    if height(&left) > height(&right) + 1 {
+        let left = left.unwrap();
+        if height(&left.left) >= height(&left.right) {
+            // LL right rotation
+            node(left.left.clone(), left.data.clone(), node(left.right.clone(), data, right))
+        } else {
+            // LR left-right rotation
+            let inner = left.right.as_ref().unwrap();
+            node(
+                node(left.left.clone(), left.data.clone(), inner.left.clone()),
+                inner.data.clone(),
+                node(inner.right.clone(), data, right),
+            )
+        }
+    } else if height(&right) > height(&left) + 1 {
+        let right = right.unwrap();
+        if height(&right.right) >= height(&right.left) {
+            // RR left rotation
+            node(node(left, data, right.left.clone()), right.data.clone(), right.right.clone())
+        } else {
+            // RL right-left rotation
+            let inner = right.left.as_ref().unwrap();
+            node(
+                node(left, data, inner.left.clone()),
+                inner.data.clone(),
+                node(inner.right.clone(), right.data.clone(), right.right.clone()),
+            )
+        }
+    } else {
+        node(left, data, right)
+    }
+}
+
+fn insert<T: Ord + Clone + Debug>(link: &Link<T>, data: T) -> Link<T> {
+    match link {
+        None => node(None, data, None),
+        Some(current) => match data.cmp(&current.data) {
+            Ordering::Less => balance(insert(&current.left, data), current.data.clone(), current.right.clone()),
+            Ordering::Greater => balance(current.left.clone(), current.data.clone(), insert(&current.right, data)),
+            Ordering::Equal => link.clone(),
+        },
+    }
+}
+
+fn delete<T: Ord + Clone + Debug>(link: &Link<T>, data: &T) -> Link<T> {
+    let current = link.as_ref()?;
+    match current.data.cmp(data) {
+        Ordering::Greater => balance(delete(&current.left, data), current.data.clone(), current.right.clone()),
+        Ordering::Less => balance(current.left.clone(), current.data.clone(), delete(&current.right, data)),
+        Ordering::Equal => match (&current.left, &current.right) {
+            (Some(_), Some(right)) => {
+                // Like AVLTree, take the key of the successor and delete the successor.
+                let min_value: T = right.as_ref().get_min();
+                let right: Link<T> = delete(&current.right, &min_value);
+                balance(current.left.clone(), min_value, right)
+            }
+            (Some(child), None) | (None, Some(child)) => Some(child.clone()),
+            (None, None) => None,
+        },
+    }
+}
+
+/// An AVL tree whose versions all stay readable: `insert` and `delete`
+/// return a new tree and leave the old one untouched.
+///
+/// A new version copies the O(log n) nodes on the path to the changed key
+/// and shares every other subtree with the old version through `Arc`, so
+/// `clone` is O(1) and versions can be read from several threads. The tree
+/// balances like `AVLTree`, so the same operations give the same shape.
+///
+/// Through the `Tree` trait, `insert` and `delete` replace the tree with
+/// its new version.
+///
+/// # Examples
+/// ```
+/// use trees::persistent_avltree::PersistentAVLTree;
+/// use trees::base::Tree;
+///
+/// let empty = PersistentAVLTree::new();
+/// let one = empty.insert(1);
+/// let two = one.insert(2);
+/// let snapshot = two.clone();
+/// let three = two.insert(3).delete(1);
+///
+/// assert!(empty.is_empty());
+/// assert_eq!(one.count_nodes(), 1);
+/// assert_eq!(snapshot.range(0, 10), vec![1, 2]);
+/// assert_eq!(three.range(0, 10), vec![2, 3]);
+/// ```
+#[derive(Debug)]
+pub struct PersistentAVLTree<T: Ord + Clone + Debug> {
+    root: Link<T>,
+    len: u32,
+}
+
+impl<T: Ord + Clone + Debug> Clone for PersistentAVLTree<T> {
+    /// Another handle on the same version, sharing all its nodes.
+    fn clone(&self) -> Self {
+        PersistentAVLTree { root: self.root.clone(), len: self.len }
+    }
+}
+
+impl<T: Ord + Clone + Debug> Drop for PersistentAVLTree<T> {
+    // Frees the nodes no other version holds one by one, a shared node and
+    // everything below it being left to the versions still holding it.
+    fn drop(&mut self) {
+        let mut stack: Vec<Arc<PersistentAVLNode<T>>> = self.root.take().into_iter().collect();
+        while let Some(node) = stack.pop() {
+            if let Some(mut node) = Arc::into_inner(node) {
+                stack.extend(node.left.take());
+                stack.extend(node.right.take());
+            }
+        }
+    }
+}
+
+impl<T: Ord + Clone + Debug> Default for PersistentAVLTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord + Clone + Debug + JsonKey> FromJson for PersistentAVLTree<T> {
+    /// Builds a first version with the shape of the JSON, which has to be
+    /// balanced and to give the right heights, if any.
+    fn from_json(text: &str) -> Result<Self, String> {
+        let mut tree = Self::new();
+        if let Some(root) = json::parse_tree::<T>(text)? {
+            let root = json::fold(&root, |shape, left: Link<T>, right: Link<T>| {
+                let height: u32 = avltree::check_json_height(shape, height(&left), height(&right))?;
+                tree.len += 1;
+                Ok(Arc::new(PersistentAVLNode { data: shape.key.clone(), left, right, height }))
+            })?;
+            tree.root = Some(root);
+        }
+        Ok(tree)
+    }
+}
+
+impl<T: Ord + Clone + Debug> PersistentAVLTree<T> {
+    pub fn new() -> Self {
+        PersistentAVLTree { root: None, len: 0 }
+    }
+
+    /// The version of the tree holding data too.
+    #[must_use]
+    pub fn insert(&self, data: T) -> Self {
+        if self.contain(data.clone()) {
+            return self.clone();
+        }
+        PersistentAVLTree { root: insert(&self.root, data), len: self.len + 1 }
+    }
+
+    /// The version of the tree without data.
+    #[must_use]
+    pub fn delete(&self, data: T) -> Self {
+        if !self.contain(data.clone()) {
+            return self.clone();
+        }
+        PersistentAVLTree { root: delete(&self.root, &data), len: self.len - 1 }
+    }
+
+    /// Whether both versions are the very same nodes, which is the case of a
+    /// clone, or of a version where nothing was inserted nor deleted.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        match (&self.root, &other.root) {
+            (Some(root), Some(other_root)) => Arc::ptr_eq(root, other_root),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<T: Ord + Clone + Debug> Tree<T> for PersistentAVLTree<T> {
+    type Node<'a> = &'a PersistentAVLNode<T> where Self: 'a;
+
+    fn get_root(&self) -> Option<&PersistentAVLNode<T>> {
+        self.root.as_deref()
+    }
+
+    fn insert(&mut self, data: T) {
+        if self.contain(data.clone()) {
+            #[cfg(feature = "std")]
+            println!("This node already exists in the tree!");
+        } else {
+            *self = PersistentAVLTree::insert(self, data);
+        }
+    }
+
+    fn delete(&mut self, data: T) {
+        if !self.contain(data.clone()) {
+            #[cfg(feature = "std")]
+            println!("This node does not exist in the tree!");
+        } else {
+            *self = PersistentAVLTree::delete(self, data);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn print_tree(&self) {
+        print!("{}", self.render());
+    }
+
+    fn render(&self) -> String {
+        base::render_tree(self.get_root(), &|_| String::new())
+    }
+
+    fn get_height(&self) -> u32 {
+        height(&self.root)
+    }
+
+    fn count_nodes(&self) -> u32 {
+        self.len
+    }
+}
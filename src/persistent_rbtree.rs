@@ -0,0 +1,400 @@
+use core::cmp::Ordering;
+use core::fmt::Debug;
+use alloc::sync::Arc;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::vec;
+
+use crate::base::{self, Tree, TreeNode};
+use crate::json::{self, FromJson, JsonKey};
+use crate::rbtree::{self, NodeColor};
+use crate::svg::NodeStyle;
+
+type Link<T> = Option<Arc<PersistentRBNode<T>>>;
+
+/// A node of a PersistentRBTree, shared by every version of the tree holding it.
+#[derive(Debug)]
+pub struct PersistentRBNode<T: Ord + Clone + Debug> {
+    pub key: T,
+    color: NodeColor,
+    left: Link<T>,
+    right: Link<T>,
+}
+
+impl<T: Ord + Clone + Debug> PersistentRBNode<T> {
+    /// The color of the node, red or black.
+    pub fn get_color(&self) -> NodeColor {
+        self.color.clone()
+    }
+}
+
+impl<T: Ord + Clone + Debug> TreeNode<T> for PersistentRBNode<T> {
+    fn get_left(&self) -> Option<&Self> {
+        self.left.as_deref()
+    }
+
+    fn get_right(&self) -> Option<&Self> {
+        self.right.as_deref()
+    }
+
+    fn get_data(&self) -> T {
+        self.key.clone()
+    }
+
+    fn json_attributes(&self) -> Vec<(&'static str, String)> {
+        let color = if self.color == NodeColor::Black { "\"black\"" } else { "\"red\"" };
+        vec![("color", color.to_string())]
+    }
+
+    fn svg_style(&self) -> NodeStyle {
+        match self.color {
+            NodeColor::Red => NodeStyle { fill: "#cc2222", stroke: "#7a0000", text_color: "#ffffff", annotation: None },
+            NodeColor::Black => NodeStyle { fill: "#222222", stroke: "#000000", text_color: "#ffffff", annotation: None },
+        }
+    }
+}
+
+// The functions below are the red-black trees of Okasaki (insertion) and
+// Kahrs (deletion), written without parent links so that a new version only
+// copies the path it changes.
+
+fn node<T: Ord + Clone + Debug>(color: NodeColor, left: Link<T>, key: T, right: Link<T>) -> Link<T> {
+    Some(Arc::new(PersistentRBNode { key, color, left, right }))
+}
+
+// A copy of the node with another color.
+fn paint<T: Ord + Clone + Debug>(link: &Link<T>, color: NodeColor) -> Link<T> {
+    let current = link.as_ref()?;
+    node(color, current.left.clone(), current.key.clone(), current.right.clone())
+}
+
+fn is_red<T: Ord + Clone + Debug>(link: &Link<T>) -> bool {
+    matches!(link, Some(current) if current.color == NodeColor::Red)
+}
+
+// A black node, as opposed to a red node or an empty subtree.
+fn is_black_node<T: Ord + Clone + Debug>(link: &Link<T>) -> bool {
+    matches!(link, Some(current) if current.color == NodeColor::Black)
+}
+
+// A black node above left and right, or a red node with two black children
+// when one of them holds a red node with a red child.
+fn balance<T: Ord + Clone + Debug>(left: Link<T>, key: T, right: Link<T>) -> Link<T> {
+    if is_red(&left) && is_red(&right) {
+        return node(NodeColor::Red, paint(&left, NodeColor::Black), key, paint(&right, NodeColor::Black));
+    }
+    if let Some(outer) = left.as_ref().filter(|_| is_red(&left)) {
+        if is_red(&outer.left) {
+            return node(
+                NodeColor::Red,
+                paint(&outer.left, NodeColor::Black),
+                outer.key.clone(),
+                node(NodeColor::Black, outer.right.clone(), key, right),
+            );
+        }
+        if let Some(inner) = outer.right.as_ref().filter(|_| is_red(&outer.right)) {
+            return node(
+                NodeColor::Red,
+                node(NodeColor::Black, outer.left.clone(), outer.key.clone(), inner.left.clone()),
+                inner.key.clone(),
+                node(NodeColor::Black, inner.right.clone(), key, right),
+            );
+        }
+    }
+    if let Some(outer) = right.as_ref().filter(|_| is_red(&right)) {
+        if is_red(&outer.right) {
+            return node(
+                NodeColor::Red,
+                node(NodeColor::Black, left, key, outer.left.clone()),
+                outer.key.clone(),
+                paint(&outer.right, NodeColor::Black),
+            );
+        }
+        if let Some(inner) = outer.left.as_ref().filter(|_| is_red(&outer.left)) {
+            return node(
+                NodeColor::Red,
+                node(NodeColor::Black, left, key, inner.left.clone()),
+                inner.key.clone(),
+                node(NodeColor::Black, inner.right.clone(), outer.key.clone(), outer.right.clone()),
+            );
+        }
+    }
+    node(NodeColor::Black, left, key, right)
+}
+
+fn insert<T: Ord + Clone + Debug>(link: &Link<T>, key: T) -> Link<T> {
+    let current = match link {
+        None => return node(NodeColor::Red, None, key, None),
+        Some(current) => current,
+    };
+    let (left, right) = match key.cmp(&current.key) {
+        Ordering::Less => (insert(&current.left, key), current.right.clone()),
+        Ordering::Greater => (current.left.clone(), insert(&current.right, key)),
+        Ordering::Equal => return link.clone(),
+    };
+    match current.color {
+        NodeColor::Black => balance(left, current.key.clone(), right),
+        // A red-red pair is left for the black grandparent to balance.
+        NodeColor::Red => node(NodeColor::Red, left, current.key.clone(), right),
+    }
+}
+
+// Rebuilds a node whose left subtree lost one black node.
+fn balance_left<T: Ord + Clone + Debug>(left: Link<T>, key: T, right: Link<T>) -> Link<T> {
+    if is_red(&left) {
+        return node(NodeColor::Red, paint(&left, NodeColor::Black), key, right);
+    }
+    if is_black_node(&right) {
+        return balance(left, key, paint(&right, NodeColor::Red));
+    }
+    let outer = right.as_ref().expect("a red-black tree with a missing black node");
+    let inner = outer.left.as_ref().filter(|_| is_black_node(&outer.left)).expect("a red-black tree with a missing black node");
+    node(
+        NodeColor::Red,
+        node(NodeColor::Black, left, key, inner.left.clone()),
+        inner.key.clone(),
+        balance(inner.right.clone(), outer.key.clone(), paint(&outer.right, NodeColor::Red)),
+    )
+}
+
+// Rebuilds a node whose right subtree lost one black node.
+fn balance_right<T: Ord + Clone + Debug>(left: Link<T>, key: T, right: Link<T>) -> Link<T> {
+    if is_red(&right) {
+        return node(NodeColor::Red, left, key, paint(&right, NodeColor::Black));
+    }
+    if is_black_node(&left) {
+        return balance(paint(&left, NodeColor::Red), key, right);
+    }
+    let outer = left.as_ref().expect("a red-black tree with a missing black node");
+    let inner = outer.right.as_ref().filter(|_| is_black_node(&outer.right)).expect("a red-black tree with a missing black node");
+    node(
+        NodeColor::Red,
+        balance(paint(&outer.left, NodeColor::Red), outer.key.clone(), inner.left.clone()),
+        inner.key.clone(),
+        node(NodeColor::Black, inner.right.clone(), key, right),
+    )
+}
+
+// Joins the two subtrees of a deleted node, every key of left being smaller
+// than every key of right.
+fn join<T: Ord + Clone + Debug>(left: &Link<T>, right: &Link<T>) -> Link<T> {
+    let (first, second) = match (left, right) {
+        (None, _) => return right.clone(),
+        (_, None) => return left.clone(),
+        (Some(first), Some(second)) => (first, second),
+    };
+    match (&first.color, &second.color) {
+        (NodeColor::Red, NodeColor::Red) => {
+            let middle: Link<T> = join(&first.right, &second.left);
+            match &middle {
+                Some(inner) if inner.color == NodeColor::Red => node(
+                    NodeColor::Red,
+                    node(NodeColor::Red, first.left.clone(), first.key.clone(), inner.left.clone()),
+                    inner.key.clone(),
+                    node(NodeColor::Red, inner.right.clone(), second.key.clone(), second.right.clone()),
+                ),
+                _ => node(
+                    NodeColor::Red,
+                    first.left.clone(),
+                    first.key.clone(),
+                    node(NodeColor::Red, middle, second.key.clone(), second.right.clone()),
+                ),
+            }
+        }
+        (NodeColor::Black, NodeColor::Black) => {
+            let middle: Link<T> = join(&first.right, &second.left);
+            match &middle {
+                Some(inner) if inner.color == NodeColor::Red => node(
+                    NodeColor::Red,
+                    node(NodeColor::Black, first.left.clone(), first.key.clone(), inner.left.clone()),
+                    inner.key.clone(),
+                    node(NodeColor::Black, inner.right.clone(), second.key.clone(), second.right.clone()),
+                ),
+                _ => balance_left(
+                    first.left.clone(),
+                    first.key.clone(),
+                    node(NodeColor::Black, middle, second.key.clone(), second.right.clone()),
+                ),
+            }
+        }
+        (_, NodeColor::Red) => node(NodeColor::Red, join(left, &second.left), second.key.clone(), second.right.clone()),
+        (NodeColor::Red, _) => node(NodeColor::Red, first.left.clone(), first.key.clone(), join(&first.right, right)),
+    }
+}
+
+// Deletes a key that is in the tree.
+fn delete<T: Ord + Clone + Debug>(link: &Link<T>, key: &T) -> Link<T> {
+    let current = link.as_ref()?;
+    match key.cmp(&current.key) {
+        Ordering::Less if is_black_node(&current.left) => {
+            balance_left(delete(&current.left, key), current.key.clone(), current.right.clone())
+        }
+        Ordering::Less => node(NodeColor::Red, delete(&current.left, key), current.key.clone(), current.right.clone()),
+        Ordering::Greater if is_black_node(&current.right) => {
+            balance_right(current.left.clone(), current.key.clone(), delete(&current.right, key))
+        }
+        Ordering::Greater => node(NodeColor::Red, current.left.clone(), current.key.clone(), delete(&current.right, key)),
+        Ordering::Equal => join(&current.left, &current.right),
+    }
+}
+
+/// A red black tree whose versions all stay readable: `insert` and `delete`
+/// return a new tree and leave the old one untouched.
+///
+/// A new version copies the O(log n) nodes on the path to the changed key
+/// and shares every other subtree with the old version through `Arc`, so
+/// `clone` is O(1) and versions can be read from several threads. Without
+/// parent links it balances like the functional red black trees of Okasaki
+/// and Kahrs, so its shapes differ from those of `RBTree`.
+///
+/// Through the `Tree` trait, `insert` and `delete` replace the tree with
+/// its new version.
+///
+/// # Examples
+/// ```
+/// use trees::persistent_rbtree::PersistentRBTree;
+/// use trees::base::Tree;
+///
+/// let mut versions = vec![PersistentRBTree::new()];
+/// for i in 0..10 {
+///     let next = versions.last().unwrap().insert(i);
+///     versions.push(next);
+/// }
+/// for (count, version) in versions.iter().enumerate() {
+///     assert_eq!(version.count_nodes() as usize, count);
+/// }
+/// let smaller = versions[10].delete(4);
+/// assert!(!smaller.contain(4) && versions[10].contain(4));
+/// ```
+#[derive(Debug)]
+pub struct PersistentRBTree<T: Ord + Clone + Debug> {
+    root: Link<T>,
+    len: u32,
+}
+
+impl<T: Ord + Clone + Debug> Clone for PersistentRBTree<T> {
+    /// Another handle on the same version, sharing all its nodes.
+    fn clone(&self) -> Self {
+        PersistentRBTree { root: self.root.clone(), len: self.len }
+    }
+}
+
+impl<T: Ord + Clone + Debug> Drop for PersistentRBTree<T> {
+    // Frees the nodes no other version holds one by one, a shared node and
+    // everything below it being left to the versions still holding it.
+    fn drop(&mut self) {
+        let mut stack: Vec<Arc<PersistentRBNode<T>>> = self.root.take().into_iter().collect();
+        while let Some(node) = stack.pop() {
+            if let Some(mut node) = Arc::into_inner(node) {
+                stack.extend(node.left.take());
+                stack.extend(node.right.take());
+            }
+        }
+    }
+}
+
+impl<T: Ord + Clone + Debug> Default for PersistentRBTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord + Clone + Debug + JsonKey> FromJson for PersistentRBTree<T> {
+    /// Builds a first version holding the nodes of the JSON as they are,
+    /// once their colors pass the checks of `RBTree::from_json`.
+    fn from_json(text: &str) -> Result<Self, String> {
+        let mut tree = Self::new();
+        if let Some(root) = json::parse_tree::<T>(text)? {
+            rbtree::check_json_colors(&root)?;
+            let root = json::fold(&root, |shape, left: Link<T>, right: Link<T>| {
+                tree.len += 1;
+                Ok(Arc::new(PersistentRBNode { key: shape.key.clone(), color: rbtree::json_color(shape)?, left, right }))
+            })?;
+            tree.root = Some(root);
+        }
+        Ok(tree)
+    }
+}
+
+impl<T: Ord + Clone + Debug> PersistentRBTree<T> {
+    pub fn new() -> Self {
+        PersistentRBTree { root: None, len: 0 }
+    }
+
+    /// The version of the tree holding key too.
+    #[must_use]
+    pub fn insert(&self, key: T) -> Self {
+        if self.contain(key.clone()) {
+            return self.clone();
+        }
+        let root: Link<T> = insert(&self.root, key);
+        if is_red(&root) {
+            return PersistentRBTree { root: paint(&root, NodeColor::Black), len: self.len + 1 };
+        }
+        PersistentRBTree { root, len: self.len + 1 }
+    }
+
+    /// The version of the tree without key.
+    #[must_use]
+    pub fn delete(&self, key: T) -> Self {
+        if !self.contain(key.clone()) {
+            return self.clone();
+        }
+        let root: Link<T> = delete(&self.root, &key);
+        if is_red(&root) {
+            return PersistentRBTree { root: paint(&root, NodeColor::Black), len: self.len - 1 };
+        }
+        PersistentRBTree { root, len: self.len - 1 }
+    }
+
+    /// Whether both versions are the very same nodes, which is the case of a
+    /// clone, or of a version where nothing was inserted nor deleted.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        match (&self.root, &other.root) {
+            (Some(root), Some(other_root)) => Arc::ptr_eq(root, other_root),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<T: Ord + Clone + Debug> Tree<T> for PersistentRBTree<T> {
+    type Node<'a> = &'a PersistentRBNode<T> where Self: 'a;
+
+    fn get_root(&self) -> Option<&PersistentRBNode<T>> {
+        self.root.as_deref()
+    }
+
+    fn insert(&mut self, key: T) {
+        if self.contain(key.clone()) {
+            #[cfg(feature = "std")]
+            println!("The node already exists in the tree.");
+        } else {
+            *self = PersistentRBTree::insert(self, key);
+        }
+    }
+
+    fn delete(&mut self, key: T) {
+        if !self.contain(key.clone()) {
+            #[cfg(feature = "std")]
+            println!("The node of value {:#?} doesn't exist.", key);
+        } else {
+            *self = PersistentRBTree::delete(self, key);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn print_tree(&self) {
+        print!("{}", self.render());
+    }
+
+    fn count_nodes(&self) -> u32 {
+        self.len
+    }
+
+    fn render(&self) -> String {
+        base::render_tree(self.get_root(), &|node: &&PersistentRBNode<T>| {
+            if node.color == NodeColor::Black { " Black".to_string() } else { " Red".to_string() }
+        })
+    }
+}
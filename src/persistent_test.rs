@@ -0,0 +1,156 @@
+#[cfg(test)]
+mod test {
+    extern crate std;
+
+    use std::sync::Arc;
+    use std::thread;
+    use std::vec;
+    use std::vec::Vec;
+
+    use crate::avltree::AVLTree;
+    use crate::base::{Tree, TreeNode};
+    use crate::persistent_avltree::PersistentAVLTree;
+    use crate::persistent_rbtree::PersistentRBTree;
+    use crate::rbtree::RBTree;
+    use crate::workload::{Distribution, Mix, Operation, Workload};
+
+    #[test]
+    fn test_versions_are_unchanged() {
+        let mut avl_versions = vec![PersistentAVLTree::new()];
+        let mut rb_versions = vec![PersistentRBTree::new()];
+        for number in 0..50 {
+            avl_versions.push(avl_versions.last().unwrap().insert(number));
+            rb_versions.push(rb_versions.last().unwrap().insert(number));
+        }
+        for number in 0..50 {
+            avl_versions.push(avl_versions.last().unwrap().delete(number * 7 % 50));
+            rb_versions.push(rb_versions.last().unwrap().delete(number * 7 % 50));
+        }
+        for (index, (avl, rb)) in avl_versions.iter().zip(&rb_versions).enumerate() {
+            let expected: Vec<i32> = if index <= 50 {
+                (0..index as i32).collect()
+            } else {
+                let deleted: Vec<i32> = (0..index as i32 - 50).map(|number| number * 7 % 50).collect();
+                (0..50).filter(|number| !deleted.contains(number)).collect()
+            };
+            assert_eq!(avl.range(0, 50), expected);
+            assert_eq!(rb.range(0, 50), expected);
+        }
+    }
+
+    #[test]
+    fn test_untouched_subtrees_are_shared() {
+        let mut tree = PersistentAVLTree::new();
+        for number in 0..1023 {
+            tree = tree.insert(number);
+        }
+        let snapshot = tree.clone();
+        assert!(snapshot.ptr_eq(&tree));
+        assert!(tree.delete(5000).ptr_eq(&tree));
+
+        // Only the path from the root to the new key is copied.
+        let bigger = tree.insert(5000);
+        assert!(!bigger.ptr_eq(&tree));
+        let old_left = tree.get_root().unwrap().get_left().unwrap();
+        let new_left = bigger.get_root().unwrap().get_left().unwrap();
+        assert!(std::ptr::eq(old_left, new_left));
+        assert_eq!(tree.count_nodes(), 1023);
+        assert_eq!(bigger.count_nodes(), 1024);
+    }
+
+    #[test]
+    fn test_avl_same_shape_as_avltree() {
+        let mix = Mix { insert: 50, delete: 40, lookup: 10 };
+        for distribution in Distribution::ALL {
+            let workload = Workload::generate(distribution, 600, mix, 5);
+            let mut avl: AVLTree<i32> = AVLTree::new();
+            let mut persistent: PersistentAVLTree<i32> = PersistentAVLTree::new();
+            assert_eq!(workload.replay(&mut avl), workload.replay(&mut persistent));
+            assert_eq!(persistent.to_json(), avl.to_json(), "{:?}", distribution);
+            assert_eq!(persistent.render(), avl.render());
+            assert_eq!(persistent.get_height(), avl.get_height());
+        }
+    }
+
+    #[test]
+    fn test_rb_invariants() {
+        let mix = Mix { insert: 50, delete: 40, lookup: 10 };
+        for distribution in Distribution::ALL {
+            let workload = Workload::generate(distribution, 600, mix, 11);
+            let mut tree: PersistentRBTree<i32> = PersistentRBTree::new();
+            let mut keys: Vec<i32> = Vec::new();
+            for op in &workload.operations {
+                match *op {
+                    Operation::Insert(key) => {
+                        tree = tree.insert(key);
+                        if let Err(index) = keys.binary_search(&key) {
+                            keys.insert(index, key);
+                        }
+                    }
+                    Operation::Delete(key) => {
+                        tree = tree.delete(key);
+                        if let Ok(index) = keys.binary_search(&key) {
+                            keys.remove(index);
+                        }
+                    }
+                    Operation::Lookup(key) => assert_eq!(tree.contain(key), keys.binary_search(&key).is_ok()),
+                }
+                // Parsing the JSON checks the order, the colors and the black heights.
+                assert!(RBTree::<i32>::from_json(&tree.to_json()).is_ok(), "{:?}", distribution);
+            }
+            assert_eq!(tree.count_nodes() as usize, keys.len());
+        }
+    }
+
+    #[test]
+    fn test_versions_across_threads() {
+        let mut versions = Vec::new();
+        let mut tree = PersistentRBTree::new();
+        for number in 0..100 {
+            tree = tree.insert(number);
+            versions.push(tree.clone());
+        }
+        let versions = Arc::new(versions);
+        let readers: Vec<_> = (0..4)
+            .map(|reader| {
+                let versions = Arc::clone(&versions);
+                thread::spawn(move || {
+                    for (index, version) in versions.iter().enumerate().skip(reader) {
+                        assert_eq!(version.count_nodes() as usize, index + 1);
+                    }
+                })
+            })
+            .collect();
+        // The writer keeps on making versions while the readers go through the old ones.
+        for number in 0..100 {
+            tree = tree.delete(number);
+        }
+        for reader in readers {
+            reader.join().unwrap();
+        }
+        assert!(tree.is_empty());
+        assert_eq!(versions[99].render().lines().count(), 100);
+    }
+
+    #[test]
+    fn test_drop_keeps_shared_nodes() {
+        let mut tree = PersistentAVLTree::new();
+        for number in 0..1000 {
+            tree = tree.insert(number);
+        }
+        let newer = tree.insert(1000).delete(0);
+        drop(tree);
+        assert_eq!(newer.range(0, 2000), (1..=1000).collect::<Vec<i32>>());
+
+        let versions: Vec<PersistentRBTree<i32>> = (0..1000)
+            .scan(PersistentRBTree::new(), |tree, number| {
+                *tree = PersistentRBTree::insert(tree, number);
+                Some(tree.clone())
+            })
+            .collect();
+        let last = versions[999].clone();
+        drop(versions);
+        assert_eq!(last.count_nodes(), 1000);
+        assert!(RBTree::<i32>::from_json(&last.to_json()).is_ok());
+    }
+}
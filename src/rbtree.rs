@@ -1,11 +1,23 @@
-use std::cell::{RefCell, Ref, RefMut};
-use std::rc::Rc;
-use std::fmt::Debug;
+use core::cell::{RefCell, Ref, RefMut};
+use alloc::rc::Rc;
+use core::fmt::{Debug, Write};
+use core::cmp::Ordering;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
 
-use crate::base::{TreeNode, Tree};
+use crate::base::{self, NodeRef, Tree};
+use crate::svg::NodeStyle;
+use crate::json::{self, FromJson, JsonKey, JsonNode, JsonValue};
+use crate::observer::{NoopObserver, RotationDirection, TreeEvent, TreeObserver};
+use crate::trace::TraceStep;
+use crate::stats::StatsCounter;
+#[cfg(feature = "stats")]
+use crate::stats::TreeStats;
 
+/// The color of a node in the RBTree.
 #[derive(Clone, Debug, PartialEq)]
-enum NodeColor {
+pub enum NodeColor {
     Red,
     Black,
 }
@@ -16,41 +28,113 @@ enum NodeColor {
 type RcRefcellRBTNode<T> = Rc<RefCell<RBTreeNode<T>>>;
 type OptionNode<T> = Option<RcRefcellRBTNode<T>>;
 
+// The color a node of a parsed JSON tree gives, for every kind of red-black tree.
+pub(crate) fn json_color<T: Debug>(shape: &JsonNode<T>) -> Result<NodeColor, String> {
+    match shape.attribute("color") {
+        Some(JsonValue::String(color)) if color == "red" => Ok(NodeColor::Red),
+        Some(JsonValue::String(color)) if color == "black" => Ok(NodeColor::Black),
+        _ => Err(format!("The node {:?} needs a color, either \"red\" or \"black\"", shape.key)),
+    }
+}
+
+// Checks the colors of a parsed JSON tree: a black root, no red node with a
+// red child and the same number of black nodes on every path.
+pub(crate) fn check_json_colors<T: Debug>(root: &JsonNode<T>) -> Result<(), String> {
+    // Every node gets the black height of its subtree from those of its children.
+    json::fold(root, |shape: &JsonNode<T>, left: Option<u32>, right: Option<u32>| {
+        let color: NodeColor = json_color(shape)?;
+        for child in shape.left.iter().chain(shape.right.iter()) {
+            if color == NodeColor::Red && json_color(child)? == NodeColor::Red {
+                return Err(format!("The red node {:?} has a red child", shape.key));
+            }
+        }
+        let (left, right) = (left.unwrap_or(1), right.unwrap_or(1));
+        if left != right {
+            return Err(format!("The paths below {:?} have different black heights", shape.key));
+        }
+        let own: u32 = if color == NodeColor::Black { 1 } else { 0 };
+        Ok(left + own)
+    })?;
+    match json_color(root)? {
+        NodeColor::Black => Ok(()),
+        NodeColor::Red => Err("The root of a red-black tree must be black".to_string()),
+    }
+}
+
 /// RBTreeNode is a node in the RBTree.
 /// key is the value of the node.
 /// color is the color of the node, black or red.
+/// height and leaves are the height of the node and the number of leaves
+/// below it, itself included.
 #[derive(Debug)]
-pub struct RBTreeNode<T: Ord + Copy + Debug> {
+pub struct RBTreeNode<T: Ord + Clone + Debug> {
     pub key: T,
     color: NodeColor,
     parent: OptionNode<T>,
     left: OptionNode<T>,
     right: OptionNode<T>,
+    height: u32,
+    leaves: u32,
 }
 
 /// A red black tree is a kind of self-balancing binary search tree
 /// that can be used to store elements.
 /// The root node is the root node of the red black tree.
+/// The observer receives the rotations and recolorings done by the tree.
+/// The nodes link to their parent with `Rc`, so the tree cannot leave its
+/// thread; `ArenaRBTree` is the red black tree to share between threads.
 #[derive(Debug)]
-pub struct RBTree<T: Ord + Copy + Debug> {
+pub struct RBTree<T: Ord + Clone + Debug, O: TreeObserver<T> = NoopObserver> {
     root: OptionNode<T>,
+    len: u32,
+    observer: O,
+    trace: Option<Vec<TraceStep<T>>>,
+    stats: StatsCounter,
 }
 
-impl<T: Ord + Copy + Debug> TreeNode<T> for RBTreeNode<T> {
-    fn get_left(&self) -> &OptionNode<T> {
-        &self.left
+// The nodes are shared with their parent links, so the algorithms of
+// `NodeRef` read them through the shared pointer.
+impl<T: Ord + Clone + Debug> NodeRef<T> for RcRefcellRBTNode<T> {
+    fn key(&self) -> T {
+        self.borrow().key.clone()
     }
 
-    fn get_right(&self) -> &OptionNode<T> {
-        &self.right
+    fn left(&self) -> Option<Self> {
+        self.borrow().left.clone()
     }
 
-    fn get_data(&self) -> T {
-        self.key
+    fn right(&self) -> Option<Self> {
+        self.borrow().right.clone()
+    }
+
+    fn compare(&self, value: &T) -> Ordering {
+        self.borrow().key.cmp(value)
+    }
+
+    fn json_attributes(&self) -> Vec<(&'static str, String)> {
+        let color = if self.borrow().color == NodeColor::Black { "\"black\"" } else { "\"red\"" };
+        vec![("color", color.to_string())]
+    }
+
+    fn svg_style(&self) -> NodeStyle {
+        match self.borrow().color {
+            NodeColor::Red => NodeStyle {
+                fill: "#cc2222",
+                stroke: "#7a0000",
+                text_color: "#ffffff",
+                annotation: None,
+            },
+            NodeColor::Black => NodeStyle {
+                fill: "#222222",
+                stroke: "#000000",
+                text_color: "#ffffff",
+                annotation: None,
+            },
+        }
     }
 }
 
-impl<T: Ord + Copy + Debug> RBTreeNode<T> {
+impl<T: Ord + Clone + Debug> RBTreeNode<T> {
     fn new(value: T) -> RBTreeNode<T> {
         RBTreeNode {
             color: NodeColor::Red,
@@ -58,18 +142,38 @@ impl<T: Ord + Copy + Debug> RBTreeNode<T> {
             parent: None,
             left: None,
             right: None,
+            height: 1,
+            leaves: 1,
         }
     }
 
-    fn print_node(&self, prefix_space: &String, child_prefix: String, is_right: bool) {
+    /// The color of the node, red or black.
+    pub fn get_color(&self) -> NodeColor {
+        self.color.clone()
+    }
+
+    // Frees the nodes one by one. Every child holds its parent through
+    // `Rc`, so the links are cut first or no node would ever be freed, and
+    // the children are taken out so that no drop recurses down the tree.
+    fn drop_nodes(root: OptionNode<T>) {
+        let mut stack: Vec<RcRefcellRBTNode<T>> = root.into_iter().collect();
+        while let Some(node) = stack.pop() {
+            let mut node = node.borrow_mut();
+            node.parent = None;
+            stack.extend(node.left.take());
+            stack.extend(node.right.take());
+        }
+    }
+
+    fn render_node(&self, prefix_space: &String, child_prefix: String, is_right: bool, output: &mut String) {
         let color = if self.color == NodeColor::Black {
             "Black"
         } else {
             "Red"
         };
         match prefix_space.len() {
-            6 => println!("|____ {} {:?} {}", child_prefix, self.key, color),
-            _ => println!("{}{} {:?} {}", prefix_space, child_prefix, self.key, color),
+            6 => { let _ = writeln!(output, "|____ {} {:?} {}", child_prefix, self.key, color); }
+            _ => { let _ = writeln!(output, "{}{} {:?} {}", prefix_space, child_prefix, self.key, color); }
         }
         let mut new_prefix_space: String = String::from(prefix_space);
         match is_right {
@@ -87,31 +191,151 @@ impl<T: Ord + Copy + Debug> RBTreeNode<T> {
                 "      |____ ",
             ),
         }
-        if let Some(left) = self.get_left() {
-            left.borrow().print_node(
+        if let Some(left) = &self.left {
+            left.borrow().render_node(
                 &new_prefix_space,
                 "L".to_string(),
                 false,
+                output,
             );
         }
-        if let Some(right) = self.get_right() {
-            right.borrow().print_node(
+        if let Some(right) = &self.right {
+            right.borrow().render_node(
                 &new_prefix_space,
                 "R".to_string(),
                 true,
+                output,
             );
         }
     }
 }
 
 
-impl<T: Ord + Copy + Debug> RBTree<T> {
+impl<T: Ord + Clone + Debug, O: TreeObserver<T> + Default> Default for RBTree<T, O> {
+    fn default() -> Self {
+        Self::with_observer(O::default())
+    }
+}
+
+impl<T: Ord + Clone + Debug, O: TreeObserver<T>> Drop for RBTree<T, O> {
+    fn drop(&mut self) {
+        RBTreeNode::drop_nodes(self.root.take());
+    }
+}
+
+impl<T: Ord + Clone + Debug + JsonKey, O: TreeObserver<T> + Default> FromJson for RBTree<T, O> {
+    fn from_json(text: &str) -> Result<Self, String> {
+        Self::from_json(text)
+    }
+}
+
+impl<T: Ord + Clone + Debug> RBTree<T> {
     /// Makes a new empty RBTree.
     ///
     /// Does not allocate anything on its own.
     pub fn new() -> Self {
         RBTree {
             root: None,
+            len: 0,
+            observer: NoopObserver,
+            trace: None,
+            stats: StatsCounter::new(),
+        }
+    }
+}
+
+impl<T: Ord + Clone + Debug, O: TreeObserver<T>> RBTree<T, O> {
+    /// Makes a new empty RBTree reporting its events to the observer.
+    ///
+    /// # Examples
+    /// ```
+    /// use trees::rbtree::RBTree;
+    /// use trees::base::Tree;
+    /// use trees::observer::{EventLog, TreeEvent};
+    ///
+    /// let mut tree = RBTree::with_observer(EventLog::default());
+    /// tree.insert(1);
+    /// assert_eq!(tree.observer().events, vec![TreeEvent::Inserted { key: 1 }]);
+    /// ```
+    pub fn with_observer(observer: O) -> Self {
+        RBTree {
+            root: None,
+            len: 0,
+            observer,
+            trace: None,
+            stats: StatsCounter::new(),
+        }
+    }
+
+    /// The observer of the tree.
+    pub fn observer(&self) -> &O {
+        &self.observer
+    }
+
+    /// The observer of the tree, mutably.
+    pub fn observer_mut(&mut self) -> &mut O {
+        &mut self.observer
+    }
+
+    /// The counters of the tree since it was made or last reset.
+    ///
+    /// Only available with the `stats` feature.
+    ///
+    /// # Examples
+    /// ```
+    /// use trees::rbtree::RBTree;
+    /// use crate::trees::base::Tree;
+    ///
+    /// let mut tree = RBTree::new();
+    /// for i in 1..4 {
+    ///     tree.insert(i);
+    /// }
+    /// let stats = tree.stats();
+    /// assert_eq!(stats.allocations, 3);
+    /// assert_eq!(stats.single_rotations, 1);
+    /// tree.reset_stats();
+    /// assert_eq!(tree.stats().comparisons, 0);
+    /// ```
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> TreeStats {
+        self.stats.get()
+    }
+
+    /// Sets every counter of the tree back to zero.
+    ///
+    /// Only available with the `stats` feature.
+    #[cfg(feature = "stats")]
+    pub fn reset_stats(&mut self) {
+        self.stats.reset();
+    }
+
+    // Records a step of a traced insert or delete, does nothing otherwise.
+    fn _trace_step(&mut self, case: &str, nodes: Vec<T>) {
+        if self.trace.is_some() {
+            let snapshot: String = self.render();
+            if let Some(steps) = self.trace.as_mut() {
+                steps.push(TraceStep { case: case.to_string(), nodes, snapshot });
+            }
+        }
+    }
+
+    // Runs an insert or delete while recording its steps.
+    fn _traced(&mut self, operation: impl FnOnce(&mut Self)) -> Vec<TraceStep<T>> {
+        self.trace = Some(Vec::new());
+        operation(self);
+        let mut steps: Vec<TraceStep<T>> = self.trace.take().unwrap_or_default();
+        let snapshot: String = self.render();
+        if steps.last().map(|step| &step.snapshot) != Some(&snapshot) {
+            steps.push(TraceStep { case: "done".to_string(), nodes: Vec::new(), snapshot });
+        }
+        steps
+    }
+
+    fn _count_rotation(&self, double_rotation: bool) {
+        if double_rotation {
+            self.stats.double_rotation();
+        } else {
+            self.stats.single_rotation();
         }
     }
 
@@ -121,11 +345,16 @@ impl<T: Ord + Copy + Debug> RBTree<T> {
             // child = new_child;
             if child.borrow().parent.is_none() {
                 // 1. no root
-                Self::_change_color(&mut self.root.as_ref().unwrap(), NodeColor::Black);
+                let root: RcRefcellRBTNode<T> = self.root.clone().unwrap();
+                self._change_color(&mut &root, NodeColor::Black);
+                let key: T = root.borrow().key.clone();
+                self._trace_step("1 node is root", vec![key]);
                 return ;
             }
             if !Self::_is_parent_red(&child) {
                 // 2. parent black do nothing
+                let key: T = child.borrow().key.clone();
+                self._trace_step("2 parent is black", vec![key]);
                 return ;
             }
             // 3.parent is red
@@ -133,33 +362,42 @@ impl<T: Ord + Copy + Debug> RBTree<T> {
             // grandparent exists
             let grandparent: Rc<RefCell<RBTreeNode<T>>> = Rc::clone(parent.borrow().parent.as_ref().unwrap());
             let uncle: Rc<RefCell<RBTreeNode<T>>>;
+            let keys: Vec<T> = vec![child.borrow().key.clone(), parent.borrow().key.clone(), grandparent.borrow().key.clone()];
 
             // find uncle node
-            let parent_left_side: bool = RBTree::_is_left_child(&parent);
+            let parent_left_side: bool = Self::_is_left_child(&parent);
             if parent_left_side {
                 //3.2 uncle is right node. uncle black or is_none
                 if grandparent.borrow().right.is_none()
                     || grandparent.borrow().right.as_ref().unwrap().borrow().color == NodeColor::Black
                 {
-                    if !RBTree::_is_left_child(&child) {
+                    let double_rotation: bool = !Self::_is_left_child(&child);
+                    if double_rotation {
                         //3.2.2 child is right node
                         self._left_rotate(&parent);
                         parent = grandparent.borrow().left.as_ref().unwrap().clone();
                         // child = parent;
+                        self._trace_step("3.2.2 child is right node", keys.clone());
                     }
                     //3.2.1 child is left node
-                    RBTree::_change_color(&mut &parent, NodeColor::Black);
-                    RBTree::_change_color(&mut &grandparent, NodeColor::Red);
+                    self._change_color(&mut &parent, NodeColor::Black);
+                    self._change_color(&mut &grandparent, NodeColor::Red);
                     self._right_rotate(&grandparent);
                     // grandparent.borrow_mut().rotate_right();
+                    let key: T = grandparent.borrow().key.clone();
+                    self.observer.on_event(&TreeEvent::Rebalanced { key });
+                    self._count_rotation(double_rotation);
+                    self._trace_step("3.2.1 child is left node", keys);
                     return;
                 } else {
                     //3.2.3
                     // if right side uncle exists
                     uncle = grandparent.borrow().right.as_ref().unwrap().clone();
-                    RBTree::_change_color(&mut &parent, NodeColor::Black);
-                    RBTree::_change_color(&mut &uncle, NodeColor::Black);
-                    RBTree::_change_color(&mut &grandparent, NodeColor::Red);
+                    self._change_color(&mut &parent, NodeColor::Black);
+                    self._change_color(&mut &uncle, NodeColor::Black);
+                    self._change_color(&mut &grandparent, NodeColor::Red);
+                    let key: T = uncle.borrow().key.clone();
+                    self._trace_step("3.2.3 uncle is red", [keys, vec![key]].concat());
                     // We've solved the problem at our node, but grandparent may have the same issue, so run it again.
                     child = grandparent;
                     continue;
@@ -170,25 +408,33 @@ impl<T: Ord + Copy + Debug> RBTree<T> {
                     || grandparent.borrow().left.as_ref().unwrap().borrow().color
                     == NodeColor::Black
                 {
-                    if RBTree::_is_left_child(&child) {
+                    let double_rotation: bool = Self::_is_left_child(&child);
+                    if double_rotation {
                         //3.3.2 child is left child
                         self._right_rotate(&parent);
                         parent = grandparent.borrow().right.as_ref().unwrap().clone();
+                        self._trace_step("3.3.2 child is left child", keys.clone());
                     }
                     //3.3.1 child is right child
-                    RBTree::_change_color(&mut &parent, NodeColor::Black);
-                    RBTree::_change_color(&mut &grandparent, NodeColor::Red);
+                    self._change_color(&mut &parent, NodeColor::Black);
+                    self._change_color(&mut &grandparent, NodeColor::Red);
                     self._left_rotate(&grandparent);
 
                     // grandparent.borrow_mut().rotate_left();
+                    let key: T = grandparent.borrow().key.clone();
+                    self.observer.on_event(&TreeEvent::Rebalanced { key });
+                    self._count_rotation(double_rotation);
+                    self._trace_step("3.3.1 child is right child", keys);
                     return ;
                 } else {
                     //3.3.3
                     // if left side uncle exists
                     uncle = grandparent.borrow().left.as_ref().unwrap().clone();
-                    RBTree::_change_color(&mut &parent, NodeColor::Black);
-                    RBTree::_change_color(&mut &uncle, NodeColor::Black);
-                    RBTree::_change_color(&mut &grandparent, NodeColor::Red);
+                    self._change_color(&mut &parent, NodeColor::Black);
+                    self._change_color(&mut &uncle, NodeColor::Black);
+                    self._change_color(&mut &grandparent, NodeColor::Red);
+                    let key: T = uncle.borrow().key.clone();
+                    self._trace_step("3.3.3 uncle is red", [keys, vec![key]].concat());
                     // We've solved the problem at our node, but grandparent may have the same issue, so run it again.
                     child = grandparent;
                     continue;
@@ -197,29 +443,28 @@ impl<T: Ord + Copy + Debug> RBTree<T> {
         }
     }
 
-    fn search(&mut self, value: T) -> (bool, OptionNode<T>) {
+    fn search(&mut self, value: &T) -> (bool, OptionNode<T>) {
         let mut parent = None;
         if self.root.is_none() {
             return (false, None);
         }
         let mut child = Some(self.root.as_ref().unwrap().clone());
+        let mut depth: u64 = 0;
         while child.is_some() {
             parent = child;
+            depth += 1;
+            self.stats.reached_depth(depth);
             let parent_node = parent.as_ref().unwrap();
             // get the key
-            if parent_node.borrow().key > value {
-                child = match parent_node.borrow().left {
-                    Some(ref node) => Some(node.clone()),
-                    None => None,
-                };
-            } else if parent_node.borrow().key < value {
-                child = match parent_node.borrow().right {
-                    Some(ref node) => Some(node.clone()),
-                    None => None,
-                };
-            } else { return (true, parent); }
+            self.stats.comparison();
+            let ordering: Ordering = parent_node.borrow().key.cmp(value);
+            match ordering {
+                Ordering::Greater => child = parent_node.borrow().left.as_ref().map(|node| node.clone()),
+                Ordering::Less => child = parent_node.borrow().right.as_ref().map(|node| node.clone()),
+                Ordering::Equal => return (true, parent),
+            }
         }
-        return (false, parent);
+        (false, parent)
     }
 
     fn _is_left_child(node: &RcRefcellRBTNode<T>) -> bool {
@@ -240,6 +485,8 @@ impl<T: Ord + Copy + Debug> RBTree<T> {
     }
 
     fn _left_rotate(&mut self, rotation_node: &RcRefcellRBTNode<T>) {
+        let pivot: T = rotation_node.borrow().key.clone();
+        self.observer.on_event(&TreeEvent::Rotated { pivot, direction: RotationDirection::Left });
         // x_parent and r are Option
         {
             let parent: &OptionNode<T> = &rotation_node.borrow().parent;
@@ -268,9 +515,13 @@ impl<T: Ord + Copy + Debug> RBTree<T> {
             rotation_node.borrow_mut().right = None;
         }
         right_node.borrow_mut().left = Some(rotation_node.clone());
+        Self::_update_metadata(rotation_node);
+        Self::_update_metadata(&right_node);
     }
 
     fn _right_rotate(&mut self, rotation_node: &RcRefcellRBTNode<T>) {
+        let pivot: T = rotation_node.borrow().key.clone();
+        self.observer.on_event(&TreeEvent::Rotated { pivot, direction: RotationDirection::Right });
         {
             let parent: &OptionNode<T> = &rotation_node.borrow().parent;
             let left: &OptionNode<T> = &rotation_node.borrow().left;
@@ -295,6 +546,35 @@ impl<T: Ord + Copy + Debug> RBTree<T> {
             rotation_node.borrow_mut().left = None;
         }
         left_node.borrow_mut().right = Some(rotation_node.clone());
+        Self::_update_metadata(rotation_node);
+        Self::_update_metadata(&left_node);
+    }
+
+    // Recomputes the height and the leaves of node from its children, whose
+    // own are up to date.
+    fn _update_metadata(node: &RcRefcellRBTNode<T>) {
+        let mut current: RefMut<RBTreeNode<T>> = node.borrow_mut();
+        let (height, leaves) = match (&current.left, &current.right) {
+            (None, None) => (1, 1),
+            (left, right) => {
+                let (left, right) = (left.as_ref().map(|left| left.borrow()), right.as_ref().map(|right| right.borrow()));
+                let height: u32 = left.as_ref().map_or(0, |left| left.height).max(right.as_ref().map_or(0, |right| right.height)) + 1;
+                (height, left.as_ref().map_or(0, |left| left.leaves) + right.as_ref().map_or(0, |right| right.leaves))
+            }
+        };
+        current.height = height;
+        current.leaves = leaves;
+    }
+
+    // Recomputes the metadata of node and of every node above it, once the
+    // subtree of node has changed. Rotations keep the metadata of the nodes
+    // they move, so only the path up from the change is left to update.
+    fn _update_metadata_upwards(node: &RcRefcellRBTNode<T>) {
+        let mut next: OptionNode<T> = Some(node.clone());
+        while let Some(current) = next {
+            Self::_update_metadata(&current);
+            next = current.borrow().parent.clone();
+        }
     }
 
     // recursive find the right child
@@ -302,7 +582,7 @@ impl<T: Ord + Copy + Debug> RBTree<T> {
         if node.as_ref().unwrap().borrow().right.is_some() {
             return Self::_recur_right_child(node.as_ref().unwrap().borrow().right.clone());
         }
-        return node;
+        node
     }
 
     /// find the replacement node to replace the delete node
@@ -336,57 +616,67 @@ impl<T: Ord + Copy + Debug> RBTree<T> {
             && (replacement.is_none()
             || Self::_return_color(replacement.as_ref().unwrap()) == NodeColor::Black);
 
-        // 1. node is Leaf Node
-        if replacement.is_none() {
-            // node is root
-            if node.borrow().parent.is_none() {
-                self.root = None;
-            } else {
-                if double_black {
-                    self._delete_repair(node);
-                }
-                // delete node
-                if Self::_is_left_child(node) {
-                    parent.as_ref().unwrap().borrow_mut().left = None;
+        let key: T = node.borrow().key.clone();
+        match replacement {
+            // 1. node is Leaf Node
+            None => {
+                // node is root
+                if node.borrow().parent.is_none() {
+                    self.root = None;
                 } else {
-                    parent.as_ref().unwrap().borrow_mut().right = None;
+                    if double_black {
+                        self._delete_repair(node);
+                    }
+                    // delete node
+                    if Self::_is_left_child(node) {
+                        parent.as_ref().unwrap().borrow_mut().left = None;
+                    } else {
+                        parent.as_ref().unwrap().borrow_mut().right = None;
+                    }
+                    Self::_update_metadata_upwards(parent.as_ref().unwrap());
                 }
+                self._trace_step("1 node is leaf", vec![key]);
             }
-            return Ok(());
-        }
-        // 2. node only has one child
-        else if node.borrow().left.is_none() || node.borrow().right.is_none() {
-            // node is root, the tree only has two nodes.
-            if node.borrow().parent.is_none() {
-                let temp: T = replacement.as_ref().unwrap().borrow().key;
-                let mut root: RefMut<RBTreeNode<T>> = self.root.as_ref().unwrap().borrow_mut();
-                root.key = temp;
-                root.left = None;
-                root.right = None;
-            } else {
-                // set parent's child
-                if !Self::_is_left_child(node) {
-                    parent.as_ref().unwrap().borrow_mut().right = replacement.clone();
+            // 2. node only has one child
+            Some(replacement) if node.borrow().left.is_none() || node.borrow().right.is_none() => {
+                // node is root, the tree only has two nodes.
+                if node.borrow().parent.is_none() {
+                    let temp: T = replacement.borrow().key.clone();
+                    let mut root: RefMut<RBTreeNode<T>> = self.root.as_ref().unwrap().borrow_mut();
+                    root.key = temp.clone();
+                    root.left = None;
+                    root.right = None;
+                    drop(root);
+                    Self::_update_metadata(self.root.as_ref().unwrap());
+                    self._trace_step("2 node has one child", vec![key, temp]);
                 } else {
-                    parent.as_ref().unwrap().borrow_mut().left = replacement.clone();
-                }
-                // set replacement's parent
-                replacement.as_ref().unwrap().borrow_mut().parent = parent.clone();
-                // doubled black needs adjust, one red just set R black. impossible double red
-                if !double_black {
-                    Self::_change_color(&mut replacement.as_ref().unwrap(), NodeColor::Black);
-                } else {
-                    self._delete_repair(&replacement.unwrap());
+                    // set parent's child
+                    if !Self::_is_left_child(node) {
+                        parent.as_ref().unwrap().borrow_mut().right = Some(replacement.clone());
+                    } else {
+                        parent.as_ref().unwrap().borrow_mut().left = Some(replacement.clone());
+                    }
+                    // set replacement's parent
+                    replacement.borrow_mut().parent = parent.clone();
+                    let replacement_key: T = replacement.borrow().key.clone();
+                    self._trace_step("2 node has one child", vec![key, replacement_key]);
+                    // doubled black needs adjust, one red just set R black. impossible double red
+                    if !double_black {
+                        self._change_color(&mut &replacement, NodeColor::Black);
+                    } else {
+                        self._delete_repair(&replacement);
+                    }
+                    Self::_update_metadata_upwards(&replacement);
                 }
             }
-            return Ok(());
-        }
-        // 3. node has two children
-        else {
-            // actually delete replacement.
-            let temp: T = replacement.as_ref().unwrap().borrow().key;
-            node.borrow_mut().key = temp;
-            self._delete_private(&mut replacement.as_ref().unwrap()).unwrap();
+            // 3. node has two children
+            Some(replacement) => {
+                // actually delete replacement.
+                let temp: T = replacement.borrow().key.clone();
+                node.borrow_mut().key = temp.clone();
+                self._trace_step("3 node has two children", vec![key, temp]);
+                self._delete_private(&mut &replacement).unwrap();
+            }
         }
         Ok(())
     }
@@ -396,71 +686,92 @@ impl<T: Ord + Copy + Debug> RBTree<T> {
             return;
         }
         //get parent Node
-        let parent = Some(Rc::clone(node.borrow().parent.as_ref().unwrap()));
+        let parent: RcRefcellRBTNode<T> = Rc::clone(node.borrow().parent.as_ref().unwrap());
         // get sibling Node
         let sibling: OptionNode<T> = Self::_return_node_same_level(node);
 
         //1.no sibling, adjust parent
-        if sibling.is_none() {
-            self._delete_repair(&parent.unwrap())
-        } else {
-            // 2.sibling is black
-            if Self::_return_color(sibling.as_ref().unwrap()) == NodeColor::Black {
-                // 2.1 sibling doesnt have red child
-                if !Self::_has_red_child(sibling.as_ref().unwrap()) {
-                    Self::_change_color(&mut sibling.as_ref().unwrap(), NodeColor::Red);
-                    if Self::_return_color(parent.as_ref().unwrap()) == NodeColor::Red {
-                        Self::_change_color(&mut parent.as_ref().unwrap(), NodeColor::Black);
-                    } else {
-                        self._delete_repair(&parent.unwrap());
-                    }
-                } else {
-                    // 2.2.1 ll
-                    if !Self::_is_left_child(node) {
-                        if sibling.as_ref().unwrap().borrow().left.is_some() && Self::_return_color(sibling.as_ref().unwrap().borrow().left.as_ref().unwrap()) == NodeColor::Red {
-                            Self::_change_color(&mut sibling.as_ref().unwrap().borrow().left.as_ref().unwrap(), NodeColor::Black);
-                            let parent_color = Self::_return_color(parent.as_ref().unwrap());
-                            Self::_change_color(&mut sibling.as_ref().unwrap(), parent_color);
-                            self._right_rotate(parent.as_ref().unwrap());
-                            Self::_change_color(&mut parent.as_ref().unwrap(), NodeColor::Black);
+        let keys: Vec<T> = vec![node.borrow().key.clone(), parent.borrow().key.clone()];
+        match sibling.as_ref() {
+            None => {
+                self._trace_step("1 no sibling", keys);
+                self._delete_repair(&parent)
+            }
+            Some(mut sibling) => {
+                // 2.sibling is black
+                if Self::_return_color(sibling) == NodeColor::Black {
+                    // 2.1 sibling doesnt have red child
+                    let keys: Vec<T> = [keys, vec![sibling.borrow().key.clone()]].concat();
+                    if !Self::_has_red_child(sibling) {
+                        self._change_color(&mut sibling, NodeColor::Red);
+                        if Self::_return_color(&parent) == NodeColor::Red {
+                            self._change_color(&mut &parent, NodeColor::Black);
+                            self._trace_step("2.1 sibling has no red child", keys);
                         } else {
-                            // 2.2.2 lr
-                            let parent_color = Self::_return_color(parent.as_ref().unwrap());
-                            Self::_change_color(&mut sibling.as_ref().unwrap().borrow().right.as_ref().unwrap(), parent_color);
-                            self._left_rotate(sibling.as_ref().unwrap());
-                            self._right_rotate(parent.as_ref().unwrap());
-                            Self::_change_color(&mut parent.as_ref().unwrap(), NodeColor::Black);
+                            self._trace_step("2.1 sibling has no red child", keys);
+                            self._delete_repair(&parent);
                         }
                     } else {
-                        // 2.2.3 rl
-                        if sibling.as_ref().unwrap().borrow().left.is_some() && Self::_return_color(sibling.as_ref().unwrap().borrow().left.as_ref().unwrap()) == NodeColor::Red {
-                            let parent_color: NodeColor = Self::_return_color(parent.as_ref().unwrap());
-                            Self::_change_color(&mut sibling.as_ref().unwrap().borrow().left.as_ref().unwrap(), parent_color);
-                            self._right_rotate(sibling.as_ref().unwrap());
-                            self._left_rotate(parent.as_ref().unwrap());
-                            Self::_change_color(&mut parent.as_ref().unwrap(), NodeColor::Black);
+                        // 2.2.1 ll
+                        if !Self::_is_left_child(node) {
+                            if sibling.borrow().left.is_some() && Self::_return_color(sibling.borrow().left.as_ref().unwrap()) == NodeColor::Red {
+                                self._change_color(&mut sibling.borrow().left.as_ref().unwrap(), NodeColor::Black);
+                                let parent_color = Self::_return_color(&parent);
+                                self._change_color(&mut sibling, parent_color);
+                                self._right_rotate(&parent);
+                                self._change_color(&mut &parent, NodeColor::Black);
+                                self.stats.single_rotation();
+                                self._trace_step("2.2.1 ll", keys);
+                            } else {
+                                // 2.2.2 lr
+                                let parent_color = Self::_return_color(&parent);
+                                self._change_color(&mut sibling.borrow().right.as_ref().unwrap(), parent_color);
+                                self._left_rotate(sibling);
+                                self._right_rotate(&parent);
+                                self._change_color(&mut &parent, NodeColor::Black);
+                                self.stats.double_rotation();
+                                self._trace_step("2.2.2 lr", keys);
+                            }
                         } else {
-                            // 2.2.4 rr
-                            Self::_change_color(&mut sibling.as_ref().unwrap().borrow().right.as_ref().unwrap(), NodeColor::Black);
-                            let parent_color: NodeColor = Self::_return_color(parent.as_ref().unwrap());
-                            Self::_change_color(&mut sibling.as_ref().unwrap(), parent_color);
-                            self._left_rotate(parent.as_ref().unwrap());
-                            Self::_change_color(&mut parent.as_ref().unwrap(), NodeColor::Black);
+                            // 2.2.3 rl
+                            if sibling.borrow().left.is_some() && Self::_return_color(sibling.borrow().left.as_ref().unwrap()) == NodeColor::Red {
+                                let parent_color: NodeColor = Self::_return_color(&parent);
+                                self._change_color(&mut sibling.borrow().left.as_ref().unwrap(), parent_color);
+                                self._right_rotate(sibling);
+                                self._left_rotate(&parent);
+                                self._change_color(&mut &parent, NodeColor::Black);
+                                self.stats.double_rotation();
+                                self._trace_step("2.2.3 rl", keys);
+                            } else {
+                                // 2.2.4 rr
+                                self._change_color(&mut sibling.borrow().right.as_ref().unwrap(), NodeColor::Black);
+                                let parent_color: NodeColor = Self::_return_color(&parent);
+                                self._change_color(&mut sibling, parent_color);
+                                self._left_rotate(&parent);
+                                self._change_color(&mut &parent, NodeColor::Black);
+                                self.stats.single_rotation();
+                                self._trace_step("2.2.4 rr", keys);
+                            }
                         }
+                        let key: T = parent.borrow().key.clone();
+                        self.observer.on_event(&TreeEvent::Rebalanced { key });
                     }
                 }
-            }
-            // 3.sibling is red
-            else {
-                Self::_change_color(&mut sibling.as_ref().unwrap(), NodeColor::Black);
-                Self::_change_color(&mut parent.as_ref().unwrap(), NodeColor::Red);
-                // if R is left_child. left_rotate
-                if Self::_is_left_child(node) {
-                    self._left_rotate(parent.as_ref().unwrap());
-                } else {
-                    self._right_rotate(parent.as_ref().unwrap());
+                // 3.sibling is red
+                else {
+                    self._change_color(&mut sibling, NodeColor::Black);
+                    self._change_color(&mut &parent, NodeColor::Red);
+                    // if R is left_child. left_rotate
+                    if Self::_is_left_child(node) {
+                        self._left_rotate(&parent);
+                    } else {
+                        self._right_rotate(&parent);
+                    }
+                    let keys: Vec<T> = [keys, vec![sibling.borrow().key.clone()]].concat();
+                    self.stats.single_rotation();
+                    self._trace_step("3 sibling is red", keys);
+                    self._delete_repair(node);
                 }
-                self._delete_repair(node);
             }
         }
     }
@@ -469,21 +780,27 @@ impl<T: Ord + Copy + Debug> RBTree<T> {
         node.borrow().color.clone()
     }
 
-    fn _change_color(node: &mut &RcRefcellRBTNode<T>, color: NodeColor) {
-        node.borrow_mut().color = color;
+    fn _change_color(&mut self, node: &mut &RcRefcellRBTNode<T>, color: NodeColor) {
+        let from: NodeColor = node.borrow().color.clone();
+        if from != color {
+            let key: T = node.borrow().key.clone();
+            node.borrow_mut().color = color.clone();
+            self.stats.recoloring();
+            self.observer.on_event(&TreeEvent::Recolored { key, from, to: color });
+        }
     }
 
     fn _return_node_same_level(node:&RcRefcellRBTNode<T>)->OptionNode<T> {
         let borrowed_node=node.borrow();
-        if borrowed_node.parent.is_some() {
-            let parent_node=&borrowed_node.parent.as_ref().unwrap().borrow();
+        if let Some(parent) = &borrowed_node.parent {
+            let parent_node=&parent.borrow();
             if Self::_is_left_child(node) {
                 return parent_node.right.clone()
             }else {
                 return parent_node.left.clone()
             }
         }
-        return None
+        None
     }
 
     fn _has_red_child(node: &RcRefcellRBTNode<T>) -> bool {
@@ -492,6 +809,136 @@ impl<T: Ord + Copy + Debug> RBTree<T> {
         condition_one || condition_two
     }
 
+    // Builds a node of a parsed JSON tree that passed `check_json_colors` on
+    // its built children, for `json::fold`.
+    fn _from_json_node(shape: &JsonNode<T>, left: OptionNode<T>, right: OptionNode<T>) -> Result<RcRefcellRBTNode<T>, String> {
+        let color: NodeColor = json_color(shape)?;
+        let node: RcRefcellRBTNode<T> = Rc::new(RefCell::new(RBTreeNode::new(shape.key.clone())));
+        for child in left.iter().chain(right.iter()) {
+            child.borrow_mut().parent = Some(node.clone());
+        }
+        {
+            let mut current: RefMut<RBTreeNode<T>> = node.borrow_mut();
+            current.color = color;
+            current.left = left;
+            current.right = right;
+        }
+        Self::_update_metadata(&node);
+        Ok(node)
+    }
+
+    // Builds a balanced subtree from the next count keys, which come in
+    // ascending order: the smaller half goes left and the larger half right.
+    // Halving the counts puts every leaf on the last two levels, so the
+    // nodes of the last level are red when it is not full and all the
+    // others are black.
+    fn _from_sorted(keys: &mut alloc::vec::IntoIter<T>, count: usize, depth: u32, red_depth: Option<u32>) -> OptionNode<T> {
+        if count == 0 {
+            return None;
+        }
+        let left: OptionNode<T> = Self::_from_sorted(keys, (count - 1) / 2, depth + 1, red_depth);
+        let node: RcRefcellRBTNode<T> = Rc::new(RefCell::new(RBTreeNode::new(keys.next().unwrap())));
+        let right: OptionNode<T> = Self::_from_sorted(keys, count / 2, depth + 1, red_depth);
+        for child in left.iter().chain(right.iter()) {
+            child.borrow_mut().parent = Some(node.clone());
+        }
+        {
+            let mut current: RefMut<RBTreeNode<T>> = node.borrow_mut();
+            current.color = if red_depth == Some(depth) { NodeColor::Red } else { NodeColor::Black };
+            current.left = left;
+            current.right = right;
+        }
+        Self::_update_metadata(&node);
+        Some(node)
+    }
+
+    /// Builds a balanced RBTree from keys in strictly ascending order, in
+    /// linear time instead of inserting them one by one.
+    ///
+    /// The keys are not checked, unsorted or repeated keys giving a tree
+    /// that is not a search tree; see `try_from_sorted_iter`.
+    ///
+    /// # Examples
+    /// ```
+    /// use trees::rbtree::RBTree;
+    /// use crate::trees::base::Tree;
+    ///
+    /// let tree: RBTree<i32> = RBTree::from_sorted_iter(1..=4);
+    /// assert_eq!(tree.render(), "Root 2 Black\n|____ L 1 Black\n|____ R 3 Black\n      |____ R 4 Red\n");
+    /// ```
+    pub fn from_sorted_iter<I: IntoIterator<Item = T>>(keys: I) -> Self
+    where
+        O: Default,
+    {
+        let keys: Vec<T> = keys.into_iter().collect();
+        let count: usize = keys.len();
+        // A full last level stays black, as does a lone root.
+        let red_depth: Option<u32> = match count {
+            0 => None,
+            _ if (count + 1).is_power_of_two() => None,
+            _ => Some(count.ilog2()),
+        };
+        let mut tree = Self::with_observer(O::default());
+        tree.root = Self::_from_sorted(&mut keys.into_iter(), count, 0, red_depth);
+        tree.len = count as u32;
+        tree
+    }
+
+    /// Builds a balanced RBTree like `from_sorted_iter`, failing when the
+    /// keys are not in strictly ascending order.
+    ///
+    /// # Examples
+    /// ```
+    /// use trees::rbtree::RBTree;
+    ///
+    /// assert!(RBTree::<i32>::try_from_sorted_iter(vec![1, 2, 3]).is_ok());
+    /// assert!(RBTree::<i32>::try_from_sorted_iter(vec![1, 2, 2]).is_err());
+    /// ```
+    pub fn try_from_sorted_iter<I: IntoIterator<Item = T>>(keys: I) -> Result<Self, String>
+    where
+        O: Default,
+    {
+        Ok(Self::from_sorted_iter(base::collect_sorted(keys)?))
+    }
+
+    /// Rebuilds a RBTree from the JSON written by `Tree::to_json`.
+    ///
+    /// The keys must be in binary search tree order and every node needs a
+    /// color, with a black root, no red node having a red child and the same
+    /// number of black nodes on every path.
+    ///
+    /// # Examples
+    /// ```
+    /// use trees::rbtree::RBTree;
+    /// use crate::trees::base::Tree;
+    ///
+    /// let mut tree = RBTree::new();
+    /// for i in 0..10 {
+    ///     tree.insert(i);
+    /// }
+    /// let copy: RBTree<i32> = RBTree::from_json(&tree.to_json()).unwrap();
+    /// assert_eq!(copy.to_json(), tree.to_json());
+    ///
+    /// // A red root is rejected.
+    /// assert!(RBTree::<i32>::from_json(r#"{"key":1,"color":"red"}"#).is_err());
+    /// ```
+    pub fn from_json(text: &str) -> Result<Self, String>
+    where
+        T: JsonKey,
+        O: Default,
+    {
+        let mut tree = Self::with_observer(O::default());
+        if let Some(root) = json::parse_tree::<T>(text)? {
+            // The colors are checked before any node is built: nodes hold
+            // their parents, so a tree abandoned halfway would never be freed.
+            check_json_colors(&root)?;
+            let root: RcRefcellRBTNode<T> = json::fold(&root, Self::_from_json_node)?;
+            tree.len = root.count_nodes();
+            tree.root = Some(root);
+        }
+        Ok(tree)
+    }
+
     /// Clear the RBTree, removing all elements.
     ///
     /// # Examples
@@ -505,12 +952,17 @@ impl<T: Ord + Copy + Debug> RBTree<T> {
     /// assert!(tree.is_empty());
     /// ```
     pub fn clear(&mut self) {
-        *self = RBTree::new();
+        self.stats.frees(self.count_nodes() as u64);
+        RBTreeNode::drop_nodes(self.root.take());
+        self.len = 0;
+        #[cfg(feature = "std")]
         println!("Clear operation is complete!");
     }
 }
 
-impl<T: Ord + Copy + Debug> Tree<T, RBTreeNode<T>> for RBTree<T> {
+impl<T: Ord + Clone + Debug, O: TreeObserver<T>> Tree<T> for RBTree<T, O> {
+    type Node<'a> = Rc<RefCell<RBTreeNode<T>>> where Self: 'a;
+
     /// Return the root node of the RBTree.
     ///
     /// # Examples
@@ -536,8 +988,24 @@ impl<T: Ord + Copy + Debug> Tree<T, RBTreeNode<T>> for RBTree<T> {
     /// println!("The value of root is {}",node.as_ref().unwrap().borrow().key);
     /// assert_eq!(node.as_ref().unwrap().borrow().key, 2);
     /// ```
-    fn get_root(&self) -> &OptionNode<T> {
-        &self.root
+    fn get_root(&self) -> OptionNode<T> {
+        self.root.clone()
+    }
+
+    fn contain(&self, value: T) -> bool {
+        let mut node: OptionNode<T> = self.root.clone();
+        let mut depth: u64 = 0;
+        while let Some(current) = node {
+            depth += 1;
+            self.stats.reached_depth(depth);
+            self.stats.comparison();
+            node = match current.borrow().key.cmp(&value) {
+                Ordering::Greater => current.borrow().left.clone(),
+                Ordering::Less => current.borrow().right.clone(),
+                Ordering::Equal => return true,
+            };
+        }
+        false
     }
 
     /// Inserting a new element.
@@ -560,29 +1028,43 @@ impl<T: Ord + Copy + Debug> Tree<T, RBTreeNode<T>> for RBTree<T> {
     fn insert(&mut self, value: T) {
         match self.is_empty() {
             true => { // 1. tree is empty
-                let mut new_node = RBTreeNode::new(value);
+                let mut new_node = RBTreeNode::new(value.clone());
                 new_node.color = NodeColor::Black;
                 self.root = Some(Rc::new(RefCell::new(new_node)));
+                self.len += 1;
+                self.stats.allocation();
+                self.observer.on_event(&TreeEvent::Inserted { key: value.clone() });
+                self._trace_step("1 node is root", vec![value]);
             }
             false => {
-                let (found, parent_option) = self.search(value);
+                let (found, parent_option) = self.search(&value);
                 match found {
-                    true => (println!("The node already exists in the tree.")), // 2. node already exists
+                    true => { // 2. node already exists
+                        #[cfg(feature = "std")]
+                        println!("The node already exists in the tree.");
+                        self._trace_step("already exists", vec![value]);
+                    }
                     false => {
                         // 3. insert node
+                        self.stats.comparison();
                         let child_belongs_on_left: bool = value < parent_option.as_ref().unwrap().borrow().key;
-                        let new_child_node: RcRefcellRBTNode<T> = Rc::new(RefCell::new(RBTreeNode::new(value)));
+                        let new_child_node: RcRefcellRBTNode<T> = Rc::new(RefCell::new(RBTreeNode::new(value.clone())));
+                        self.stats.allocation();
                         let new_child_ref_clone: RcRefcellRBTNode<T> = new_child_node.clone();
                         let new_child = Some(new_child_node);
                         // set the new_child's parent
-                        let parent_ref: RcRefcellRBTNode<T> = Rc::clone(&parent_option.as_ref().unwrap());
+                        let parent_ref: RcRefcellRBTNode<T> = Rc::clone(parent_option.as_ref().unwrap());
                         new_child.as_ref().unwrap().borrow_mut().parent = Some(parent_ref);
                         // put it on the side that it should be on
                         match child_belongs_on_left {
                             true => parent_option.as_ref().unwrap().borrow_mut().left = new_child,
                             false => parent_option.as_ref().unwrap().borrow_mut().right = new_child,
                         }
-                        self._insert_repair(new_child_ref_clone);
+                        self.observer.on_event(&TreeEvent::Inserted { key: value.clone() });
+                        self._trace_step("insert as red leaf", vec![value]);
+                        self.len += 1;
+                        self._insert_repair(new_child_ref_clone.clone());
+                        Self::_update_metadata_upwards(&new_child_ref_clone);
                     }
                 }
             }
@@ -615,13 +1097,18 @@ impl<T: Ord + Copy + Debug> Tree<T, RBTreeNode<T>> for RBTree<T> {
     /// assert_eq!(tree.count_nodes(), 6);
     /// ```
     fn delete(&mut self, value: T) {
-        let (flag, searched_node) = self.search(value);
+        let (flag, searched_node) = self.search(&value);
         match flag {
             false => {
+                #[cfg(feature = "std")]
                 println!("The node of value {:#?} doesn't exist.",value);
+                self._trace_step("not found", vec![value]);
             }
             true => {
                 let mut searched_node_ref: &RcRefcellRBTNode<T> = searched_node.as_ref().unwrap();
+                self.observer.on_event(&TreeEvent::Deleted { key: value });
+                self.stats.frees(1);
+                self.len -= 1;
                 let _ = self._delete_private(&mut searched_node_ref);
             }
         };
@@ -648,14 +1135,62 @@ impl<T: Ord + Copy + Debug> Tree<T, RBTreeNode<T>> for RBTree<T> {
     /// //             |____ L 5 Red
     /// //             |____ R 7 Red
     /// ```
+    #[cfg(feature = "std")]
     fn print_tree(&self) {
-        match &self.get_root() {
-            None => println!("This tree is empty!"),
-            Some(root) => root.borrow().print_node(
+        print!("{}", self.render());
+    }
+
+    /// Inserting a new element, recording every repair case applied.
+    ///
+    /// # Examples
+    /// ```
+    /// use trees::rbtree::RBTree;
+    /// use crate::trees::base::Tree;
+    ///
+    /// let mut tree = RBTree::new();
+    /// for i in [5, 3] {
+    ///     tree.insert(i);
+    /// }
+    /// let steps = tree.insert_traced(4);
+    /// let cases: Vec<&str> = steps.iter().map(|step| step.case.as_str()).collect();
+    /// assert_eq!(cases, vec!["insert as red leaf", "3.2.2 child is right node", "3.2.1 child is left node"]);
+    /// assert_eq!(steps[1].nodes, vec![4, 3, 5]);
+    /// ```
+    fn insert_traced(&mut self, value: T) -> Vec<TraceStep<T>> {
+        self._traced(|tree| tree.insert(value))
+    }
+
+    /// Remove the element with the target value, recording every case applied.
+    fn delete_traced(&mut self, value: T) -> Vec<TraceStep<T>> {
+        self._traced(|tree| tree.delete(value))
+    }
+
+    fn render(&self) -> String {
+        let mut output = String::new();
+        match &self.root {
+            None => output.push_str("This tree is empty!\n"),
+            Some(root) => root.borrow().render_node(
                 &"".to_string(),
                 "Root".to_string(),
                 false,
+                &mut output,
             ),
         }
+        output
+    }
+
+    /// The height of the tree, kept by the root.
+    fn get_height(&self) -> u32 {
+        self.root.as_ref().map_or(0, |root| root.borrow().height)
+    }
+
+    /// The number of leaves of the tree, kept by the root.
+    fn count_leaves(&self) -> u32 {
+        self.root.as_ref().map_or(0, |root| root.borrow().leaves)
+    }
+
+    /// The number of elements, kept by the tree.
+    fn count_nodes(&self) -> u32 {
+        self.len
     }
 }
\ No newline at end of file
@@ -1,7 +1,11 @@
 #[cfg(test)]
 mod test {
+    extern crate std;
+
     use crate::base::Tree;
     use crate::rbtree;
+    use std::vec;
+    use std::vec::Vec;
 
     #[test]
     fn test_rbtree() {
@@ -41,4 +45,74 @@ mod test {
         }
         assert_eq!(rb_tree.is_empty(), true);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_drop_frees_nodes() {
+        // Every child holds its parent, so the nodes must be unlinked to be freed.
+        let mut rb_tree = rbtree::RBTree::new();
+        for number in 0..1000 {
+            rb_tree.insert(number);
+        }
+        let root = std::rc::Rc::downgrade(&rb_tree.get_root().unwrap());
+        drop(rb_tree);
+        assert!(root.upgrade().is_none());
+
+        let mut rb_tree = rbtree::RBTree::new();
+        for number in 0..1000 {
+            rb_tree.insert(number);
+        }
+        let root = std::rc::Rc::downgrade(&rb_tree.get_root().unwrap());
+        rb_tree.clear();
+        assert!(root.upgrade().is_none());
+        rb_tree.insert(1);
+        assert_eq!(rb_tree.count_nodes(), 1);
+    }
+
+    #[test]
+    fn test_from_sorted_iter() {
+        for size in 0..70 {
+            let tree: rbtree::RBTree<i32> = rbtree::RBTree::from_sorted_iter(0..size);
+            // Parsing the JSON checks the order, the colors and the black heights.
+            assert!(rbtree::RBTree::<i32>::from_json(&tree.to_json()).is_ok(), "{}", size);
+            assert_eq!(tree.range(0, size), (0..size).collect::<Vec<i32>>());
+            assert_eq!(tree.get_height(), 32 - (size as u32).leading_zeros());
+        }
+        // The parent links let the tree go on balancing itself.
+        let mut tree: rbtree::RBTree<i32> = rbtree::RBTree::from_sorted_iter((0..100).map(|number| number * 2));
+        for number in 0..100 {
+            tree.insert(number * 2 + 1);
+            tree.delete(number * 4);
+        }
+        assert!(rbtree::RBTree::<i32>::from_json(&tree.to_json()).is_ok());
+        assert_eq!(tree.count_nodes(), 150);
+
+        assert!(rbtree::RBTree::<i32>::try_from_sorted_iter(vec![3, 2]).is_err());
+        assert!(rbtree::RBTree::<i32>::try_from_sorted_iter(Vec::new()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_cached_metadata() {
+        use crate::base::NodeRef;
+        use crate::workload::{Distribution, Mix, Operation, Workload};
+
+        let mix = Mix { insert: 50, delete: 40, lookup: 10 };
+        for distribution in Distribution::ALL {
+            let workload = Workload::generate(distribution, 400, mix, 3);
+            let mut tree: rbtree::RBTree<i32> = rbtree::RBTree::new();
+            for operation in &workload.operations {
+                match *operation {
+                    Operation::Insert(key) => tree.insert(key),
+                    Operation::Delete(key) => tree.delete(key),
+                    Operation::Lookup(_) => continue,
+                }
+                // The cached answers against walks of the whole tree.
+                let root = tree.get_root();
+                assert_eq!(tree.get_height(), root.as_ref().map_or(0, |root| root.get_height()), "{:?}", distribution);
+                assert_eq!(tree.count_leaves(), root.as_ref().map_or(0, |root| root.count_leaves()));
+                assert_eq!(tree.count_nodes(), root.as_ref().map_or(0, |root| root.count_nodes()));
+            }
+        }
+        let tree: rbtree::RBTree<i32> = rbtree::RBTree::from_json(&rbtree::RBTree::<i32>::from_sorted_iter(0..100).to_json()).unwrap();
+        assert_eq!((tree.count_nodes(), tree.get_height(), tree.count_leaves()), (100, 7, 37));
+    }
+}
@@ -0,0 +1,158 @@
+use trees::arena_avltree::ArenaAVLTree;
+use trees::arena_rbtree::ArenaRBTree;
+use trees::avltree::AVLTree;
+use trees::base::Tree;
+use trees::bstree::BSTree;
+use trees::observer::{NoopObserver, TreeObserver};
+use trees::persistent_avltree::PersistentAVLTree;
+use trees::persistent_rbtree::PersistentRBTree;
+use trees::rbtree::RBTree;
+#[cfg(feature = "stats")]
+use trees::stats::TreeStats;
+
+use crate::cli::CliKey;
+
+// The observer of a tree taking one, None for the others.
+macro_rules! observer_of {
+    ($tree:ident) => {
+        None
+    };
+    ($tree:ident, $observer:ident) => {
+        Some($tree.observer())
+    };
+}
+
+// The counters of a tree keeping them, None for the others.
+#[cfg(feature = "stats")]
+macro_rules! stats_of {
+    ($tree:ident) => {
+        None
+    };
+    ($tree:ident, stats) => {
+        Some($tree.stats())
+    };
+}
+
+// Writes TreeKind, AnyTree and `with_tree!` from the list of kinds; the
+// leading `$` lets the generated `with_tree!` have variables of its own.
+macro_rules! tree_kinds {
+    ($d:tt $($variant:ident => $tree:ident<K $(, $observer:ident)?>, $name:literal, $title:literal $(, $stats:ident)?;)*) => {
+        /// The kinds of tree the binary can build.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub enum TreeKind {
+            $($variant,)*
+        }
+
+        impl TreeKind {
+            /// Every kind, in menu order.
+            pub const ALL: &'static [TreeKind] = &[$(TreeKind::$variant,)*];
+
+            /// The name of the kind on the command line.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(TreeKind::$variant => $name,)*
+                }
+            }
+
+            /// The name of the kind in the interactive menu.
+            pub fn title(&self) -> &'static str {
+                match self {
+                    $(TreeKind::$variant => $title,)*
+                }
+            }
+        }
+
+        /// A tree of any kind, O being the observer of the kinds taking one.
+        pub enum AnyTree<K: CliKey, O: TreeObserver<K> + Default = NoopObserver> {
+            $($variant($tree<K $(, $observer)?>),)*
+        }
+
+        impl<K: CliKey, O: TreeObserver<K> + Default> AnyTree<K, O> {
+            /// An empty tree of the kind.
+            pub fn new(kind: TreeKind) -> Self {
+                match kind {
+                    $(TreeKind::$variant => AnyTree::$variant(Default::default()),)*
+                }
+            }
+
+            pub fn kind(&self) -> TreeKind {
+                match self {
+                    $(AnyTree::$variant(_) => TreeKind::$variant,)*
+                }
+            }
+
+            /// The observer of the tree, None for the kinds without one.
+            pub fn observer(&self) -> Option<&O> {
+                match self {
+                    $(AnyTree::$variant(_tree) => observer_of!(_tree $(, $observer)?),)*
+                }
+            }
+
+            /// The counters of the tree, None for the kinds without them.
+            #[cfg(feature = "stats")]
+            pub fn stats(&self) -> Option<TreeStats> {
+                match self {
+                    $(AnyTree::$variant(_tree) => stats_of!(_tree $(, $stats)?),)*
+                }
+            }
+        }
+
+        // Runs the body with the tree of any kind bound to name, the body
+        // being compiled once per kind.
+        macro_rules! with_tree {
+            ($d tree:expr, $d name:ident => $d body:expr) => {
+                match $d tree {
+                    $($crate::registry::AnyTree::$variant($d name) => $d body,)*
+                }
+            };
+        }
+    };
+}
+
+// Every kind of tree of the binary, one line each: the menu, the sessions,
+// the scripts and the build, stats and bench commands all offer these.
+tree_kinds! {
+    $
+    Rb => RBTree<K, O>, "rb", "Red-Black Tree", stats;
+    Avl => AVLTree<K, O>, "avl", "AVL Tree", stats;
+    Bst => BSTree<K>, "bst", "Binary Search Tree";
+    ArenaRb => ArenaRBTree<K, O>, "arena-rb", "Arena Red-Black Tree";
+    ArenaAvl => ArenaAVLTree<K, O>, "arena-avl", "Arena AVL Tree";
+    PersistentRb => PersistentRBTree<K>, "persistent-rb", "Persistent Red-Black Tree";
+    PersistentAvl => PersistentAVLTree<K>, "persistent-avl", "Persistent AVL Tree";
+}
+
+// Clippy misses that the macro comes from an expansion, and so needs the
+// import to be used by path.
+#[allow(clippy::single_component_path_imports)]
+pub(crate) use with_tree;
+
+impl TreeKind {
+    pub fn parse(name: &str) -> Result<TreeKind, String> {
+        TreeKind::ALL
+            .iter()
+            .find(|kind| kind.name() == name)
+            .copied()
+            .ok_or_else(|| format!("unknown tree kind '{}', expected {}", name, TreeKind::names()))
+    }
+
+    /// The names of every kind, as "rb, avl or bst".
+    pub fn names() -> String {
+        let names: Vec<&str> = TreeKind::ALL.iter().map(TreeKind::name).collect();
+        match names.split_last() {
+            Some((last, [])) => last.to_string(),
+            Some((last, rest)) => format!("{} or {}", rest.join(", "), last),
+            None => String::new(),
+        }
+    }
+}
+
+impl<K: CliKey, O: TreeObserver<K> + Default> AnyTree<K, O> {
+    /// Every key of the tree, in increasing order.
+    pub fn keys(&self) -> Vec<K> {
+        with_tree!(self, tree => match (tree.get_min(), tree.get_max()) {
+            (Some(low), Some(high)) => tree.range(low, high),
+            _ => Vec::new(),
+        })
+    }
+}
@@ -0,0 +1,198 @@
+use trees::base::Tree;
+
+use std::fs::File;
+use std::io::{stdin, stdout, BufRead, BufReader, Write};
+
+use crate::registry::{with_tree, AnyTree, TreeKind};
+
+/// The script ran to the end.
+pub const EXIT_OK: i32 = 0;
+/// A line of the script is not a valid command, nothing after it was run.
+pub const EXIT_SCRIPT_ERROR: i32 = 1;
+/// The script could not be read, or the output could not be written.
+pub const EXIT_IO_ERROR: i32 = 2;
+
+/// The commands of the scripts, with the kinds of tree of the registry.
+pub fn script_help() -> String {
+    format!(
+        "\
+Script commands, one per line ('#' starts a comment):
+  tree KIND            start again with an empty tree of that kind (default rb):
+                       {}
+  insert KEY...        insert the keys, existing keys are ignored
+  delete KEY...        delete the keys, missing keys are ignored
+  clear                remove every key
+  contains KEY         prints true or false
+  range LOW HIGH       prints the keys between LOW and HIGH (both included)
+  rank KEY             prints the number of keys smaller than KEY
+  min | max            prints the smallest or largest key, or none
+  height | count | leaves
+                       prints the height, the number of keys or of leaves
+  print                prints the tree as one line of JSON
+  render               prints the tree as text, like the interactive mode",
+        TreeKind::names()
+    )
+}
+
+/// Why a script stopped before its end.
+#[derive(Debug, PartialEq)]
+pub enum ScriptError {
+    /// The line (starting from 1) is not a valid command.
+    Invalid { line: usize, message: String },
+    /// The script could not be read or the output could not be written.
+    Io(String),
+}
+
+fn parse_keys(arguments: &[&str]) -> Result<Vec<i32>, String> {
+    arguments
+        .iter()
+        .map(|argument| argument.parse::<i32>().map_err(|_| format!("'{}' is not an integer", argument)))
+        .collect()
+}
+
+fn expect_keys(command: &str, keys: &[i32], count: usize) -> Result<(), String> {
+    if keys.len() == count {
+        Ok(())
+    } else {
+        Err(format!("'{}' takes {} argument(s), got {}", command, count, keys.len()))
+    }
+}
+
+// The line number is filled in by `run`, which knows it.
+fn invalid(message: String) -> ScriptError {
+    ScriptError::Invalid { line: 0, message }
+}
+
+fn join(keys: &[i32]) -> String {
+    keys.iter().map(|key| key.to_string()).collect::<Vec<String>>().join(" ")
+}
+
+fn optional(key: Option<i32>) -> String {
+    key.map_or("none".to_string(), |key| key.to_string())
+}
+
+fn execute<TR, W>(tree: &mut TR, command: &str, keys: &[i32], output: &mut W) -> Result<(), ScriptError>
+where
+    TR: Tree<i32>,
+    W: Write,
+{
+    let line: String = match command {
+        "insert" | "delete" => {
+            if keys.is_empty() {
+                return Err(invalid(format!("'{}' needs at least one key", command)));
+            }
+            for &key in keys {
+                // The trees print a message for these cases, keep the output clean instead.
+                match (command, tree.contain(key)) {
+                    ("insert", false) => tree.insert(key),
+                    ("delete", true) => tree.delete(key),
+                    _ => {}
+                }
+            }
+            return Ok(());
+        }
+        "contains" => {
+            expect_keys(command, keys, 1).map_err(invalid)?;
+            tree.contain(keys[0]).to_string()
+        }
+        "rank" => {
+            expect_keys(command, keys, 1).map_err(invalid)?;
+            tree.rank(keys[0]).to_string()
+        }
+        "range" => {
+            expect_keys(command, keys, 2).map_err(invalid)?;
+            join(&tree.range(keys[0], keys[1]))
+        }
+        "min" | "max" | "height" | "count" | "leaves" | "print" | "render" => {
+            expect_keys(command, keys, 0).map_err(invalid)?;
+            match command {
+                "min" => optional(tree.get_min()),
+                "max" => optional(tree.get_max()),
+                "height" => tree.get_height().to_string(),
+                "count" => tree.count_nodes().to_string(),
+                "leaves" => tree.count_leaves().to_string(),
+                "print" => tree.to_json(),
+                _ => tree.render().trim_end().to_string(),
+            }
+        }
+        _ => return Err(invalid(format!("unknown command '{}'", command))),
+    };
+    writeln!(output, "{}", line).map_err(|error| ScriptError::Io(error.to_string()))
+}
+
+fn run_line<W: Write>(tree: &mut AnyTree<i32>, line: &str, output: &mut W) -> Result<(), ScriptError> {
+    let line: &str = line.split('#').next().unwrap_or("").trim();
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let (command, arguments) = match words.split_first() {
+        None => return Ok(()),
+        Some((command, arguments)) => (command.to_lowercase(), arguments),
+    };
+    match command.as_str() {
+        "tree" => {
+            if arguments.len() != 1 {
+                return Err(invalid(format!("'tree' takes one argument: {}", TreeKind::names())));
+            }
+            *tree = AnyTree::new(TreeKind::parse(arguments[0]).map_err(invalid)?);
+            Ok(())
+        }
+        "clear" => {
+            if !arguments.is_empty() {
+                return Err(invalid("'clear' takes no argument".to_string()));
+            }
+            *tree = AnyTree::new(tree.kind());
+            Ok(())
+        }
+        _ => {
+            let keys: Vec<i32> = parse_keys(arguments).map_err(invalid)?;
+            with_tree!(tree, tree => execute(tree, &command, &keys, output))
+        }
+    }
+}
+
+/// Runs every line of the script, writing one line of output per query.
+///
+/// Stops at the first invalid line.
+pub fn run<R: BufRead, W: Write>(input: R, output: &mut W) -> Result<(), ScriptError> {
+    let mut tree: AnyTree<i32> = AnyTree::new(TreeKind::Rb);
+    for (index, line) in input.lines().enumerate() {
+        let line: String = line.map_err(|error| ScriptError::Io(error.to_string()))?;
+        run_line(&mut tree, &line, output).map_err(|error| match error {
+            ScriptError::Invalid { message, .. } => ScriptError::Invalid { line: index + 1, message },
+            error => error,
+        })?;
+    }
+    Ok(())
+}
+
+/// Runs the script in the file at path, or read from stdin when path is None or "-".
+///
+/// Errors are written to stderr, the returned value is the exit code of the process.
+pub fn run_script(path: Option<&str>) -> i32 {
+    let output = stdout();
+    let mut output = output.lock();
+    let result = match path {
+        Some("--help") | Some("-h") => {
+            println!("Usage: trees run [FILE]\nReads the script from stdin when FILE is missing or '-'.\n\n{}", script_help());
+            return EXIT_OK;
+        }
+        None | Some("-") => run(stdin().lock(), &mut output),
+        Some(path) => match File::open(path) {
+            Ok(file) => run(BufReader::new(file), &mut output),
+            Err(error) => {
+                eprintln!("error: cannot open '{}': {}", path, error);
+                return EXIT_IO_ERROR;
+            }
+        },
+    };
+    match result {
+        Ok(()) => EXIT_OK,
+        Err(ScriptError::Invalid { line, message }) => {
+            eprintln!("error: line {}: {}", line, message);
+            EXIT_SCRIPT_ERROR
+        }
+        Err(ScriptError::Io(message)) => {
+            eprintln!("error: {}", message);
+            EXIT_IO_ERROR
+        }
+    }
+}
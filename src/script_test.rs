@@ -0,0 +1,58 @@
+#[cfg(test)]
+mod test {
+    use crate::registry::TreeKind;
+    use crate::script::{self, ScriptError};
+
+    fn run(script: &str) -> (Result<(), ScriptError>, String) {
+        let mut output: Vec<u8> = Vec::new();
+        let result = script::run(script.as_bytes(), &mut output);
+        (result, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn test_run_queries() {
+        let (result, output) = run("\
+# a red-black tree by default
+insert 5 3 8 1 4
+insert 5
+delete 3 42
+contains 3
+contains 4
+range 2 6
+range 10 20
+rank 5
+min
+max
+count
+height
+leaves
+");
+        assert_eq!(result, Ok(()));
+        assert_eq!(output, "false\ntrue\n4 5\n\n2\n1\n8\n4\n3\n2\n");
+    }
+
+    #[test]
+    fn test_tree_kinds() {
+        for kind in TreeKind::ALL {
+            let (result, output) = run(&format!("tree {}\ninsert 1 2 3\nrange 1 3\nclear\nmin\ncount\nprint\n", kind.name()));
+            assert_eq!(result, Ok(()));
+            assert_eq!(output, "1 2 3\nnone\n0\nnull\n");
+        }
+        let (_, output) = run("tree bst\ninsert 1 2 3\nheight\ntree avl\ninsert 1 2 3\nheight\nprint\n");
+        assert_eq!(output.lines().take(2).collect::<Vec<&str>>(), ["3", "2"]);
+        assert!(output.lines().nth(2).unwrap().starts_with("{\"key\":2,\"height\":2"));
+        let (_, output) = run("insert 2 1\nrender\n");
+        assert_eq!(output, "Root 2 Black\n|____ L 1 Red\n");
+    }
+
+    #[test]
+    fn test_invalid_lines() {
+        let (result, output) = run("insert 1\ncount\nfrobnicate\ncount\n");
+        assert_eq!(result, Err(ScriptError::Invalid { line: 3, message: "unknown command 'frobnicate'".to_string() }));
+        // Nothing after the invalid line runs.
+        assert_eq!(output, "1\n");
+        for script in ["insert x", "insert", "range 1", "min 3", "tree splay", "clear now", "contains 1 2"] {
+            assert!(matches!(run(script).0, Err(ScriptError::Invalid { line: 1, .. })), "{}", script);
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use trees::base::Tree;
+
+use crate::cli::CliKey;
+use crate::history::{snapshot, History};
+use crate::registry::{with_tree, AnyTree, TreeKind};
+
+/// A tree of the session with its own undo history.
+pub struct NamedTree<K: CliKey> {
+    pub name: String,
+    pub tree: AnyTree<K>,
+    pub history: History,
+}
+
+/// The named trees of a CLI session, one of them being the current tree
+/// that the operations of the menu apply to.
+pub struct Session<K: CliKey> {
+    trees: Vec<NamedTree<K>>,
+    current: usize,
+}
+
+impl<K: CliKey> Session<K> {
+    /// A session holding one empty tree of the kind, which is the current tree.
+    pub fn new(kind: &str, name: &str) -> Result<Self, String> {
+        let mut session = Session { trees: Vec::new(), current: 0 };
+        session.create(kind, name)?;
+        Ok(session)
+    }
+
+    fn position(&self, name: &str) -> Result<usize, String> {
+        self.trees
+            .iter()
+            .position(|named| named.name == name)
+            .ok_or_else(|| format!("there is no tree named '{}'", name))
+    }
+
+    fn get(&self, name: &str) -> Result<&NamedTree<K>, String> {
+        self.position(name).map(|position| &self.trees[position])
+    }
+
+    pub fn current(&self) -> &NamedTree<K> {
+        &self.trees[self.current]
+    }
+
+    pub fn current_mut(&mut self) -> &mut NamedTree<K> {
+        &mut self.trees[self.current]
+    }
+
+    /// Adds an empty tree of the kind and makes it the current tree.
+    pub fn create(&mut self, kind: &str, name: &str) -> Result<(), String> {
+        self.add(TreeKind::parse(kind)?, name)
+    }
+
+    fn add(&mut self, kind: TreeKind, name: &str) -> Result<(), String> {
+        if self.position(name).is_ok() {
+            return Err(format!("there is already a tree named '{}'", name));
+        }
+        let tree = AnyTree::new(kind);
+        self.trees.push(NamedTree { name: name.to_string(), tree, history: History::default() });
+        self.current = self.trees.len() - 1;
+        Ok(())
+    }
+
+    /// Makes the tree with that name the current tree.
+    pub fn switch(&mut self, name: &str) -> Result<(), String> {
+        self.current = self.position(name)?;
+        Ok(())
+    }
+
+    /// One line per tree, in creation order, the current tree being marked with '*'.
+    pub fn list(&self) -> Vec<String> {
+        self.trees
+            .iter()
+            .enumerate()
+            .map(|(position, named)| {
+                let marker = if position == self.current { '*' } else { ' ' };
+                let count = with_tree!(&named.tree, tree => tree.count_nodes());
+                format!("{} {} ({}, {} keys)", marker, named.name, named.tree.kind().name(), count)
+            })
+            .collect()
+    }
+
+    /// Puts the keys of both first and second into the tree named into.
+    ///
+    /// An existing tree keeps its kind and is replaced, the change going to
+    /// its history; otherwise a tree of the kind of first is created. The
+    /// current tree does not change.
+    pub fn union(&mut self, first: &str, second: &str, into: &str) -> Result<(), String> {
+        let mut keys: Vec<K> = self.get(first)?.tree.keys();
+        keys.extend(self.get(second)?.tree.keys());
+        let kind = match self.get(into) {
+            Ok(named) => named.tree.kind(),
+            Err(_) => self.get(first)?.tree.kind(),
+        };
+        let mut union = AnyTree::new(kind);
+        with_tree!(&mut union, tree => {
+            for key in keys {
+                if !tree.contain(key.clone()) {
+                    tree.insert(key);
+                }
+            }
+        });
+        let label = format!("union {} {}", first, second);
+        match self.position(into) {
+            Ok(position) => {
+                let named = &mut self.trees[position];
+                let before = with_tree!(&named.tree, tree => snapshot(tree));
+                named.tree = union;
+                named.history.record(label, before, with_tree!(&named.tree, tree => snapshot(tree)));
+            }
+            Err(_) => {
+                let current = self.current;
+                self.add(kind, into)?;
+                self.trees[self.current].tree = union;
+                self.current = current;
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the two trees hold the same keys, whatever their kind and shape.
+    pub fn equal(&self, first: &str, second: &str) -> Result<bool, String> {
+        Ok(self.get(first)?.tree.keys() == self.get(second)?.tree.keys())
+    }
+}
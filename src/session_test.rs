@@ -0,0 +1,63 @@
+#[cfg(test)]
+mod test {
+    use trees::base::Tree;
+
+    use crate::registry::{with_tree, TreeKind};
+    use crate::session::Session;
+
+    fn insert(session: &mut Session<i32>, keys: &[i32]) {
+        with_tree!(&mut session.current_mut().tree, tree => keys.iter().for_each(|&key| tree.insert(key)));
+    }
+
+    #[test]
+    fn test_named_trees() {
+        let mut session: Session<i32> = Session::new("rb", "tree").unwrap();
+        insert(&mut session, &[1, 2, 3]);
+        session.create("avl", "scores").unwrap();
+        insert(&mut session, &[3, 4]);
+        assert_eq!(session.current().name, "scores");
+        assert!(session.create("bst", "tree").is_err());
+        assert!(session.create("splay", "other").is_err());
+        assert_eq!(session.list(), ["  tree (rb, 3 keys)", "* scores (avl, 2 keys)"]);
+
+        session.switch("tree").unwrap();
+        assert_eq!(session.current().tree.keys(), [1, 2, 3]);
+        assert_eq!(session.switch("missing"), Err("there is no tree named 'missing'".to_string()));
+        assert_eq!(session.current().name, "tree");
+    }
+
+    #[test]
+    fn test_union_and_equal() {
+        let mut session: Session<i32> = Session::new("bst", "a").unwrap();
+        insert(&mut session, &[5, 1, 9]);
+        session.create("rb", "b").unwrap();
+        insert(&mut session, &[9, 2]);
+
+        session.union("a", "b", "c").unwrap();
+        assert_eq!(session.current().name, "b");
+        assert_eq!(session.list()[2], "  c (bst, 4 keys)");
+        session.switch("c").unwrap();
+        assert_eq!(session.current().tree.keys(), [1, 2, 5, 9]);
+
+        // An existing tree keeps its kind and can undo the union.
+        session.create("avl", "d").unwrap();
+        session.union("b", "a", "d").unwrap();
+        assert!(session.equal("c", "d").unwrap());
+        assert!(!session.equal("a", "d").unwrap());
+        assert!(session.equal("a", "missing").is_err());
+        assert_eq!(session.current().tree.kind(), TreeKind::Avl);
+        assert_eq!(session.current_mut().history.lines(), ["1. union b a"]);
+    }
+
+    #[test]
+    fn test_every_kind() {
+        let mut session: Session<i32> = Session::new("rb", "rb").unwrap();
+        for kind in TreeKind::ALL {
+            session.create(kind.name(), &format!("{}-tree", kind.name())).unwrap();
+            insert(&mut session, &[3, 1, 2]);
+            assert_eq!(session.current().tree.kind(), *kind);
+            assert_eq!(session.current().tree.keys(), [1, 2, 3]);
+        }
+        assert!(session.equal("persistent-rb-tree", "arena-avl-tree").unwrap());
+    }
+}
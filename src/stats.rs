@@ -0,0 +1,193 @@
+#[cfg(feature = "stats")]
+use core::sync::atomic::Ordering;
+#[cfg(feature = "stats")]
+use count::{narrow, widen, AtomicCount};
+
+// Targets without 64-bit atomics, such as thumbv7em, keep the counters in
+// 32 bits, a count past u32::MAX staying at u32::MAX.
+#[cfg(all(feature = "stats", target_has_atomic = "64"))]
+mod count {
+    pub use core::sync::atomic::AtomicU64 as AtomicCount;
+
+    pub fn narrow(count: u64) -> u64 {
+        count
+    }
+
+    pub fn widen(count: u64) -> u64 {
+        count
+    }
+}
+
+#[cfg(all(feature = "stats", not(target_has_atomic = "64")))]
+mod count {
+    pub use core::sync::atomic::AtomicU32 as AtomicCount;
+
+    pub fn narrow(count: u64) -> u32 {
+        count.min(u32::MAX.into()) as u32
+    }
+
+    pub fn widen(count: u32) -> u64 {
+        count.into()
+    }
+}
+
+/// The counters of a tree, as returned by `stats()`.
+///
+/// `stats()` only exists when the crate is built with the `stats` feature,
+/// without it the trees keep no counters at all.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TreeStats {
+    /// Key comparisons made while searching, inserting and deleting.
+    pub comparisons: u64,
+    /// Rebalancing cases solved with one rotation.
+    pub single_rotations: u64,
+    /// Rebalancing cases solved with two rotations (left-right or right-left).
+    pub double_rotations: u64,
+    /// Red-black nodes that changed color.
+    pub recolorings: u64,
+    /// Nodes created.
+    pub allocations: u64,
+    /// Nodes removed from the tree.
+    pub frees: u64,
+    /// The deepest recursion (or search path, for the iterative code) of one operation.
+    pub max_depth: u64,
+}
+
+/// The counters kept inside a tree.
+///
+/// The counters are atomics, so that queries taking `&self` can count their
+/// comparisons too while the tree stays `Sync`. They are 64 bits wide where
+/// the target has 64-bit atomics and 32 bits wide elsewhere. Without the `stats` feature
+/// this is an empty struct whose methods do nothing and are inlined away.
+#[cfg(feature = "stats")]
+#[derive(Debug, Default)]
+pub struct StatsCounter {
+    comparisons: AtomicCount,
+    single_rotations: AtomicCount,
+    double_rotations: AtomicCount,
+    recolorings: AtomicCount,
+    allocations: AtomicCount,
+    frees: AtomicCount,
+    max_depth: AtomicCount,
+    depth: AtomicCount,
+}
+
+#[cfg(not(feature = "stats"))]
+#[derive(Debug, Default)]
+pub struct StatsCounter;
+
+/// Leaves one level of recursion when dropped, see `StatsCounter::descend`.
+pub struct DepthGuard<'a> {
+    #[cfg(feature = "stats")]
+    counter: &'a StatsCounter,
+    #[cfg(not(feature = "stats"))]
+    counter: core::marker::PhantomData<&'a StatsCounter>,
+}
+
+#[cfg(feature = "stats")]
+impl Drop for DepthGuard<'_> {
+    fn drop(&mut self) {
+        self.counter.depth.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(feature = "stats")]
+impl StatsCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // The counters are independent of each other, so relaxed ordering is enough.
+    fn add(counter: &AtomicCount, count: u64) {
+        counter.fetch_add(narrow(count), Ordering::Relaxed);
+    }
+
+    pub fn comparison(&self) {
+        Self::add(&self.comparisons, 1);
+    }
+
+    pub fn single_rotation(&self) {
+        Self::add(&self.single_rotations, 1);
+    }
+
+    pub fn double_rotation(&self) {
+        Self::add(&self.double_rotations, 1);
+    }
+
+    pub fn recoloring(&self) {
+        Self::add(&self.recolorings, 1);
+    }
+
+    pub fn allocation(&self) {
+        Self::add(&self.allocations, 1);
+    }
+
+    pub fn frees(&self, count: u64) {
+        Self::add(&self.frees, count);
+    }
+
+    /// Records that an iterative operation went depth levels down the tree.
+    pub fn reached_depth(&self, depth: u64) {
+        self.max_depth.fetch_max(narrow(depth), Ordering::Relaxed);
+    }
+
+    /// Enters one more level of recursion until the returned guard is dropped.
+    pub fn descend(&self) -> DepthGuard<'_> {
+        let depth: u64 = widen(self.depth.fetch_add(1, Ordering::Relaxed)) + 1;
+        self.reached_depth(depth);
+        DepthGuard { counter: self }
+    }
+
+    pub fn get(&self) -> TreeStats {
+        let load = |counter: &AtomicCount| widen(counter.load(Ordering::Relaxed));
+        TreeStats {
+            comparisons: load(&self.comparisons),
+            single_rotations: load(&self.single_rotations),
+            double_rotations: load(&self.double_rotations),
+            recolorings: load(&self.recolorings),
+            allocations: load(&self.allocations),
+            frees: load(&self.frees),
+            max_depth: load(&self.max_depth),
+        }
+    }
+
+    pub fn reset(&self) {
+        for counter in [&self.comparisons, &self.single_rotations, &self.double_rotations, &self.recolorings, &self.allocations, &self.frees, &self.max_depth] {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(not(feature = "stats"))]
+impl StatsCounter {
+    #[inline(always)]
+    pub fn new() -> Self {
+        StatsCounter
+    }
+
+    #[inline(always)]
+    pub fn comparison(&self) {}
+
+    #[inline(always)]
+    pub fn single_rotation(&self) {}
+
+    #[inline(always)]
+    pub fn double_rotation(&self) {}
+
+    #[inline(always)]
+    pub fn recoloring(&self) {}
+
+    #[inline(always)]
+    pub fn allocation(&self) {}
+
+    #[inline(always)]
+    pub fn frees(&self, _count: u64) {}
+
+    #[inline(always)]
+    pub fn reached_depth(&self, _depth: u64) {}
+
+    #[inline(always)]
+    pub fn descend(&self) -> DepthGuard<'_> {
+        DepthGuard { counter: core::marker::PhantomData }
+    }
+}
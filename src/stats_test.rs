@@ -0,0 +1,71 @@
+#[cfg(all(test, feature = "stats"))]
+mod test {
+    use crate::base::Tree;
+    use crate::stats::TreeStats;
+    use crate::{avltree, rbtree};
+
+    #[test]
+    fn test_rbtree_stats() {
+        let mut rb_tree: rbtree::RBTree<i32> = rbtree::RBTree::new();
+        assert_eq!(rb_tree.stats(), TreeStats::default());
+        for number in [10, 5, 15] {
+            rb_tree.insert(number);
+        }
+        // 10 is the root, 5 and 15 take one comparison to find their place and one to pick the side.
+        assert_eq!(rb_tree.stats(), TreeStats { comparisons: 4, allocations: 3, recolorings: 0, max_depth: 1, ..TreeStats::default() });
+
+        // Left-right case: one double rotation.
+        rb_tree.reset_stats();
+        for number in [1, 3, 2] {
+            rb_tree.insert(number);
+        }
+        let stats: TreeStats = rb_tree.stats();
+        assert_eq!(stats.allocations, 3);
+        assert_eq!(stats.single_rotations, 0);
+        assert_eq!(stats.double_rotations, 1);
+        assert!(stats.recolorings > 0);
+        assert_eq!(stats.max_depth, 3);
+
+        rb_tree.reset_stats();
+        assert!(rb_tree.contain(15));
+        // One comparison for every level the lookup went through.
+        assert_eq!(rb_tree.stats().comparisons, 2);
+        assert_eq!(rb_tree.stats().max_depth, 2);
+        rb_tree.delete(15);
+        rb_tree.delete(42);
+        assert_eq!(rb_tree.stats().frees, 1);
+        rb_tree.clear();
+        assert_eq!(rb_tree.stats().frees, 6);
+    }
+
+    #[test]
+    fn test_avltree_stats() {
+        let mut avl_tree: avltree::AVLTree<i32> = avltree::AVLTree::new();
+        for number in 0..7 {
+            avl_tree.insert(number);
+        }
+        let stats: TreeStats = avl_tree.stats();
+        assert_eq!(stats.allocations, 7);
+        assert_eq!(stats.single_rotations, 4);
+        assert_eq!(stats.double_rotations, 0);
+        assert_eq!(stats.recolorings, 0);
+        assert_eq!(stats.max_depth, 4);
+
+        avl_tree.reset_stats();
+        for number in [0, 2, 1] {
+            avl_tree.delete(number);
+        }
+        let stats: TreeStats = avl_tree.stats();
+        assert_eq!(stats.frees, 3);
+        assert_eq!(stats.allocations, 0);
+        assert!(stats.comparisons > 0);
+        assert_eq!(stats.single_rotations + stats.double_rotations, 1);
+
+        // Lookups count their comparisons too.
+        avl_tree.reset_stats();
+        assert!(!avl_tree.contain(100));
+        let stats: TreeStats = avl_tree.stats();
+        assert!(stats.comparisons > 0);
+        assert_eq!(stats.comparisons, stats.max_depth);
+    }
+}
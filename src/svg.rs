@@ -0,0 +1,272 @@
+use core::fmt::{Debug, Write};
+use core::cmp::{max, min};
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::{format, vec};
+
+use crate::base::NodeRef;
+
+// Horizontal distance (in pixels) of one layout unit. Two siblings are at
+// least two units apart, so neighbouring nodes never overlap.
+const UNIT_WIDTH: i64 = 28;
+const LEVEL_HEIGHT: i64 = 64;
+const NODE_RADIUS: i64 = 18;
+const MARGIN: i64 = 40;
+
+/// How a single node is drawn by `Tree::to_svg`.
+///
+/// Trees override `TreeNode::svg_style` to show their own metadata,
+/// e.g. the color of a red-black node or the height of an AVL node.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NodeStyle {
+    pub fill: &'static str,
+    pub stroke: &'static str,
+    pub text_color: &'static str,
+    pub annotation: Option<String>,
+}
+
+impl Default for NodeStyle {
+    fn default() -> Self {
+        NodeStyle {
+            fill: "#ffffff",
+            stroke: "#000000",
+            text_color: "#000000",
+            annotation: None,
+        }
+    }
+}
+
+/// A node of the tree after the layout step.
+/// x is measured in layout units, depth in levels starting from 0.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlacedNode {
+    pub label: String,
+    pub style: NodeStyle,
+    pub x: i64,
+    pub depth: i64,
+    pub parent: Option<usize>,
+}
+
+// The leftmost and rightmost x of every level of a subtree, relative to the
+// root of the subtree. The levels are kept deepest first, so that a parent
+// adds its own level at the end, and shift is added to every kept x, so that
+// moving a whole subtree takes constant time.
+struct Contour {
+    levels: Vec<(i64, i64)>,
+    shift: i64,
+}
+
+impl Contour {
+    fn leaf() -> Self {
+        Contour { levels: vec![(0, 0)], shift: 0 }
+    }
+
+    fn depth(&self) -> usize {
+        self.levels.len()
+    }
+
+    // The bounds of the level at depth below the root of the subtree.
+    fn level(&self, depth: usize) -> (i64, i64) {
+        let (left, right) = self.levels[self.levels.len() - 1 - depth];
+        (left + self.shift, right + self.shift)
+    }
+
+    fn set_level(&mut self, depth: usize, bounds: (i64, i64)) {
+        let index: usize = self.levels.len() - 1 - depth;
+        self.levels[index] = (bounds.0 - self.shift, bounds.1 - self.shift);
+    }
+
+    fn moved(mut self, offset: i64) -> Self {
+        self.shift += offset;
+        self
+    }
+
+    // Adds the level of a parent at x = 0 above the subtree.
+    fn add_root(&mut self) {
+        self.levels.push((-self.shift, -self.shift));
+    }
+}
+
+// Puts two subtrees side by side under a parent at x = 0, returning their
+// offset from the parent and the contour of the whole subtree.
+fn join(left: Contour, right: Contour) -> (i64, Contour) {
+    let overlap: usize = min(left.depth(), right.depth());
+    // Push the two subtrees apart until every level they share is separated
+    // by two units, the parent staying in the middle.
+    let mut gap: i64 = 0;
+    for depth in 0..overlap {
+        gap = max(gap, left.level(depth).1 - right.level(depth).0 + 2);
+    }
+    let offset: i64 = (gap + 1) / 2;
+    let (left, right) = (left.moved(-offset), right.moved(offset));
+    // The deeper contour is kept, its levels next to the other one spanning both.
+    let left_is_deeper: bool = left.depth() >= right.depth();
+    let (mut joined, other) = if left_is_deeper { (left, right) } else { (right, left) };
+    for depth in 0..overlap {
+        let (joined_level, other_level) = (joined.level(depth), other.level(depth));
+        let bounds = if left_is_deeper { (joined_level.0, other_level.1) } else { (other_level.0, joined_level.1) };
+        joined.set_level(depth, bounds);
+    }
+    joined.add_root();
+    (offset, joined)
+}
+
+// A node of the tree with the positions of its parent and children in the
+// preorder list of nodes.
+struct Item {
+    label: String,
+    style: NodeStyle,
+    parent: Option<usize>,
+    left: Option<usize>,
+    right: Option<usize>,
+}
+
+fn preorder<T, N>(root: N) -> Vec<Item>
+where
+    T: Ord + Clone + Debug,
+    N: NodeRef<T>,
+{
+    let mut items: Vec<Item> = Vec::new();
+    let mut pending: Vec<(N, Option<(usize, bool)>)> = vec![(root, None)];
+    while let Some((node, parent)) = pending.pop() {
+        let index: usize = items.len();
+        match parent {
+            Some((parent, true)) => items[parent].left = Some(index),
+            Some((parent, false)) => items[parent].right = Some(index),
+            None => {}
+        }
+        items.push(Item {
+            label: format!("{:?}", node.key()),
+            style: node.svg_style(),
+            parent: parent.map(|(parent, _)| parent),
+            left: None,
+            right: None,
+        });
+        pending.extend(node.right().map(|right| (right, Some((index, false)))));
+        pending.extend(node.left().map(|left| (left, Some((index, true)))));
+    }
+    items
+}
+
+/// Computes a tidy drawing of the tree in the spirit of Reingold and Tilford:
+/// subtrees are laid out independently, then pushed together as close as their
+/// contours allow, and every parent is centred above its children.
+///
+/// Nodes are returned in preorder, the leftmost node having x = 0.
+pub fn layout<T, N>(root: Option<N>) -> Vec<PlacedNode>
+where
+    T: Ord + Clone + Debug,
+    N: NodeRef<T>,
+{
+    let items: Vec<Item> = match root {
+        None => return Vec::new(),
+        Some(root) => preorder(root),
+    };
+    // The subtrees are laid out from the last node in preorder, whose children
+    // come after them, each node getting its offset from its parent.
+    let mut contours: Vec<Option<Contour>> = (0..items.len()).map(|_| None).collect();
+    let mut offsets: Vec<i64> = vec![0; items.len()];
+    for (index, item) in items.iter().enumerate().rev() {
+        let mut subtree = |child: usize| contours[child].take().expect("the children are laid out first");
+        let contour: Contour = match (item.left, item.right) {
+            (Some(left), Some(right)) => {
+                let (offset, contour) = join(subtree(left), subtree(right));
+                offsets[left] = -offset;
+                offsets[right] = offset;
+                contour
+            }
+            (Some(child), None) | (None, Some(child)) => {
+                // A lone child still leans to its own side, so left and right stay distinguishable.
+                let offset: i64 = if item.left.is_some() { -1 } else { 1 };
+                let mut contour: Contour = subtree(child).moved(offset);
+                contour.add_root();
+                offsets[child] = offset;
+                contour
+            }
+            (None, None) => Contour::leaf(),
+        };
+        contours[index] = Some(contour);
+    }
+
+    let mut placed: Vec<PlacedNode> = Vec::with_capacity(items.len());
+    for (index, item) in items.into_iter().enumerate() {
+        let (x, depth) = match item.parent {
+            None => (offsets[index], 0),
+            Some(parent) => (placed[parent].x + offsets[index], placed[parent].depth + 1),
+        };
+        placed.push(PlacedNode { label: item.label, style: item.style, x, depth, parent: item.parent });
+    }
+    let left_most: i64 = placed.iter().map(|node| node.x).min().unwrap_or(0);
+    for node in placed.iter_mut() {
+        node.x -= left_most;
+    }
+    placed
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+/// Renders the tree as a standalone SVG document.
+pub fn render<T, N>(root: Option<N>) -> String
+where
+    T: Ord + Clone + Debug,
+    N: NodeRef<T>,
+{
+    let placed: Vec<PlacedNode> = layout(root);
+    let (mut right_most, mut deepest) = (0, 0);
+    for node in placed.iter() {
+        right_most = max(right_most, node.x);
+        deepest = max(deepest, node.depth);
+    }
+    let width: i64 = right_most * UNIT_WIDTH + 2 * MARGIN;
+    let height: i64 = if placed.is_empty() { 2 * MARGIN } else { deepest * LEVEL_HEIGHT + 2 * MARGIN };
+    let center = |node: &PlacedNode| (node.x * UNIT_WIDTH + MARGIN, node.depth * LEVEL_HEIGHT + MARGIN);
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\" font-family=\"sans-serif\" font-size=\"14\">",
+        width, height, width, height
+    );
+    // Edges first, so the circles are painted on top of them.
+    for node in placed.iter() {
+        if let Some(parent) = node.parent {
+            let (x1, y1) = center(&placed[parent]);
+            let (x2, y2) = center(node);
+            let _ = writeln!(svg, "  <line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"#555555\" stroke-width=\"1.5\"/>", x1, y1, x2, y2);
+        }
+    }
+    for node in placed.iter() {
+        let (x, y) = center(node);
+        let _ = writeln!(
+            svg,
+            "  <circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\" stroke=\"{}\" stroke-width=\"1.5\"/>",
+            x, y, NODE_RADIUS, node.style.fill, node.style.stroke
+        );
+        let _ = writeln!(
+            svg,
+            "  <text x=\"{}\" y=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\" fill=\"{}\">{}</text>",
+            x, y, node.style.text_color, escape(&node.label)
+        );
+        if let Some(annotation) = &node.style.annotation {
+            let _ = writeln!(
+                svg,
+                "  <text x=\"{}\" y=\"{}\" text-anchor=\"middle\" font-size=\"10\" fill=\"#336699\">{}</text>",
+                x, y + NODE_RADIUS + 12, escape(annotation)
+            );
+        }
+    }
+    let _ = writeln!(svg, "</svg>");
+    svg
+}
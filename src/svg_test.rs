@@ -0,0 +1,94 @@
+#[cfg(test)]
+mod test {
+    extern crate std;
+
+    use crate::base::Tree;
+    use crate::svg::{self, PlacedNode};
+    use crate::{avltree, bstree, rbtree};
+    use std::format;
+    use std::string::String;
+    use std::vec::Vec;
+
+    fn has_overlap(placed: &[PlacedNode]) -> bool {
+        for (index, first) in placed.iter().enumerate() {
+            for second in placed[index + 1..].iter() {
+                if first.depth == second.depth && (first.x - second.x).abs() < 2 {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    #[test]
+    fn test_layout() {
+        let mut bs_tree: bstree::BSTree<i32> = bstree::BSTree::new();
+        assert!(svg::layout(bs_tree.get_root()).is_empty());
+        for number in [50, 20, 80, 10, 30, 25, 35, 33, 90, 85, 99, 5, 1] {
+            bs_tree.insert(number);
+        }
+        let placed = svg::layout(bs_tree.get_root());
+        assert_eq!(placed.len(), 13);
+        assert!(!has_overlap(&placed));
+        assert_eq!(placed.iter().map(|node| node.x).min(), Some(0));
+        // Every parent sits above its children, left child on the left.
+        for node in placed.iter() {
+            if let Some(parent) = node.parent {
+                let parent = &placed[parent];
+                assert_eq!(parent.depth + 1, node.depth);
+                let is_left: bool = node.label.parse::<i32>().unwrap() < parent.label.parse::<i32>().unwrap();
+                assert_eq!(is_left, node.x < parent.x);
+            }
+        }
+        // Every parent with two children is centred above them.
+        for (index, node) in placed.iter().enumerate() {
+            let children: Vec<i64> = placed.iter().filter(|child| child.parent == Some(index)).map(|child| child.x).collect();
+            if let [left, right] = children[..] {
+                assert_eq!(left + right, 2 * node.x);
+            }
+        }
+    }
+
+    #[test]
+    fn test_layout_uses_contours() {
+        // The right subtree of 20 goes under 50, which an inorder layout
+        // giving every key a column of its own would not allow.
+        let mut bs_tree: bstree::BSTree<i32> = bstree::BSTree::new();
+        for number in [50, 20, 10, 30, 25, 35] {
+            bs_tree.insert(number);
+        }
+        let placed = svg::layout(bs_tree.get_root());
+        assert!(!has_overlap(&placed));
+        let x_of = |label: &str| placed.iter().find(|node| node.label == label).map(|node| node.x);
+        assert_eq!(x_of("10"), Some(0));
+        assert_eq!(x_of("20"), Some(1));
+        assert_eq!(x_of("25"), Some(1));
+        assert_eq!(x_of("50"), Some(2));
+        assert_eq!(x_of("30"), Some(2));
+        assert_eq!(x_of("35"), Some(3));
+    }
+
+    #[test]
+    fn test_to_svg() {
+        let mut rb_tree: rbtree::RBTree<i32> = rbtree::RBTree::new();
+        let mut avl_tree: avltree::AVLTree<i32> = avltree::AVLTree::new();
+        assert_eq!(rb_tree.to_svg().matches("<circle").count(), 0);
+        for number in 0..20 {
+            rb_tree.insert(number);
+            avl_tree.insert(number);
+        }
+        let rb_svg: String = rb_tree.to_svg();
+        assert!(rb_svg.starts_with("<svg"));
+        assert!(rb_svg.trim_end().ends_with("</svg>"));
+        assert_eq!(rb_svg.matches("<circle").count(), 20);
+        assert_eq!(rb_svg.matches("<line").count(), 19);
+        assert!(rb_svg.contains("fill=\"#cc2222\""));
+        assert!(rb_svg.contains("fill=\"#222222\""));
+        assert!(!has_overlap(&svg::layout(rb_tree.get_root())));
+
+        let avl_svg: String = avl_tree.to_svg();
+        assert_eq!(avl_svg.matches("<circle").count(), 20);
+        assert!(avl_svg.contains(&format!("h={} bf=", avl_tree.get_height())));
+        assert_eq!(avl_svg.matches("h=1 bf=0").count() as u32, avl_tree.count_leaves());
+    }
+}
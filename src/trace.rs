@@ -0,0 +1,23 @@
+use core::fmt::{self, Debug, Display};
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// One step of a traced insert or delete.
+///
+/// case names the rule that was applied, using the numbering of the
+/// comments in the tree modules (e.g. "3.2.2 child is right node"),
+/// nodes lists the keys it involved and snapshot is the rendered tree
+/// right after the step.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraceStep<T> {
+    pub case: String,
+    pub nodes: Vec<T>,
+    pub snapshot: String,
+}
+
+impl<T: Debug> Display for TraceStep<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(formatter, "{} {:?}", self.case, self.nodes)?;
+        write!(formatter, "{}", self.snapshot)
+    }
+}
@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod test {
+    extern crate std;
+
+    use crate::base::Tree;
+    use crate::trace::TraceStep;
+    use crate::{avltree, bstree, rbtree};
+    use std::format;
+    use std::string::ToString;
+    use std::vec;
+    use std::vec::Vec;
+
+    fn cases<T>(steps: &[TraceStep<T>]) -> Vec<&str> {
+        steps.iter().map(|step| step.case.as_str()).collect()
+    }
+
+    #[test]
+    fn test_rbtree_trace() {
+        let mut rb_tree: rbtree::RBTree<i32> = rbtree::RBTree::new();
+        assert_eq!(cases(&rb_tree.insert_traced(10)), ["1 node is root"]);
+        assert_eq!(cases(&rb_tree.insert_traced(5)), ["insert as red leaf", "2 parent is black"]);
+        let steps = rb_tree.insert_traced(15);
+        assert_eq!(cases(&steps), ["insert as red leaf", "2 parent is black"]);
+        // Red uncle: recolor and continue from the grandparent.
+        let steps = rb_tree.insert_traced(1);
+        assert_eq!(cases(&steps), ["insert as red leaf", "3.2.3 uncle is red", "1 node is root"]);
+        assert_eq!(steps[1].nodes, vec![1, 5, 10, 15]);
+        assert_eq!(steps.last().unwrap().snapshot, rb_tree.render());
+        assert_eq!(cases(&rb_tree.insert_traced(1)), ["already exists"]);
+
+        let steps = rb_tree.delete_traced(10);
+        assert_eq!(steps[0].case, "3 node has two children");
+        assert_eq!(steps[0].nodes, vec![10, 5]);
+        assert_eq!(steps.last().unwrap().snapshot, rb_tree.render());
+        assert_eq!(cases(&rb_tree.delete_traced(42)), ["not found"]);
+
+        // Every step names its case and its snapshot already holds the new key.
+        let mut rb_tree: rbtree::RBTree<i32> = rbtree::RBTree::new();
+        for number in 0..64 {
+            for step in rb_tree.insert_traced(number) {
+                assert!(!step.case.is_empty());
+                assert!(step.snapshot.contains(&format!("{}", number)));
+            }
+        }
+        for number in (0..64).step_by(3) {
+            let steps = rb_tree.delete_traced(number);
+            assert!(!steps.is_empty());
+            assert_eq!(steps.last().unwrap().snapshot, rb_tree.render());
+        }
+        assert!(!rb_tree.render().lines().any(|line| line.ends_with(" 0")));
+    }
+
+    #[test]
+    fn test_avltree_trace() {
+        let mut avl_tree: avltree::AVLTree<i32> = avltree::AVLTree::new();
+        assert_eq!(cases(&avl_tree.insert_traced(1)), ["insert as leaf"]);
+        // Every snapshot already shows the whole tree, so no final one is added.
+        let steps = avl_tree.insert_traced(2);
+        assert_eq!(cases(&steps), ["insert as leaf"]);
+        assert_eq!(steps[0].snapshot, avl_tree.render());
+        let steps = avl_tree.insert_traced(3);
+        assert_eq!(cases(&steps), ["insert as leaf", "RR left rotation"]);
+        assert_eq!(steps[1].nodes, vec![1, 3]);
+        assert_eq!(steps[1].snapshot, avl_tree.render());
+        assert_eq!(cases(&avl_tree.insert_traced(2)), ["already exists"]);
+
+        let steps = avl_tree.delete_traced(2);
+        assert_eq!(cases(&steps), ["3 node has two children", "1 node is leaf"]);
+        assert_eq!(steps[0].nodes, vec![2, 3]);
+        assert_eq!(steps.last().unwrap().snapshot, avl_tree.render());
+        assert_eq!(cases(&avl_tree.delete_traced(2)), ["not found"]);
+    }
+
+    #[test]
+    fn test_avltree_trace_shows_whole_tree() {
+        let mut avl_tree: avltree::AVLTree<i32> = avltree::AVLTree::new();
+        for number in 1..=8 {
+            avl_tree.insert(number);
+        }
+        // 9 is inserted three levels down and rotates 7, far below the root 4.
+        let steps = avl_tree.insert_traced(9);
+        assert_eq!(cases(&steps), ["insert as leaf", "RR left rotation"]);
+        assert_eq!(steps[1].nodes, vec![7, 9]);
+        for step in steps.iter() {
+            assert!(step.snapshot.starts_with("Root 4\n"));
+            assert!(step.snapshot.contains("L 1"));
+        }
+        assert_eq!(steps[1].snapshot, avl_tree.render());
+
+        let steps = avl_tree.delete_traced(1);
+        assert!(steps.iter().all(|step| step.snapshot.contains("Root 4")));
+        assert_eq!(steps.last().unwrap().snapshot, avl_tree.render());
+    }
+
+    #[test]
+    fn test_default_trace() {
+        let mut bs_tree: bstree::BSTree<i32> = bstree::BSTree::new();
+        let steps = bs_tree.insert_traced(7);
+        assert_eq!(steps, vec![TraceStep { case: "insert".to_string(), nodes: vec![7], snapshot: bs_tree.render() }]);
+        let steps = bs_tree.delete_traced(7);
+        assert_eq!(cases(&steps), ["delete"]);
+        assert_eq!(steps[0].snapshot, "This tree is empty!\n");
+    }
+}
@@ -0,0 +1,323 @@
+use core::fmt::{self, Debug, Display};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::format;
+
+use crate::base::Tree;
+
+/// A small seeded generator (splitmix64), so that a seed always gives the
+/// same keys and workloads, on every platform.
+///
+/// # Examples
+///
+/// ```
+/// use trees::workload::Rng;
+///
+/// let mut first = Rng::new(7);
+/// let mut second = Rng::new(7);
+/// assert_eq!(first.next_u64(), second.next_u64());
+/// assert!(first.below(10) < 10);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut mixed = self.state;
+        mixed = (mixed ^ (mixed >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        mixed = (mixed ^ (mixed >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        mixed ^ (mixed >> 31)
+    }
+
+    /// A number in 0..bound, bound being at least 1.
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    /// Puts the items in a random order (Fisher-Yates).
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for last in (1..items.len()).rev() {
+            items.swap(last, self.below(last + 1));
+        }
+    }
+}
+
+/// The order, and spread, of the keys of `keys`.
+///
+/// Every distribution gives distinct, non-negative keys.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Distribution {
+    /// The keys 0..count in a uniformly random order.
+    Uniform,
+    /// The keys 0..count in increasing order.
+    Ascending,
+    /// The keys 0..count in decreasing order.
+    Descending,
+    /// Runs of about sqrt(count) consecutive keys, going alternately up and down.
+    ZigZag,
+    /// About sqrt(count) dense ranges of consecutive keys far from each other,
+    /// the keys of all ranges coming in a random order.
+    Clustered,
+    /// The smallest and largest keys left, alternately: 0, count - 1, 1, count - 2...
+    /// Every key lands below the previous one, making a plain binary search
+    /// tree a single chain with turns at every level.
+    Adversarial,
+}
+
+impl Distribution {
+    pub const ALL: [Distribution; 6] = [
+        Distribution::Uniform,
+        Distribution::Ascending,
+        Distribution::Descending,
+        Distribution::ZigZag,
+        Distribution::Clustered,
+        Distribution::Adversarial,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Distribution::Uniform => "uniform",
+            Distribution::Ascending => "ascending",
+            Distribution::Descending => "descending",
+            Distribution::ZigZag => "zigzag",
+            Distribution::Clustered => "clustered",
+            Distribution::Adversarial => "adversarial",
+        }
+    }
+
+    pub fn parse(name: &str) -> Result<Distribution, String> {
+        Distribution::ALL.iter().copied().find(|distribution| distribution.name() == name).ok_or_else(|| {
+            let names: Vec<&str> = Distribution::ALL.iter().map(Distribution::name).collect();
+            format!("unknown distribution '{}', expected one of {}", name, names.join(", "))
+        })
+    }
+}
+
+// The length of the runs of ZigZag and of the ranges of Clustered.
+fn run_length(count: usize) -> usize {
+    count.isqrt().max(1)
+}
+
+/// Makes count distinct keys in the order of the distribution.
+///
+/// The same seed always gives the same keys; Ascending, Descending, ZigZag
+/// and Adversarial do not use it.
+///
+/// # Examples
+///
+/// ```
+/// use trees::workload::{self, Distribution};
+///
+/// assert_eq!(workload::keys(Distribution::Adversarial, 5, 0), vec![0, 4, 1, 3, 2]);
+/// assert_eq!(workload::keys(Distribution::ZigZag, 6, 0), vec![0, 1, 3, 2, 4, 5]);
+///
+/// let mut keys = workload::keys(Distribution::Uniform, 100, 42);
+/// assert_eq!(keys, workload::keys(Distribution::Uniform, 100, 42));
+/// keys.sort();
+/// assert_eq!(keys, workload::keys(Distribution::Ascending, 100, 42));
+/// ```
+pub fn keys(distribution: Distribution, count: usize, seed: u64) -> Vec<i32> {
+    let mut rng = Rng::new(seed);
+    let ascending = || (0..count as i32).collect::<Vec<i32>>();
+    match distribution {
+        Distribution::Uniform => {
+            let mut keys = ascending();
+            rng.shuffle(&mut keys);
+            keys
+        }
+        Distribution::Ascending => ascending(),
+        Distribution::Descending => ascending().into_iter().rev().collect(),
+        Distribution::ZigZag => {
+            let mut keys = ascending();
+            for (number, run) in keys.chunks_mut(run_length(count)).enumerate() {
+                if number % 2 == 1 {
+                    run.reverse();
+                }
+            }
+            keys
+        }
+        Distribution::Clustered => {
+            let length = run_length(count);
+            // The gap between two ranges is ten times their length.
+            let mut keys: Vec<i32> = (0..count).map(|index| ((index / length) * length * 10 + index % length) as i32).collect();
+            rng.shuffle(&mut keys);
+            keys
+        }
+        Distribution::Adversarial => {
+            let (mut low, mut high) = (0, count as i32 - 1);
+            let mut keys = Vec::with_capacity(count);
+            while low <= high {
+                keys.push(low);
+                if low != high {
+                    keys.push(high);
+                }
+                low += 1;
+                high -= 1;
+            }
+            keys
+        }
+    }
+}
+
+/// The weights of the operations of a workload.
+///
+/// # Examples
+///
+/// ```
+/// use trees::workload::Mix;
+///
+/// assert_eq!(Mix::parse("50,30,20"), Ok(Mix { insert: 50, delete: 30, lookup: 20 }));
+/// assert!(Mix::parse("0,0,0").is_err());
+/// assert!(Mix::parse("4294967295,1,0").is_err());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Mix {
+    pub insert: u32,
+    pub delete: u32,
+    pub lookup: u32,
+}
+
+impl Mix {
+    /// Parses "INSERT,DELETE,LOOKUP", three weights that are not all zero
+    /// and whose sum fits in a u32.
+    pub fn parse(text: &str) -> Result<Mix, String> {
+        let weights: Vec<u32> = text
+            .split(',')
+            .map(|weight| weight.trim().parse::<u32>().map_err(|_| format!("'{}' is not a valid weight", weight)))
+            .collect::<Result<_, _>>()?;
+        match weights[..] {
+            [0, 0, 0] => Err("the weights of the mix cannot all be zero".to_string()),
+            [insert, delete, lookup] if insert.checked_add(delete).and_then(|sum| sum.checked_add(lookup)).is_none() => {
+                Err(format!("the weights of the mix '{}' add up to more than {}", text, u32::MAX))
+            }
+            [insert, delete, lookup] => Ok(Mix { insert, delete, lookup }),
+            _ => Err(format!("the mix '{}' needs three weights: insert,delete,lookup", text)),
+        }
+    }
+}
+
+impl Default for Mix {
+    /// Half inserts, a quarter of deletes and a quarter of lookups.
+    fn default() -> Self {
+        Mix { insert: 50, delete: 25, lookup: 25 }
+    }
+}
+
+/// One step of a workload.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Operation<T> {
+    Insert(T),
+    Delete(T),
+    Lookup(T),
+}
+
+/// Written as a line of the script mode: `insert 5`, `delete 5` or `contains 5`.
+impl<T: Display> Display for Operation<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operation::Insert(key) => write!(formatter, "insert {}", key),
+            Operation::Delete(key) => write!(formatter, "delete {}", key),
+            Operation::Lookup(key) => write!(formatter, "contains {}", key),
+        }
+    }
+}
+
+/// What `Workload::replay` did.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ReplayCounts {
+    pub inserts: usize,
+    pub deletes: usize,
+    pub lookups: usize,
+    /// The lookups that found their key.
+    pub hits: usize,
+}
+
+/// A sequence of inserts, deletes and lookups that can be replayed against any tree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Workload<T> {
+    pub operations: Vec<Operation<T>>,
+}
+
+impl Workload<i32> {
+    /// Makes count operations drawn with the weights of mix.
+    ///
+    /// Inserts take their keys in the order of the distribution, deletes
+    /// remove a random key inserted before and not deleted yet, lookups ask
+    /// for a random key of the distribution, so they find it about as often
+    /// as it was inserted. Replayed against an empty tree every insert adds
+    /// a new key and every delete removes one; an operation that cannot
+    /// happen, such as a delete from an empty tree, becomes an insert.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trees::avltree::AVLTree;
+    /// use trees::base::Tree;
+    /// use trees::workload::{Distribution, Mix, Workload};
+    ///
+    /// let workload = Workload::generate(Distribution::Uniform, 1000, Mix::default(), 3);
+    /// let mut tree = AVLTree::new();
+    /// let counts = workload.replay(&mut tree);
+    /// assert_eq!(counts.inserts + counts.deletes + counts.lookups, 1000);
+    /// assert_eq!(tree.count_nodes() as usize, counts.inserts - counts.deletes);
+    /// ```
+    pub fn generate(distribution: Distribution, count: usize, mix: Mix, seed: u64) -> Self {
+        let keys: Vec<i32> = keys(distribution, count, seed);
+        // Another stream than the one of the keys, for the same seed.
+        let mut rng = Rng::new(seed ^ 0x5eed_5eed_5eed_5eed);
+        // Summed in u64, three u32 weights cannot overflow it.
+        let total = u64::from(mix.insert) + u64::from(mix.delete) + u64::from(mix.lookup);
+        let deletes_from = u64::from(mix.insert);
+        let lookups_from = deletes_from + u64::from(mix.delete);
+        let mut next_key = keys.iter().copied();
+        let mut live: Vec<i32> = Vec::new();
+        let mut operations = Vec::with_capacity(count);
+        while operations.len() < count {
+            let draw = rng.next_u64() % total;
+            let operation = if draw >= lookups_from {
+                Operation::Lookup(keys[rng.below(keys.len())])
+            } else if draw >= deletes_from && !live.is_empty() {
+                Operation::Delete(live.swap_remove(rng.below(live.len())))
+            } else {
+                // There are count keys, so they cannot run out.
+                let key = next_key.next().expect("a key for every operation");
+                live.push(key);
+                Operation::Insert(key)
+            };
+            operations.push(operation);
+        }
+        Workload { operations }
+    }
+
+}
+
+impl<T: Ord + Clone + Debug> Workload<T> {
+    /// Applies every operation to the tree, in order.
+    pub fn replay<TR: Tree<T>>(&self, tree: &mut TR) -> ReplayCounts {
+        let mut counts = ReplayCounts::default();
+        for operation in &self.operations {
+            match operation {
+                Operation::Insert(key) => {
+                    tree.insert(key.clone());
+                    counts.inserts += 1;
+                }
+                Operation::Delete(key) => {
+                    tree.delete(key.clone());
+                    counts.deletes += 1;
+                }
+                Operation::Lookup(key) => {
+                    counts.hits += tree.contain(key.clone()) as usize;
+                    counts.lookups += 1;
+                }
+            }
+        }
+        counts
+    }
+}
@@ -0,0 +1,82 @@
+#[cfg(test)]
+mod test {
+    extern crate std;
+
+    use std::collections::BTreeSet;
+    use std::string::ToString;
+    use std::vec::Vec;
+
+    use crate::base::Tree;
+    use crate::workload::{self, Distribution, Mix, Operation, Workload};
+    use crate::{avltree, bstree, rbtree};
+
+    #[test]
+    fn test_distributions() {
+        for distribution in Distribution::ALL {
+            assert_eq!(Distribution::parse(distribution.name()), Ok(distribution));
+            for count in [0, 1, 2, 7, 100] {
+                let keys = workload::keys(distribution, count, 11);
+                assert_eq!(keys.len(), count);
+                let distinct: BTreeSet<i32> = keys.iter().copied().collect();
+                assert_eq!(distinct.len(), count, "{:?} repeats a key", distribution);
+                assert!(keys.iter().all(|&key| key >= 0));
+            }
+        }
+        assert!(Distribution::parse("gaussian").is_err());
+        assert_eq!(workload::keys(Distribution::Descending, 3, 0), [2, 1, 0]);
+        assert_ne!(workload::keys(Distribution::Uniform, 50, 1), workload::keys(Distribution::Uniform, 50, 2));
+
+        let clustered = workload::keys(Distribution::Clustered, 100, 5);
+        assert_eq!(clustered, workload::keys(Distribution::Clustered, 100, 5));
+        assert_eq!(clustered.iter().filter(|&&key| key < 10).count(), 10);
+        assert_eq!(*clustered.iter().max().unwrap(), 909);
+
+        // The adversarial order is the worst case of the plain binary search tree.
+        let mut bs_tree = bstree::BSTree::new();
+        let mut rb_tree = rbtree::RBTree::new();
+        for key in workload::keys(Distribution::Adversarial, 200, 0) {
+            bs_tree.insert(key);
+            rb_tree.insert(key);
+        }
+        assert_eq!(bs_tree.get_height(), 200);
+        assert!(rb_tree.get_height() <= 16);
+    }
+
+    #[test]
+    fn test_workload() {
+        let mix = Mix { insert: 40, delete: 30, lookup: 30 };
+        let workload = Workload::generate(Distribution::Clustered, 2000, mix, 9);
+        assert_eq!(workload, Workload::generate(Distribution::Clustered, 2000, mix, 9));
+        assert_eq!(workload.operations.len(), 2000);
+        assert_eq!(workload.operations[0].to_string().split(' ').next(), Some("insert"));
+
+        // Every delete removes a live key, replaying gives the same keys as a set.
+        let mut expected: BTreeSet<i32> = BTreeSet::new();
+        for operation in &workload.operations {
+            match operation {
+                Operation::Insert(key) => assert!(expected.insert(*key)),
+                Operation::Delete(key) => assert!(expected.remove(key)),
+                Operation::Lookup(_) => {}
+            }
+        }
+        let mut avl_tree = avltree::AVLTree::new();
+        let mut rb_tree = rbtree::RBTree::new();
+        let counts = workload.replay(&mut avl_tree);
+        assert_eq!(counts, workload.replay(&mut rb_tree));
+        assert!(counts.hits > 0 && counts.hits < counts.lookups);
+        let expected: Vec<i32> = expected.into_iter().collect();
+        assert_eq!(avl_tree.range(i32::MIN, i32::MAX), expected);
+        assert_eq!(rb_tree.range(i32::MIN, i32::MAX), expected);
+
+        let only_lookups = Workload::generate(Distribution::Uniform, 10, Mix { insert: 0, delete: 0, lookup: 1 }, 0);
+        assert!(only_lookups.operations.iter().all(|operation| matches!(operation, Operation::Lookup(_))));
+        assert_eq!(Mix::parse("1,2"), Err("the mix '1,2' needs three weights: insert,delete,lookup".to_string()));
+        assert_eq!(
+            Mix::parse("4294967295,1,0"),
+            Err("the weights of the mix '4294967295,1,0' add up to more than 4294967295".to_string())
+        );
+
+        let heavy = Mix { insert: u32::MAX, delete: u32::MAX, lookup: u32::MAX };
+        assert_eq!(Workload::generate(Distribution::Uniform, 100, heavy, 5).operations.len(), 100);
+    }
+}